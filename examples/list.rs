@@ -1,9 +1,17 @@
 extern crate ddc;
 extern crate ddc_macos;
 
+#[cfg(target_os = "macos")]
 use ddc::Ddc;
-use ddc_macos::Monitor;
+#[cfg(target_os = "macos")]
+use ddc_macos::{describe_vcp_value, Monitor};
 
+#[cfg(not(target_os = "macos"))]
+fn main() {
+    println!("Monitors can only be listed on MacOS");
+}
+
+#[cfg(target_os = "macos")]
 fn main() {
     let monitors = Monitor::enumerate().expect("Could not enumerate external monitors");
 
@@ -22,7 +30,7 @@ fn main() {
             println!("\tSerial Number: {}", number);
         }
         if let Ok(input) = monitor.get_vcp_feature(0x60) {
-            println!("\tCurrent input: {:04x}", input.value());
+            println!("\t{}", describe_vcp_value(0x60, &input));
         }
         if let Some(data) = monitor.edid() {
             let mut cursor = std::io::Cursor::new(&data);
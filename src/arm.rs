@@ -56,8 +56,9 @@ pub(crate) fn execute<'a>(
     }
 }
 
-/// Returns an AVService and its DDC I2C address for a given display
-pub(crate) fn get_display_av_service(display: CGDisplay) -> Result<(IOAVService, u16), Error> {
+/// Returns an AVService, its DDC I2C address and the registry entry ID of the display's framebuffer,
+/// identifying its bus, for a given display
+pub(crate) fn get_display_av_service(display: CGDisplay) -> Result<(IOAVService, u16, Option<u64>), Error> {
     if display.is_builtin() {
         return Err(ServiceNotFound);
     }
@@ -75,6 +76,8 @@ pub(crate) fn get_display_av_service(display: CGDisplay) -> Result<(IOAVService,
     while let Some(service) = iter.next() {
         if let Ok(registry_location) = get_service_registry_entry_path((&service).into()) {
             if registry_location == location {
+                // The AV services of all displays behind this framebuffer share its DCP and bus
+                let bus = service.registry_entry_id().ok();
                 while let Some(service) = iter.next() {
                     if get_service_registry_entry_name((&service).into())? == "DCPAVServiceProxy" {
                         let av_service = unsafe { IOAVServiceCreateWithService(kCFAllocatorDefault, (&service).into()) };
@@ -89,7 +92,7 @@ pub(crate) fn get_display_av_service(display: CGDisplay) -> Result<(IOAVService,
                         if !loc_ref.is_null() {
                             let loc_ref = unsafe { CFType::wrap_under_create_rule(loc_ref) };
                             if !av_service.is_null() && (loc_ref == external_location) {
-                                return Ok((av_service, i2c_address(service)));
+                                return Ok((av_service, i2c_address(service), bus));
                             }
                         }
                     }
@@ -0,0 +1,501 @@
+#![deny(missing_docs)]
+
+use crate::controls::FeatureValue;
+use crate::error::Error;
+use crate::identity::{BusId, Connection, MonitorIdentity};
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+#[cfg(target_os = "macos")]
+use ddc::{Ddc, DdcHost};
+use ddc::{FeatureCode, VcpValue};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A display as listed by a [DisplayBackend]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayDescription {
+    /// Identity of the monitor
+    pub identity: MonitorIdentity,
+    /// Human-readable description, such as the product name
+    pub description: String,
+    /// Serial number string, if the monitor reports one
+    pub serial_number: Option<String>,
+    /// How the monitor is connected, `None` for displays that are not real monitors
+    pub connection: Option<Connection>,
+}
+
+/// A display along with its position and persistent ID, as listed to users
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListedDisplay {
+    /// Position in the list, starting at 1
+    pub index: usize,
+    /// Persistent ID, see [MonitorSelector::Id]
+    pub id: String,
+    /// The monitor
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub display: DisplayDescription,
+}
+
+impl ListedDisplay {
+    /// Lists displays in the order of [DisplayBackend::displays]
+    pub fn list(displays: Vec<DisplayDescription>) -> Vec<ListedDisplay> {
+        displays
+            .into_iter()
+            .enumerate()
+            .map(|(index, display)| ListedDisplay {
+                index: index + 1,
+                id: display.identity.to_string(),
+                display,
+            })
+            .collect()
+    }
+}
+
+/// Selects a monitor by its position, persistent ID, serial number or name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorSelector {
+    /// Position in the monitor list, starting at 1
+    Index(usize),
+    /// Persistent ID of the monitor, see [MonitorIdentity]
+    Id(MonitorIdentity),
+    /// Serial number string
+    Serial(String),
+    /// Part of the product name, ignoring case
+    Name(String),
+}
+
+impl MonitorSelector {
+    /// Returns `true` if the display at `index` (starting at 0) is selected
+    pub fn matches(&self, index: usize, display: &DisplayDescription) -> bool {
+        match self {
+            MonitorSelector::Index(position) => *position == index + 1,
+            MonitorSelector::Id(identity) => {
+                identity.same_model(&display.identity) && identity.serial == display.identity.serial
+            }
+            MonitorSelector::Serial(serial) => display.serial_number.as_deref() == Some(serial.as_str()),
+            MonitorSelector::Name(name) => display
+                .identity
+                .name
+                .as_deref()
+                .unwrap_or(&display.description)
+                .to_lowercase()
+                .contains(&name.to_lowercase()),
+        }
+    }
+}
+
+impl FromStr for MonitorSelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(serial) = s.strip_prefix("serial=") {
+            Ok(MonitorSelector::Serial(serial.into()))
+        } else if let Some(name) = s.strip_prefix("name=") {
+            Ok(MonitorSelector::Name(name.into()))
+        } else if let Ok(index) = s.parse() {
+            Ok(MonitorSelector::Index(index))
+        } else if let Ok(identity) = s.parse() {
+            Ok(MonitorSelector::Id(identity))
+        } else {
+            Ok(MonitorSelector::Name(s.into()))
+        }
+    }
+}
+
+impl fmt::Display for MonitorSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorSelector::Index(index) => write!(f, "index {}", index),
+            MonitorSelector::Id(identity) => write!(f, "ID {}", identity),
+            MonitorSelector::Serial(serial) => write!(f, "serial number {:?}", serial),
+            MonitorSelector::Name(name) => write!(f, "name {:?}", name),
+        }
+    }
+}
+
+/// Picks the display selected by `selector` from `displays`, or the first one without a selector.
+/// A selector matching several displays is rejected.
+pub fn select_display(displays: &[DisplayDescription], selector: Option<&MonitorSelector>) -> Result<usize, Error> {
+    let Some(selector) = selector else {
+        if displays.is_empty() {
+            return Err(Error::MonitorNotFound(
+                "the default selection: no monitors found".into(),
+            ));
+        }
+        return Ok(0);
+    };
+    let matching: Vec<usize> = (0..displays.len())
+        .filter(|index| selector.matches(*index, &displays[*index]))
+        .collect();
+    match matching.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(Error::MonitorNotFound(selector.to_string())),
+        _ => Err(Error::AmbiguousMonitor(selector.to_string())),
+    }
+}
+
+/// Access to a set of displays by their position in [DisplayBackend::displays], so that tools built
+/// on this crate can be driven by something other than real monitors, e.g. in tests.
+pub trait DisplayBackend {
+    /// Lists the connected displays
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error>;
+
+    /// Reads a VCP feature of a display
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error>;
+
+    /// Writes a VCP feature of a display
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error>;
+
+    /// Reads the raw capabilities string of a display
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error>;
+
+    /// Reads the EDID of a display, if available
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error>;
+}
+
+/// Groups display indices by the [bus](Connection::bus) the displays are on, in order of first
+/// appearance. Displays within a group keep their relative order; displays without a connection are
+/// grouped with those on [BusId::UNKNOWN].
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn partition_by_bus(displays: &[DisplayDescription]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_bus: BTreeMap<BusId, usize> = BTreeMap::new();
+    for (index, display) in displays.iter().enumerate() {
+        let bus = display.connection.map_or(BusId::UNKNOWN, |connection| connection.bus);
+        let group = *group_of_bus.entry(bus).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+    groups
+}
+
+/// [DisplayBackend] driving real monitors
+#[cfg(target_os = "macos")]
+#[derive(Debug, Default)]
+pub struct MonitorBackend {
+    monitors: Vec<Monitor>,
+}
+
+#[cfg(target_os = "macos")]
+impl MonitorBackend {
+    /// Creates a backend for the given monitors
+    pub fn new(monitors: Vec<Monitor>) -> Self {
+        MonitorBackend { monitors }
+    }
+
+    /// Creates a backend for all connected monitors, see [Monitor::enumerate]
+    pub fn enumerate() -> Result<Self, Error> {
+        Ok(MonitorBackend::new(Monitor::enumerate()?))
+    }
+
+    /// Driven monitors
+    pub fn monitors_mut(&mut self) -> &mut [Monitor] {
+        &mut self.monitors
+    }
+
+    /// Splits this backend into one backend per bus, in the order the buses first appear. Monitors
+    /// sharing a bus keep their relative order. Backends of different buses can be driven
+    /// concurrently.
+    pub fn into_buses(self) -> Vec<MonitorBackend> {
+        let displays: Vec<DisplayDescription> = self.monitors.iter().map(Monitor::display_description).collect();
+        let mut monitors: Vec<Option<Monitor>> = self.monitors.into_iter().map(Some).collect();
+        partition_by_bus(&displays)
+            .into_iter()
+            .map(|group| MonitorBackend::new(group.into_iter().filter_map(|index| monitors[index].take()).collect()))
+            .collect()
+    }
+
+    fn monitor(&mut self, display: usize) -> Result<&mut Monitor, Error> {
+        self.monitors
+            .get_mut(display)
+            .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))
+    }
+}
+
+/// Description for display backends
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Describes this monitor as [MonitorBackend] lists it, e.g. to match a [MonitorSelector]
+    pub fn display_description(&self) -> DisplayDescription {
+        DisplayDescription {
+            identity: self.identity(),
+            description: self.description(),
+            serial_number: self.serial_number(),
+            connection: Some(self.connection()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl From<Vec<Monitor>> for MonitorBackend {
+    fn from(monitors: Vec<Monitor>) -> Self {
+        MonitorBackend::new(monitors)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl DisplayBackend for MonitorBackend {
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error> {
+        Ok(self.monitors.iter().map(Monitor::display_description).collect())
+    }
+
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error> {
+        let monitor = self.monitor(display)?;
+        monitor.sleep();
+        monitor.get_vcp_feature(code)
+    }
+
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error> {
+        let monitor = self.monitor(display)?;
+        monitor.sleep();
+        monitor.set_vcp_feature(code, value)
+    }
+
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error> {
+        self.monitor(display)?.capabilities_string()
+    }
+
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.monitor(display)?.edid())
+    }
+}
+
+/// A single [Monitor] as a [DisplayBackend] listing just that one, to run code written for backends
+#[cfg(target_os = "macos")]
+pub(crate) struct SingleMonitor<'a>(pub(crate) &'a mut Monitor);
+
+#[cfg(target_os = "macos")]
+impl SingleMonitor<'_> {
+    fn monitor(&mut self, display: usize) -> Result<&mut Monitor, Error> {
+        match display {
+            0 => Ok(self.0),
+            _ => Err(Error::MonitorNotFound(format!("display {}", display + 1))),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl DisplayBackend for SingleMonitor<'_> {
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error> {
+        Ok(vec![self.0.display_description()])
+    }
+
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error> {
+        let monitor = self.monitor(display)?;
+        monitor.sleep();
+        monitor.get_vcp_feature(code)
+    }
+
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error> {
+        let monitor = self.monitor(display)?;
+        monitor.sleep();
+        monitor.set_vcp_feature(code, value)
+    }
+
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error> {
+        self.monitor(display)?.capabilities_string()
+    }
+
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.monitor(display)?.edid())
+    }
+}
+
+/// A monitor simulated by a [SimulatedBackend]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedDisplay {
+    /// How the display is listed
+    pub description: DisplayDescription,
+    /// Supported VCP features and their values
+    pub values: BTreeMap<FeatureCode, FeatureValue>,
+    /// Raw capabilities string
+    pub capabilities: String,
+    /// EDID, if the display has one
+    pub edid: Option<Vec<u8>>,
+}
+
+impl SimulatedDisplay {
+    /// Creates a display without any VCP features
+    pub fn new(description: DisplayDescription) -> Self {
+        SimulatedDisplay {
+            description,
+            values: BTreeMap::new(),
+            capabilities: String::new(),
+            edid: None,
+        }
+    }
+
+    /// Adds a supported VCP feature with its current and maximum values
+    pub fn with_value(mut self, code: FeatureCode, value: u16, maximum: u16) -> Self {
+        let feature = FeatureValue {
+            code,
+            ty: 0,
+            value,
+            maximum,
+        };
+        self.values.insert(code, feature);
+        self
+    }
+
+    /// Sets the raw capabilities string
+    pub fn with_capabilities(mut self, capabilities: &str) -> Self {
+        self.capabilities = capabilities.into();
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct SimulatedState {
+    displays: Vec<SimulatedDisplay>,
+    writes: Vec<(usize, FeatureCode, u16)>,
+}
+
+/// [DisplayBackend] simulating monitors in memory: reads return the stored values, writes update
+/// them and codes a display does not support fail as they would on a real monitor.
+///
+/// Clones share the simulated monitors, so one clone can be kept to inspect or change them, e.g.
+/// to simulate a change made with a monitor's own buttons, while another one is driven.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedBackend {
+    state: Arc<Mutex<SimulatedState>>,
+}
+
+impl SimulatedBackend {
+    /// Creates a backend simulating the given displays
+    pub fn new(displays: Vec<SimulatedDisplay>) -> Self {
+        SimulatedBackend {
+            state: Arc::new(Mutex::new(SimulatedState {
+                displays,
+                writes: Vec::new(),
+            })),
+        }
+    }
+
+    /// Changes a value without recording it as a write, as if it was changed on the monitor itself
+    pub fn set_value(&self, display: usize, code: FeatureCode, value: u16) {
+        if let Some(feature) = self.state().displays[display].values.get_mut(&code) {
+            feature.value = value;
+        }
+    }
+
+    /// Current value of a VCP feature
+    pub fn value(&self, display: usize, code: FeatureCode) -> Option<u16> {
+        self.state().displays[display]
+            .values
+            .get(&code)
+            .map(|feature| feature.value)
+    }
+
+    /// Writes made through [DisplayBackend::set_vcp_feature] so far, as display, code and value
+    pub fn writes(&self) -> Vec<(usize, FeatureCode, u16)> {
+        self.state().writes.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, SimulatedState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn feature(state: &mut SimulatedState, display: usize, code: FeatureCode) -> Result<&mut FeatureValue, Error> {
+        state
+            .displays
+            .get_mut(display)
+            .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))?
+            .values
+            .get_mut(&code)
+            .ok_or(Error::UnsupportedFeature { code })
+    }
+}
+
+impl DisplayBackend for SimulatedBackend {
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error> {
+        Ok(self
+            .state()
+            .displays
+            .iter()
+            .map(|display| display.description.clone())
+            .collect())
+    }
+
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error> {
+        Ok(Self::feature(&mut self.state(), display, code)?.vcp_value())
+    }
+
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error> {
+        let mut state = self.state();
+        Self::feature(&mut state, display, code)?.value = value;
+        state.writes.push((display, code, value));
+        Ok(())
+    }
+
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error> {
+        let state = self.state();
+        let display = state
+            .displays
+            .get(display)
+            .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))?;
+        Ok(display.capabilities.as_bytes().to_vec())
+    }
+
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error> {
+        let state = self.state();
+        let display = state
+            .displays
+            .get(display)
+            .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))?;
+        Ok(display.edid.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Transport;
+
+    /// Backend simulating a display on each of the given buses, `None` for displays without a connection
+    fn backend(buses: &[Option<u64>]) -> SimulatedBackend {
+        let displays = buses
+            .iter()
+            .enumerate()
+            .map(|(index, bus)| {
+                SimulatedDisplay::new(DisplayDescription {
+                    identity: MonitorIdentity {
+                        vendor: 0x10ac,
+                        model: 0xa0c4,
+                        serial: index as u32,
+                        name: None,
+                    },
+                    description: format!("display {}", index + 1),
+                    serial_number: None,
+                    connection: bus.map(|bus| Connection {
+                        display_id: index as u32,
+                        transport: Transport::Intel,
+                        i2c_address: 0x37,
+                        bus: BusId(bus),
+                    }),
+                })
+            })
+            .collect();
+        SimulatedBackend::new(displays)
+    }
+
+    #[test]
+    fn test_partition_by_bus() {
+        let displays = backend(&[Some(7), Some(3), Some(7), Some(0), Some(3), None])
+            .displays()
+            .unwrap();
+        assert_eq!(partition_by_bus(&displays), vec![vec![0, 2], vec![1, 4], vec![3, 5]]);
+    }
+
+    #[test]
+    fn test_partition_distinct_buses() {
+        let displays = backend(&[Some(1), Some(2), Some(3)]).displays().unwrap();
+        assert_eq!(partition_by_bus(&displays), vec![vec![0], vec![1], vec![2]]);
+        assert!(partition_by_bus(&[]).is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+extern crate ddc_macos;
+extern crate serde_json;
+
+#[cfg(target_os = "macos")]
+use ddc_macos::MonitorBackend;
+#[cfg(not(target_os = "macos"))]
+use ddc_macos::SimulatedBackend;
+use ddc_macos::{Cli, Error, JsonError, CLI_USAGE};
+use std::io;
+use std::process::ExitCode;
+
+/// The connected monitors
+#[cfg(target_os = "macos")]
+fn backend() -> Result<MonitorBackend, Error> {
+    MonitorBackend::enumerate()
+}
+
+/// Monitors can only be driven on MacOS
+#[cfg(not(target_os = "macos"))]
+fn backend() -> Result<SimulatedBackend, Error> {
+    Ok(SimulatedBackend::default())
+}
+
+/// Prints an error as JSON on the standard output, where the result would have been
+fn print_json_error(error: &Error) {
+    println!(
+        "{}",
+        serde_json::to_string(&JsonError::from(error)).expect("error serializes")
+    );
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match Cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(error) if args.iter().any(|arg| arg == "--json") => {
+            print_json_error(&error);
+            return ExitCode::from(2);
+        }
+        Err(error) => {
+            eprintln!("ddc-macos: {}\n\n{}", error, CLI_USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let result = backend().and_then(|mut backend| cli.run(&mut backend, &mut io::stdout().lock()));
+    let Err(error) = result else {
+        return ExitCode::SUCCESS;
+    };
+    if cli.json {
+        print_json_error(&error);
+    } else {
+        eprintln!("ddc-macos: {}", error);
+    }
+    match error {
+        Error::InvalidArgument(_) => ExitCode::from(2),
+        _ => ExitCode::FAILURE,
+    }
+}
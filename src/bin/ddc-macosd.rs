@@ -0,0 +1,58 @@
+extern crate ddc_macos;
+
+#[cfg(target_os = "macos")]
+use ddc_macos::Monitor;
+#[cfg(not(target_os = "macos"))]
+use ddc_macos::SimulatedBackend;
+use ddc_macos::{default_socket_path, Daemon, DaemonOptions, DaemonServer, Error};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const USAGE: &str = "Usage: ddc-macosd [--socket <path>]
+
+Serves the connected monitors to local clients as JSON-RPC 2.0 over a Unix domain socket, one
+message per line. The socket defaults to ddc-macos.sock in the temporary directory.";
+
+/// Daemon driving the connected monitors
+#[cfg(target_os = "macos")]
+fn daemon() -> Result<Daemon, Error> {
+    Daemon::with_monitors(Monitor::enumerate()?, DaemonOptions::default())
+}
+
+/// Monitors can only be driven on MacOS
+#[cfg(not(target_os = "macos"))]
+fn daemon() -> Result<Daemon, Error> {
+    Daemon::new(Vec::<SimulatedBackend>::new(), DaemonOptions::default())
+}
+
+fn main() -> ExitCode {
+    let mut socket = default_socket_path();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("-s" | "--socket", Some(path)) => socket = PathBuf::from(path),
+            ("-h" | "--help", _) => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("ddc-macosd: unexpected argument {:?}\n\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let result = daemon()
+        .and_then(|daemon| DaemonServer::bind(Arc::new(daemon), &socket))
+        .and_then(|server| {
+            eprintln!("ddc-macosd: listening on {}", server.path().display());
+            server.serve()
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("ddc-macosd: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
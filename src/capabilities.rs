@@ -0,0 +1,357 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+use ddc::commands::CapabilitiesRequest;
+use ddc::{DdcCommand, ErrorCode, FeatureCode};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of attempts made to read a single capabilities string fragment before giving up
+pub const CAPABILITIES_FRAGMENT_RETRIES: usize = 3;
+
+/// Longest capabilities string read before giving up on a monitor that keeps sending fragments.
+/// Real capabilities strings are well below 1 KiB.
+pub const CAPABILITIES_MAX_LENGTH: usize = 8192;
+
+/// Display protocol class reported by the `prot` capability tag
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Protocol {
+    /// A monitor (`prot(monitor)`)
+    Monitor,
+    /// A generic display (`prot(display)`)
+    Display,
+    /// Any other protocol string
+    Unknown(String),
+}
+
+impl From<&str> for Protocol {
+    fn from(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "monitor" => Protocol::Monitor,
+            "display" => Protocol::Display,
+            _ => Protocol::Unknown(value.trim().into()),
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Monitor => write!(f, "monitor"),
+            Protocol::Display => write!(f, "display"),
+            Protocol::Unknown(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Display technology reported by the `type` capability tag
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayType {
+    /// Cathode ray tube display
+    Crt,
+    /// Liquid crystal display
+    Lcd,
+    /// LED display
+    Led,
+    /// Any other display type string
+    Unknown(String),
+}
+
+impl From<&str> for DisplayType {
+    fn from(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "crt" => DisplayType::Crt,
+            "lcd" => DisplayType::Lcd,
+            "led" => DisplayType::Led,
+            _ => DisplayType::Unknown(value.trim().into()),
+        }
+    }
+}
+
+impl fmt::Display for DisplayType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayType::Crt => write!(f, "CRT"),
+            DisplayType::Lcd => write!(f, "LCD"),
+            DisplayType::Led => write!(f, "LED"),
+            DisplayType::Unknown(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// MCCS specification version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MccsVersion {
+    /// Major version number
+    pub major: u8,
+    /// Minor version number
+    pub minor: u8,
+}
+
+impl MccsVersion {
+    /// MCCS 2.0
+    pub const V2_0: MccsVersion = MccsVersion::new(2, 0);
+    /// MCCS 2.1
+    pub const V2_1: MccsVersion = MccsVersion::new(2, 1);
+    /// MCCS 2.2
+    pub const V2_2: MccsVersion = MccsVersion::new(2, 2);
+    /// MCCS 3.0
+    pub const V3_0: MccsVersion = MccsVersion::new(3, 0);
+
+    /// Create a new version from its major and minor numbers
+    pub const fn new(major: u8, minor: u8) -> Self {
+        MccsVersion { major, minor }
+    }
+}
+
+impl FromStr for MccsVersion {
+    type Err = Error;
+
+    /// Parses versions like `2.1`, `02.01` or `2.2a` (revision letters are ignored)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidCapabilities(format!("invalid MCCS version: {:?}", s));
+        let (major, minor) = s.trim().split_once('.').ok_or_else(invalid)?;
+        let minor = minor.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        Ok(MccsVersion {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for MccsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// A VCP feature advertised in the capabilities string
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VcpCapability {
+    /// Allowed values for non-continuous features. Empty if the monitor did not list any.
+    pub values: Vec<u8>,
+}
+
+/// Parsed MCCS capabilities string of a monitor
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// Protocol class (`prot` tag)
+    pub protocol: Option<Protocol>,
+    /// Display type (`type` tag)
+    pub display_type: Option<DisplayType>,
+    /// Model name (`model` tag)
+    pub model: Option<String>,
+    /// Supported MCCS version (`mccs_ver` tag)
+    pub mccs_version: Option<MccsVersion>,
+    /// Supported DDC/CI command opcodes (`cmds` tag)
+    pub commands: Vec<u8>,
+    /// Supported VCP features and their allowed values (`vcp` tag)
+    pub vcp_features: BTreeMap<FeatureCode, VcpCapability>,
+    /// All other tags, such as `mswhql`, `asset_eep` or vendor extensions, with their raw values
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl Capabilities {
+    /// Parse a raw capabilities string as returned by the monitor.
+    ///
+    /// The parser is lenient: it ignores trailing garbage and NUL bytes, tolerates missing closing
+    /// parentheses and hex values without separating spaces, and keeps unrecognized tags as
+    /// [Capabilities::extensions]. It only fails if no tags could be found at all.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let text = String::from_utf8_lossy(data);
+        let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        let mut tags = parse_tags(text);
+        // The whole string is normally enclosed in parentheses, which appears as a single nameless tag
+        if let [(name, value)] = tags.as_slice() {
+            if name.is_empty() {
+                tags = parse_tags(value);
+            }
+        }
+        if tags.iter().all(|(name, _)| name.is_empty()) {
+            return Err(Error::InvalidCapabilities(format!("no tags found in {:?}", text)));
+        }
+
+        let mut caps = Capabilities::default();
+        for (name, value) in tags {
+            match name.to_ascii_lowercase().as_str() {
+                "" => {}
+                "prot" => caps.protocol = Some(value.into()),
+                "type" => caps.display_type = Some(value.into()),
+                "model" => caps.model = Some(value.trim().into()),
+                "cmds" => caps.commands.extend(parse_hex_list(value)),
+                "vcp" => caps.vcp_features.extend(parse_vcp_list(value)),
+                "mccs_ver" => caps.mccs_version = value.parse().ok(),
+                _ => {
+                    caps.extensions.insert(name.into(), value.into());
+                }
+            }
+        }
+        Ok(caps)
+    }
+
+    /// Returns `true` if the monitor advertises support for the VCP feature `code`
+    pub fn supports_vcp(&self, code: FeatureCode) -> bool {
+        self.vcp_features.contains_key(&code)
+    }
+
+    /// Returns the allowed values for the VCP feature `code`, if the monitor listed any
+    pub fn vcp_values(&self, code: FeatureCode) -> Option<&[u8]> {
+        self.vcp_features
+            .get(&code)
+            .map(|feature| feature.values.as_slice())
+            .filter(|values| !values.is_empty())
+    }
+
+    /// Checks that `value` is one of the values the monitor lists for the VCP feature `code`.
+    /// Features without a list of allowed values accept any value.
+    pub fn check_value(&self, code: FeatureCode, value: u16) -> Result<(), Error> {
+        match self.vcp_values(code) {
+            Some(values) if !values.iter().any(|allowed| u16::from(*allowed) == value) => {
+                Err(Error::UnsupportedValue { code, value })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the monitor advertises support for the DDC/CI command `opcode`
+    pub fn supports_command(&self, opcode: u8) -> bool {
+        self.commands.contains(&opcode)
+    }
+}
+
+impl FromStr for Capabilities {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Capabilities::parse(s.as_bytes())
+    }
+}
+
+/// Reads the raw capabilities string, one Capabilities Request fragment at a time.
+///
+/// Every fragment is retried up to [CAPABILITIES_FRAGMENT_RETRIES] times if the transfer fails or
+/// the monitor replies with the wrong offset. A trailing NUL terminator is removed. Reading fails
+/// once the string exceeds [CAPABILITIES_MAX_LENGTH] bytes.
+pub fn read_capabilities_string<D>(ddc: &mut D) -> Result<Vec<u8>, D::Error>
+where
+    D: DdcCommand,
+    D::Error: From<ErrorCode>,
+{
+    let mut string = Vec::new();
+    loop {
+        let offset = string.len() as u16;
+        let mut attempt = 0;
+        let fragment = loop {
+            attempt += 1;
+            ddc.sleep();
+            let result = ddc.execute(CapabilitiesRequest::new(offset)).and_then(|reply| {
+                if reply.offset == offset {
+                    Ok(reply.data)
+                } else {
+                    Err(ErrorCode::InvalidOffset.into())
+                }
+            });
+            match result {
+                Ok(data) => break data,
+                Err(error) if attempt >= CAPABILITIES_FRAGMENT_RETRIES => return Err(error),
+                Err(_) => continue,
+            }
+        };
+        if fragment.is_empty() {
+            break;
+        }
+        string.extend_from_slice(&fragment);
+        if string.len() > CAPABILITIES_MAX_LENGTH {
+            return Err(
+                ErrorCode::Invalid(format!("capabilities string exceeds {} bytes", CAPABILITIES_MAX_LENGTH)).into(),
+            );
+        }
+    }
+    while string.last() == Some(&0) {
+        string.pop();
+    }
+    Ok(string)
+}
+
+/// Splits `input` into a list of `name(value)` tags. Parentheses inside values are balanced;
+/// a missing closing parenthesis extends the value to the end of the input.
+fn parse_tags(input: &str) -> Vec<(&str, &str)> {
+    let mut tags = Vec::new();
+    let mut rest = input;
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim();
+        let value_start = open + 1;
+        let mut depth = 1;
+        let mut value_end = rest.len();
+        for (index, c) in rest[value_start..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                value_end = value_start + index;
+                break;
+            }
+        }
+        tags.push((name, &rest[value_start..value_end]));
+        rest = rest.get(value_end + 1..).unwrap_or("");
+    }
+    tags
+}
+
+/// Parses a list of hex bytes, either space separated (`01 02 0C`) or run together (`01020C`).
+/// Anything that is not a hex digit is ignored.
+fn parse_hex_list(input: &str) -> Vec<u8> {
+    input
+        .split(|c: char| !c.is_ascii_hexdigit())
+        .filter(|token| !token.is_empty())
+        .flat_map(|token| {
+            // A lone digit is a single value, longer tokens are pairs of digits run together
+            let chunk_size = if token.len() == 1 { 1 } else { 2 };
+            token
+                .as_bytes()
+                .chunks(chunk_size)
+                .filter_map(|chunk| u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Parses the contents of the `vcp` tag: feature codes, each optionally followed by a
+/// parenthesized list of allowed values.
+fn parse_vcp_list(input: &str) -> Vec<(FeatureCode, VcpCapability)> {
+    let mut features: Vec<(FeatureCode, VcpCapability)> = Vec::new();
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        if c == '(' {
+            let (values, remaining) = match parse_tags(rest).first() {
+                Some((_, values)) => (*values, &rest[(values.len() + 2).min(rest.len())..]),
+                None => (&rest[1..], ""),
+            };
+            if let Some((_, feature)) = features.last_mut() {
+                feature.values.extend(parse_hex_list(values));
+            }
+            rest = remaining;
+        } else if c.is_ascii_hexdigit() {
+            let digits = rest.chars().take(2).take_while(char::is_ascii_hexdigit).count();
+            if let Ok(code) = u8::from_str_radix(&rest[..digits], 16) {
+                features.push((code, VcpCapability::default()));
+            }
+            rest = &rest[digits..];
+        } else {
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    features
+}
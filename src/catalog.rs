@@ -0,0 +1,577 @@
+#![deny(missing_docs)]
+
+use crate::capabilities::MccsVersion;
+use crate::error::Error;
+use ddc::{FeatureCode, VcpValue};
+use std::fmt;
+use Access::{ReadOnly, ReadWrite, WriteOnly};
+
+/// VCP code of the MCCS version
+pub const VCP_MCCS_VERSION: FeatureCode = 0xdf;
+
+/// How values of a VCP feature are interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VcpFeatureType {
+    /// A value in the range from zero to the reported maximum
+    Continuous,
+    /// One of a set of enumerated values
+    NonContinuous,
+    /// Binary data accessed with table read and table write commands
+    Table,
+}
+
+/// Read / write access allowed for a VCP feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+    /// The feature can only be read
+    ReadOnly,
+    /// The feature can only be written
+    WriteOnly,
+    /// The feature can be read and written
+    ReadWrite,
+}
+
+/// Description of a VCP feature, either from the MCCS specification or a vendor extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcpFeature {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Human-readable feature name, as used in the MCCS specification
+    pub name: &'static str,
+    /// How values of this feature are interpreted
+    pub ty: VcpFeatureType,
+    /// Allowed access to this feature
+    pub access: Access,
+    /// Meanings of enumerated values of non-continuous features
+    pub values: &'static [(u8, &'static str)],
+}
+
+impl VcpFeature {
+    /// Describes a continuous feature
+    pub const fn continuous(code: FeatureCode, name: &'static str, access: Access) -> Self {
+        VcpFeature {
+            code,
+            name,
+            ty: VcpFeatureType::Continuous,
+            access,
+            values: &[],
+        }
+    }
+
+    /// Describes a non-continuous feature with the meanings of its values
+    pub const fn non_continuous(
+        code: FeatureCode,
+        name: &'static str,
+        access: Access,
+        values: &'static [(u8, &'static str)],
+    ) -> Self {
+        VcpFeature {
+            code,
+            name,
+            ty: VcpFeatureType::NonContinuous,
+            access,
+            values,
+        }
+    }
+
+    /// Describes a table feature
+    pub const fn table(code: FeatureCode, name: &'static str, access: Access) -> Self {
+        VcpFeature {
+            code,
+            name,
+            ty: VcpFeatureType::Table,
+            access,
+            values: &[],
+        }
+    }
+
+    /// Returns `true` if the feature value can be read from the monitor
+    pub fn is_readable(&self) -> bool {
+        self.access != Access::WriteOnly
+    }
+
+    /// Returns `true` if the feature value can be written to the monitor
+    pub fn is_writable(&self) -> bool {
+        self.access != Access::ReadOnly
+    }
+
+    /// Returns the meaning of an enumerated value, as encoded in the low byte of the value
+    pub fn value_name(&self, value: u16) -> Option<&'static str> {
+        let value = value as u8;
+        self.values.iter().find(|(v, _)| *v == value).map(|(_, name)| *name)
+    }
+
+    /// Returns the enumerated value with the given name, see [names_match]
+    pub fn value_by_name(&self, name: &str) -> Option<u8> {
+        self.values
+            .iter()
+            .find(|(_, value_name)| names_match(value_name, name))
+            .map(|(value, _)| *value)
+    }
+
+    /// Formats a value read from the monitor in a human-readable way: continuous values are shown
+    /// along with their maximum, enumerated values by their name when known.
+    pub fn format_value(&self, value: &VcpValue) -> String {
+        match self.ty {
+            VcpFeatureType::Continuous => format!("{} / {}", value.value(), value.maximum()),
+            VcpFeatureType::NonContinuous => match self.value_name(value.value()) {
+                Some(name) => name.to_string(),
+                None => format!("0x{:02x}", value.value()),
+            },
+            VcpFeatureType::Table => format!("0x{:04x}", value.value()),
+        }
+    }
+}
+
+impl fmt::Display for VcpFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Looks up a standard MCCS VCP feature by its code
+pub fn vcp_feature(code: FeatureCode) -> Option<&'static VcpFeature> {
+    VCP_FEATURES
+        .binary_search_by_key(&code, |feature| feature.code)
+        .ok()
+        .map(|index| &VCP_FEATURES[index])
+}
+
+/// Compares feature or value names ignoring case, spaces, dashes and underscores, so that e.g.
+/// `input-source` matches `Input Source`
+pub fn names_match(name: &str, other: &str) -> bool {
+    let normalized = |name: &str| {
+        name.chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    normalized(name) == normalized(other)
+}
+
+/// Looks up a standard MCCS VCP feature by its name, see [names_match]
+pub fn vcp_feature_by_name(name: &str) -> Option<&'static VcpFeature> {
+    VCP_FEATURES.iter().find(|feature| names_match(feature.name, name))
+}
+
+/// Parses a VCP feature code given as hex, with or without a `0x` prefix, or as a feature name
+pub fn parse_feature_code(s: &str) -> Result<FeatureCode, Error> {
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    FeatureCode::from_str_radix(hex, 16)
+        .ok()
+        .or_else(|| vcp_feature_by_name(s).map(|feature| feature.code))
+        .ok_or_else(|| Error::InvalidArgument(format!("unknown VCP feature {:?}", s)))
+}
+
+/// Looks up a VCP feature as defined by the MCCS `version` the monitor implements. Features whose
+/// meaning changed between versions are described as in that version; without a version, features
+/// are described as in MCCS 2.2a.
+pub fn vcp_feature_for_version(code: FeatureCode, version: Option<MccsVersion>) -> Option<&'static VcpFeature> {
+    version
+        .and_then(|version| {
+            VERSIONED_FEATURES
+                .iter()
+                .find(|(since, until, feature)| feature.code == code && *since <= version && version < *until)
+                .map(|(_, _, feature)| feature)
+        })
+        .or_else(|| vcp_feature(code))
+}
+
+/// Normalizes a raw value of a non-continuous VCP feature to its MCCS value, according to the
+/// `version` the monitor implements. MCCS 3.0 only defines the low byte of the color preset (0x14),
+/// input source (0x60) and power mode (0xD6) values and uses the high byte for additional data,
+/// such as the color temperature tolerance of the color preset, which is dropped here.
+pub fn mccs_value(code: FeatureCode, value: u16, version: Option<MccsVersion>) -> u16 {
+    match version {
+        Some(version) if matches!(code, 0x14 | 0x60 | 0xd6) && version >= MccsVersion::V3_0 => value & 0xff,
+        _ => value,
+    }
+}
+
+/// Formats a VCP value read from the monitor as `Name = value`, e.g. `Input Source = DisplayPort-1`.
+/// Codes missing from the catalog are shown by their number.
+pub fn describe_vcp_value(code: FeatureCode, value: &VcpValue) -> String {
+    describe_feature_value(code, vcp_feature(code), value)
+}
+
+/// Formats a VCP value as `Name = value` as described by `feature`, or by its number without one
+pub(crate) fn describe_feature_value(code: FeatureCode, feature: Option<&VcpFeature>, value: &VcpValue) -> String {
+    match feature {
+        Some(feature) => format!("{} = {}", feature.name, feature.format_value(value)),
+        None => format!("VCP 0x{:02x} = 0x{:04x}", code, value.value()),
+    }
+}
+
+const NEW_CONTROL_VALUE: &[(u8, &str)] = &[
+    (0x01, "No new control values"),
+    (0x02, "New control values present"),
+    (0xff, "No user controls present"),
+];
+
+const COLOR_PRESETS: &[(u8, &str)] = &[
+    (0x01, "sRGB"),
+    (0x02, "Display Native"),
+    (0x03, "4000 K"),
+    (0x04, "5000 K"),
+    (0x05, "6500 K"),
+    (0x06, "7500 K"),
+    (0x07, "8200 K"),
+    (0x08, "9300 K"),
+    (0x09, "10000 K"),
+    (0x0a, "11500 K"),
+    (0x0b, "User 1"),
+    (0x0c, "User 2"),
+    (0x0d, "User 3"),
+];
+
+const AUTO_SETUP: &[(u8, &str)] = &[
+    (0x00, "Auto setup not active"),
+    (0x01, "Performing auto setup"),
+    (0x02, "Enable continuous / periodic auto setup"),
+];
+
+const INPUT_SOURCES: &[(u8, &str)] = &[
+    (0x01, "VGA-1"),
+    (0x02, "VGA-2"),
+    (0x03, "DVI-1"),
+    (0x04, "DVI-2"),
+    (0x05, "Composite video 1"),
+    (0x06, "Composite video 2"),
+    (0x07, "S-Video-1"),
+    (0x08, "S-Video-2"),
+    (0x09, "Tuner-1"),
+    (0x0a, "Tuner-2"),
+    (0x0b, "Tuner-3"),
+    (0x0c, "Component video 1"),
+    (0x0d, "Component video 2"),
+    (0x0e, "Component video 3"),
+    (0x0f, "DisplayPort-1"),
+    (0x10, "DisplayPort-2"),
+    (0x11, "HDMI-1"),
+    (0x12, "HDMI-2"),
+];
+
+const SPEAKER_SELECT: &[(u8, &str)] = &[
+    (0x00, "Front L/R"),
+    (0x01, "Side L/R"),
+    (0x02, "Rear L/R"),
+    (0x03, "Center / Subwoofer"),
+];
+
+const AMBIENT_LIGHT_SENSOR: &[(u8, &str)] = &[(0x01, "Disabled"), (0x02, "Enabled")];
+
+const MIRROR: &[(u8, &str)] = &[(0x00, "Normal"), (0x01, "Mirrored")];
+
+const DISPLAY_SCALING: &[(u8, &str)] = &[
+    (0x01, "No scaling"),
+    (0x02, "Max image, no aspect ratio distortion"),
+    (0x03, "Max vertical image, no aspect ratio distortion"),
+    (0x04, "Max horizontal image, no aspect ratio distortion"),
+    (0x05, "Max vertical image with aspect ratio distortion"),
+    (0x06, "Max horizontal image with aspect ratio distortion"),
+    (0x07, "Linear expansion (compression) on horizontal axis"),
+    (0x08, "Linear expansion (compression) on horizontal and vertical axes"),
+    (0x09, "Squeeze mode"),
+    (0x0a, "Non-linear expansion"),
+];
+
+const AUDIO_MUTE: &[(u8, &str)] = &[(0x01, "Mute"), (0x02, "Unmute")];
+
+const WINDOW_CONTROL: &[(u8, &str)] = &[(0x01, "Off"), (0x02, "On")];
+
+const SCREEN_ORIENTATION: &[(u8, &str)] = &[
+    (0x01, "0 degrees"),
+    (0x02, "90 degrees"),
+    (0x03, "180 degrees"),
+    (0x04, "270 degrees"),
+    (0xff, "Not applicable"),
+];
+
+const SETTINGS: &[(u8, &str)] = &[
+    (0x01, "Store current settings"),
+    (0x02, "Restore factory defaults for current mode"),
+];
+
+const SUBPIXEL_LAYOUT: &[(u8, &str)] = &[
+    (0x00, "Undefined"),
+    (0x01, "RGB vertical stripe"),
+    (0x02, "RGB horizontal stripe"),
+    (0x03, "BGR vertical stripe"),
+    (0x04, "BGR horizontal stripe"),
+    (0x05, "Quad pixel, red at top left"),
+    (0x06, "Quad pixel, red at bottom left"),
+    (0x07, "Delta (triad)"),
+    (0x08, "Mosaic"),
+];
+
+const DISPLAY_TECHNOLOGY: &[(u8, &str)] = &[
+    (0x01, "CRT (shadow mask)"),
+    (0x02, "CRT (aperture grill)"),
+    (0x03, "LCD (active matrix)"),
+    (0x04, "LCoS"),
+    (0x05, "Plasma"),
+    (0x06, "OLED"),
+    (0x07, "EL"),
+    (0x08, "Dynamic MEM"),
+    (0x09, "Static MEM"),
+];
+
+const DISPLAY_CONTROLLERS: &[(u8, &str)] = &[
+    (0x01, "Conexant"),
+    (0x02, "Genesis Microchip"),
+    (0x03, "Macronix"),
+    (0x04, "IDT"),
+    (0x05, "Mstar"),
+    (0x06, "Myson"),
+    (0x07, "Philips"),
+    (0x08, "PixelWorks"),
+    (0x09, "RealTek"),
+    (0x0a, "Sage"),
+    (0x0b, "Silicon Image"),
+    (0x0c, "SmartASIC"),
+    (0x0d, "STMicroelectronics"),
+    (0x0e, "Topro"),
+    (0x0f, "Trumpion"),
+    (0x10, "Welltrend"),
+    (0x11, "Samsung"),
+    (0x12, "Novatek"),
+    (0x13, "STK"),
+    (0x14, "Silicon Optics"),
+    (0xff, "Manufacturer designed controller"),
+];
+
+const OSD: &[(u8, &str)] = &[
+    (0x01, "OSD disabled"),
+    (0x02, "OSD enabled"),
+    (0xff, "Display cannot supply this information"),
+];
+
+const OSD_LANGUAGES: &[(u8, &str)] = &[
+    (0x01, "Chinese (traditional)"),
+    (0x02, "English"),
+    (0x03, "French"),
+    (0x04, "German"),
+    (0x05, "Italian"),
+    (0x06, "Japanese"),
+    (0x07, "Korean"),
+    (0x08, "Portuguese (Portugal)"),
+    (0x09, "Russian"),
+    (0x0a, "Spanish"),
+    (0x0b, "Swedish"),
+    (0x0c, "Turkish"),
+    (0x0d, "Chinese (simplified)"),
+    (0x0e, "Portuguese (Brazil)"),
+    (0x0f, "Arabic"),
+    (0x10, "Bulgarian"),
+    (0x11, "Croatian"),
+    (0x12, "Czech"),
+    (0x13, "Danish"),
+    (0x14, "Dutch"),
+    (0x15, "Estonian"),
+    (0x16, "Finnish"),
+    (0x17, "Greek"),
+    (0x18, "Hebrew"),
+    (0x19, "Hindi"),
+    (0x1a, "Hungarian"),
+    (0x1b, "Latvian"),
+    (0x1c, "Lithuanian"),
+    (0x1d, "Norwegian"),
+    (0x1e, "Polish"),
+    (0x1f, "Romanian"),
+    (0x20, "Serbian"),
+    (0x21, "Slovak"),
+    (0x22, "Slovenian"),
+    (0x23, "Thai"),
+    (0x24, "Ukrainian"),
+    (0x25, "Vietnamese"),
+];
+
+const POWER_MODES: &[(u8, &str)] = &[
+    (0x01, "On"),
+    (0x02, "Standby"),
+    (0x03, "Suspend"),
+    (0x04, "Off"),
+    (0x05, "Power off (power button)"),
+];
+
+const SCAN_MODES: &[(u8, &str)] = &[(0x00, "Normal operation"), (0x01, "Underscan"), (0x02, "Overscan")];
+
+const DISPLAY_APPLICATIONS: &[(u8, &str)] = &[
+    (0x00, "Standard / Default mode"),
+    (0x01, "Productivity"),
+    (0x02, "Mixed"),
+    (0x03, "Movie"),
+    (0x04, "User defined"),
+    (0x05, "Games"),
+    (0x06, "Sports"),
+    (0x07, "Professional (all signal processing disabled)"),
+    (0x08, "Standard / Default mode with intermediate power consumption"),
+    (0x09, "Standard / Default mode with low power consumption"),
+    (0x0a, "Demonstration"),
+    (0xf0, "Dynamic contrast"),
+];
+
+/// Features whose definition differs from [VCP_FEATURES] in some MCCS versions, with the range of
+/// versions (inclusive start, exclusive end) the definition applies to
+static VERSIONED_FEATURES: &[(MccsVersion, MccsVersion, VcpFeature)] = &[
+    // Named "Display Mode" before MCCS 2.2
+    (
+        MccsVersion::V2_0,
+        MccsVersion::V2_2,
+        VcpFeature::non_continuous(0xdc, "Display Mode", ReadWrite, DISPLAY_APPLICATIONS),
+    ),
+];
+
+/// Standard VCP features defined by MCCS 2.2a, sorted by code
+pub static VCP_FEATURES: &[VcpFeature] = &[
+    VcpFeature::non_continuous(0x01, "Degauss", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x02, "New Control Value", ReadWrite, NEW_CONTROL_VALUE),
+    VcpFeature::non_continuous(0x03, "Soft Controls", ReadWrite, &[]),
+    VcpFeature::non_continuous(0x04, "Restore Factory Defaults", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x05, "Restore Factory Luminance / Contrast Defaults", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x06, "Restore Factory Geometry Defaults", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x08, "Restore Factory Color Defaults", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x0a, "Restore Factory TV Defaults", WriteOnly, &[]),
+    VcpFeature::non_continuous(0x0b, "Color Temperature Increment", ReadOnly, &[]),
+    VcpFeature::continuous(0x0c, "Color Temperature Request", ReadWrite),
+    VcpFeature::continuous(0x0e, "Clock", ReadWrite),
+    VcpFeature::continuous(0x10, "Brightness", ReadWrite),
+    VcpFeature::non_continuous(0x11, "Flesh Tone Enhancement", ReadWrite, &[]),
+    VcpFeature::continuous(0x12, "Contrast", ReadWrite),
+    VcpFeature::continuous(0x13, "Backlight Control", ReadWrite),
+    VcpFeature::non_continuous(0x14, "Select Color Preset", ReadWrite, COLOR_PRESETS),
+    VcpFeature::continuous(0x16, "Video Gain: Red", ReadWrite),
+    VcpFeature::continuous(0x17, "User Color Vision Compensation", ReadWrite),
+    VcpFeature::continuous(0x18, "Video Gain: Green", ReadWrite),
+    VcpFeature::continuous(0x1a, "Video Gain: Blue", ReadWrite),
+    VcpFeature::continuous(0x1c, "Focus", ReadWrite),
+    VcpFeature::non_continuous(0x1e, "Auto Setup", ReadWrite, AUTO_SETUP),
+    VcpFeature::non_continuous(0x1f, "Auto Color Setup", ReadWrite, AUTO_SETUP),
+    VcpFeature::continuous(0x20, "Horizontal Position", ReadWrite),
+    VcpFeature::continuous(0x22, "Horizontal Size", ReadWrite),
+    VcpFeature::continuous(0x24, "Horizontal Pincushion", ReadWrite),
+    VcpFeature::continuous(0x26, "Horizontal Pincushion Balance", ReadWrite),
+    VcpFeature::continuous(0x28, "Horizontal Convergence R/B", ReadWrite),
+    VcpFeature::continuous(0x29, "Horizontal Convergence M/G", ReadWrite),
+    VcpFeature::continuous(0x2a, "Horizontal Linearity", ReadWrite),
+    VcpFeature::continuous(0x2c, "Horizontal Linearity Balance", ReadWrite),
+    VcpFeature::non_continuous(0x2e, "Gray Scale Expansion", ReadWrite, &[]),
+    VcpFeature::continuous(0x30, "Vertical Position", ReadWrite),
+    VcpFeature::continuous(0x32, "Vertical Size", ReadWrite),
+    VcpFeature::continuous(0x34, "Vertical Pincushion", ReadWrite),
+    VcpFeature::continuous(0x36, "Vertical Pincushion Balance", ReadWrite),
+    VcpFeature::continuous(0x38, "Vertical Convergence R/B", ReadWrite),
+    VcpFeature::continuous(0x39, "Vertical Convergence M/G", ReadWrite),
+    VcpFeature::continuous(0x3a, "Vertical Linearity", ReadWrite),
+    VcpFeature::continuous(0x3c, "Vertical Linearity Balance", ReadWrite),
+    VcpFeature::continuous(0x3e, "Clock Phase", ReadWrite),
+    VcpFeature::continuous(0x40, "Horizontal Parallelogram", ReadWrite),
+    VcpFeature::continuous(0x41, "Vertical Parallelogram", ReadWrite),
+    VcpFeature::continuous(0x42, "Horizontal Keystone", ReadWrite),
+    VcpFeature::continuous(0x43, "Vertical Keystone", ReadWrite),
+    VcpFeature::continuous(0x44, "Rotation", ReadWrite),
+    VcpFeature::continuous(0x46, "Top Corner Flare", ReadWrite),
+    VcpFeature::continuous(0x48, "Top Corner Hook", ReadWrite),
+    VcpFeature::continuous(0x4a, "Bottom Corner Flare", ReadWrite),
+    VcpFeature::continuous(0x4c, "Bottom Corner Hook", ReadWrite),
+    VcpFeature::non_continuous(0x52, "Active Control", ReadOnly, &[]),
+    VcpFeature::non_continuous(0x54, "Performance Preservation", ReadWrite, &[]),
+    VcpFeature::continuous(0x56, "Horizontal Moire", ReadWrite),
+    VcpFeature::continuous(0x58, "Vertical Moire", ReadWrite),
+    VcpFeature::continuous(0x59, "6 Axis Saturation: Red", ReadWrite),
+    VcpFeature::continuous(0x5a, "6 Axis Saturation: Yellow", ReadWrite),
+    VcpFeature::continuous(0x5b, "6 Axis Saturation: Green", ReadWrite),
+    VcpFeature::continuous(0x5c, "6 Axis Saturation: Cyan", ReadWrite),
+    VcpFeature::continuous(0x5d, "6 Axis Saturation: Blue", ReadWrite),
+    VcpFeature::continuous(0x5e, "6 Axis Saturation: Magenta", ReadWrite),
+    VcpFeature::non_continuous(0x60, "Input Source", ReadWrite, INPUT_SOURCES),
+    VcpFeature::continuous(0x62, "Audio Speaker Volume", ReadWrite),
+    VcpFeature::non_continuous(0x63, "Speaker Select", ReadWrite, SPEAKER_SELECT),
+    VcpFeature::continuous(0x64, "Audio Microphone Volume", ReadWrite),
+    VcpFeature::non_continuous(0x66, "Ambient Light Sensor", ReadWrite, AMBIENT_LIGHT_SENSOR),
+    VcpFeature::continuous(0x6b, "Backlight Level: White", ReadWrite),
+    VcpFeature::continuous(0x6c, "Video Black Level: Red", ReadWrite),
+    VcpFeature::continuous(0x6d, "Backlight Level: Red", ReadWrite),
+    VcpFeature::continuous(0x6e, "Video Black Level: Green", ReadWrite),
+    VcpFeature::continuous(0x6f, "Backlight Level: Green", ReadWrite),
+    VcpFeature::continuous(0x70, "Video Black Level: Blue", ReadWrite),
+    VcpFeature::continuous(0x71, "Backlight Level: Blue", ReadWrite),
+    VcpFeature::non_continuous(0x72, "Gamma", ReadWrite, &[]),
+    VcpFeature::table(0x73, "LUT Size", ReadOnly),
+    VcpFeature::table(0x74, "Single Point LUT Operation", ReadWrite),
+    VcpFeature::table(0x75, "Block LUT Operation", ReadWrite),
+    VcpFeature::table(0x76, "Remote Procedure Call", WriteOnly),
+    VcpFeature::table(0x78, "Display Identification Operation", ReadOnly),
+    VcpFeature::continuous(0x7a, "Adjust Focal Plane", ReadWrite),
+    VcpFeature::continuous(0x7c, "Adjust Zoom", ReadWrite),
+    VcpFeature::continuous(0x7e, "Trapezoid", ReadWrite),
+    VcpFeature::non_continuous(0x82, "Horizontal Mirror (Flip)", ReadWrite, MIRROR),
+    VcpFeature::non_continuous(0x84, "Vertical Mirror (Flip)", ReadWrite, MIRROR),
+    VcpFeature::non_continuous(0x86, "Display Scaling", ReadWrite, DISPLAY_SCALING),
+    VcpFeature::continuous(0x87, "Sharpness", ReadWrite),
+    VcpFeature::continuous(0x88, "Velocity Scan Modulation", ReadWrite),
+    VcpFeature::continuous(0x8a, "Color Saturation", ReadWrite),
+    VcpFeature::non_continuous(0x8b, "TV Channel Up / Down", WriteOnly, &[]),
+    VcpFeature::continuous(0x8c, "TV Sharpness", ReadWrite),
+    VcpFeature::non_continuous(0x8d, "Audio Mute / Screen Blank", ReadWrite, AUDIO_MUTE),
+    VcpFeature::continuous(0x8e, "TV Contrast", ReadWrite),
+    VcpFeature::continuous(0x8f, "Audio Treble", ReadWrite),
+    VcpFeature::continuous(0x90, "Hue", ReadWrite),
+    VcpFeature::continuous(0x91, "Audio Bass", ReadWrite),
+    VcpFeature::continuous(0x92, "TV Black Level / Luminance", ReadWrite),
+    VcpFeature::continuous(0x93, "Audio Balance L/R", ReadWrite),
+    VcpFeature::non_continuous(0x94, "Audio Processor Mode", ReadWrite, &[]),
+    VcpFeature::continuous(0x95, "Window Position (TL_X)", ReadWrite),
+    VcpFeature::continuous(0x96, "Window Position (TL_Y)", ReadWrite),
+    VcpFeature::continuous(0x97, "Window Position (BR_X)", ReadWrite),
+    VcpFeature::continuous(0x98, "Window Position (BR_Y)", ReadWrite),
+    VcpFeature::non_continuous(0x99, "Window Control On / Off", ReadWrite, WINDOW_CONTROL),
+    VcpFeature::continuous(0x9a, "Window Background", ReadWrite),
+    VcpFeature::continuous(0x9b, "6 Axis Hue Control: Red", ReadWrite),
+    VcpFeature::continuous(0x9c, "6 Axis Hue Control: Yellow", ReadWrite),
+    VcpFeature::continuous(0x9d, "6 Axis Hue Control: Green", ReadWrite),
+    VcpFeature::continuous(0x9e, "6 Axis Hue Control: Cyan", ReadWrite),
+    VcpFeature::continuous(0x9f, "6 Axis Hue Control: Blue", ReadWrite),
+    VcpFeature::continuous(0xa0, "6 Axis Hue Control: Magenta", ReadWrite),
+    VcpFeature::non_continuous(0xa2, "Auto Setup On / Off", WriteOnly, &[]),
+    VcpFeature::table(0xa4, "Window Mask Control", ReadWrite),
+    VcpFeature::non_continuous(0xa5, "Change the Selected Window", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xaa, "Screen Orientation", ReadOnly, SCREEN_ORIENTATION),
+    VcpFeature::continuous(0xac, "Horizontal Frequency", ReadOnly),
+    VcpFeature::continuous(0xae, "Vertical Frequency", ReadOnly),
+    VcpFeature::non_continuous(0xb0, "Settings", WriteOnly, SETTINGS),
+    VcpFeature::non_continuous(0xb2, "Flat Panel Sub-Pixel Layout", ReadOnly, SUBPIXEL_LAYOUT),
+    VcpFeature::table(0xb4, "Source Timing Mode", ReadWrite),
+    VcpFeature::non_continuous(0xb5, "Source Color Coding", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xb6, "Display Technology Type", ReadOnly, DISPLAY_TECHNOLOGY),
+    VcpFeature::non_continuous(0xb7, "Monitor Status", ReadOnly, &[]),
+    VcpFeature::continuous(0xb8, "Packet Count", ReadWrite),
+    VcpFeature::continuous(0xb9, "Monitor X Origin", ReadWrite),
+    VcpFeature::continuous(0xba, "Monitor Y Origin", ReadWrite),
+    VcpFeature::continuous(0xbb, "Header Error Count", ReadWrite),
+    VcpFeature::continuous(0xbc, "Body CRC Error Count", ReadWrite),
+    VcpFeature::continuous(0xbd, "Client ID", ReadWrite),
+    VcpFeature::non_continuous(0xbe, "Link Control", ReadWrite, &[]),
+    VcpFeature::continuous(0xc0, "Display Usage Time", ReadOnly),
+    VcpFeature::continuous(0xc2, "Display Descriptor Length", ReadOnly),
+    VcpFeature::table(0xc3, "Transmit Display Descriptor", ReadWrite),
+    VcpFeature::non_continuous(0xc4, "Enable Display of 'Display Descriptor'", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xc6, "Application Enable Key", ReadOnly, &[]),
+    VcpFeature::non_continuous(0xc8, "Display Controller Type", ReadWrite, DISPLAY_CONTROLLERS),
+    VcpFeature::continuous(0xc9, "Display Firmware Level", ReadOnly),
+    VcpFeature::non_continuous(0xca, "OSD / Button Control", ReadWrite, OSD),
+    VcpFeature::non_continuous(0xcc, "OSD Language", ReadWrite, OSD_LANGUAGES),
+    VcpFeature::non_continuous(0xcd, "Status Indicators", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xce, "Auxiliary Display Size", ReadOnly, &[]),
+    VcpFeature::table(0xcf, "Auxiliary Display Data", WriteOnly),
+    VcpFeature::non_continuous(0xd0, "Output Select", ReadWrite, &[]),
+    VcpFeature::table(0xd2, "Asset Tag", ReadWrite),
+    VcpFeature::non_continuous(0xd4, "Stereo Video Mode", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xd6, "Power Mode", ReadWrite, POWER_MODES),
+    VcpFeature::non_continuous(0xd7, "Auxiliary Power Output", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xda, "Scan Mode", ReadWrite, SCAN_MODES),
+    VcpFeature::non_continuous(0xdb, "Image Mode", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xdc, "Display Application", ReadWrite, DISPLAY_APPLICATIONS),
+    VcpFeature::non_continuous(0xde, "Scratch Pad", ReadWrite, &[]),
+    VcpFeature::non_continuous(0xdf, "VCP Version", ReadOnly, &[]),
+];
@@ -0,0 +1,264 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::{Ddc, DdcHost, FeatureCode};
+use std::collections::BTreeMap;
+
+/// VCP code of the New Control Value flag
+pub const VCP_NEW_CONTROL_VALUE: FeatureCode = 0x02;
+/// VCP code of the Active Control FIFO
+pub const VCP_ACTIVE_CONTROL: FeatureCode = 0x52;
+
+/// Largest number of entries read from the Active Control FIFO in one poll, in case a monitor never
+/// reports it as empty
+pub const MAX_ACTIVE_CONTROLS: usize = 32;
+
+/// State reported by the New Control Value flag (VCP 0x02)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NewControlValue {
+    /// No control was changed since the flag was last reset
+    None,
+    /// Controls were changed, they are listed in the Active Control FIFO (VCP 0x52)
+    Present,
+    /// The monitor has no user controls
+    NoUserControls,
+}
+
+impl NewControlValue {
+    /// Decodes the value of VCP 0x02, or `None` for a reserved value
+    pub fn from_value(value: u16) -> Option<Self> {
+        match value & 0xff {
+            0x01 => Some(NewControlValue::None),
+            0x02 => Some(NewControlValue::Present),
+            0xff => Some(NewControlValue::NoUserControls),
+            _ => None,
+        }
+    }
+}
+
+/// How a [ChangePoller] learns about changed controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeDetection {
+    /// The monitor reports changes through the New Control Value flag and the Active Control FIFO
+    NewControlValue,
+    /// The watched controls are read on every poll and compared to their previous values
+    ReadAndDiff,
+}
+
+/// A control that changed since the previous poll, e.g. from the monitor's own buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlChange {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Previously known value, if any
+    pub previous: Option<u16>,
+    /// New value
+    pub value: u16,
+}
+
+/// Detects controls changed outside of this crate, such as through the monitor's OSD.
+///
+/// Monitors supporting it are polled through the New Control Value flag (VCP 0x02): when it is set,
+/// the Active Control FIFO (VCP 0x52) is drained, the flag is reset and the listed controls are
+/// read. Other monitors fall back to reading the watched controls and comparing them with the
+/// previous poll. The first poll only establishes the known values and picks the detection method.
+/// Watched controls the monitor reports as unsupported are no longer read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangePoller {
+    codes: Vec<FeatureCode>,
+    unsupported: Vec<FeatureCode>,
+    values: BTreeMap<FeatureCode, u16>,
+    detection: Option<ChangeDetection>,
+}
+
+impl ChangePoller {
+    /// Creates a poller watching the given VCP codes
+    pub fn new(codes: impl IntoIterator<Item = FeatureCode>) -> Self {
+        let mut codes: Vec<FeatureCode> = codes.into_iter().collect();
+        codes.sort_unstable();
+        codes.dedup();
+        ChangePoller {
+            codes,
+            unsupported: Vec::new(),
+            values: BTreeMap::new(),
+            detection: None,
+        }
+    }
+
+    /// Uses the given detection method instead of probing the monitor for it
+    pub fn with_detection(mut self, detection: ChangeDetection) -> Self {
+        self.detection = Some(detection);
+        self
+    }
+
+    /// Watched VCP codes
+    pub fn codes(&self) -> &[FeatureCode] {
+        &self.codes
+    }
+
+    /// Watched VCP codes the monitor reported as unsupported, which are no longer read
+    pub fn unsupported(&self) -> &[FeatureCode] {
+        &self.unsupported
+    }
+
+    /// Detection method in use, or `None` before the first poll
+    pub fn detection(&self) -> Option<ChangeDetection> {
+        self.detection
+    }
+
+    /// Last known value of a VCP feature
+    pub fn value(&self, code: FeatureCode) -> Option<u16> {
+        self.values.get(&code).copied()
+    }
+
+    /// Records a value read from the monitor and returns the change if it differs from the last
+    /// known one. The first value of a code only establishes the known value.
+    pub fn record(&mut self, code: FeatureCode, value: u16) -> Option<ControlChange> {
+        let previous = self.values.insert(code, value)?;
+        (previous != value).then_some(ControlChange {
+            code,
+            previous: Some(previous),
+            value,
+        })
+    }
+
+    fn is_started(&self) -> bool {
+        self.detection.is_some() && self.codes.iter().all(|code| self.values.contains_key(code))
+    }
+
+    /// Reads every watched control and returns the ones that changed
+    fn read_and_diff<D>(&mut self, ddc: &mut D) -> Result<Vec<ControlChange>, Error>
+    where
+        D: Ddc<Error = Error> + DdcHost,
+    {
+        let mut changes = Vec::new();
+        for code in self.codes.clone() {
+            ddc.sleep();
+            match ddc.get_vcp_feature(code) {
+                Ok(value) => changes.extend(self.record(code, value.value())),
+                Err(Error::UnsupportedFeature { .. }) => {
+                    self.codes.retain(|watched| *watched != code);
+                    self.unsupported.push(code);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Reads the New Control Value flag, or returns `None` if the monitor does not support it
+    fn new_control_value<D>(ddc: &mut D) -> Result<Option<NewControlValue>, Error>
+    where
+        D: Ddc<Error = Error> + DdcHost,
+    {
+        ddc.sleep();
+        match ddc.get_vcp_feature(VCP_NEW_CONTROL_VALUE) {
+            Ok(value) => Ok(NewControlValue::from_value(value.value())),
+            Err(Error::UnsupportedFeature { .. } | Error::Ddc(_) | Error::NullMessage) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Drains the Active Control FIFO, returning the changed codes in order without duplicates
+    fn active_controls<D>(ddc: &mut D) -> Result<Vec<FeatureCode>, Error>
+    where
+        D: Ddc<Error = Error> + DdcHost,
+    {
+        let mut codes = Vec::new();
+        for _ in 0..MAX_ACTIVE_CONTROLS {
+            ddc.sleep();
+            let code = ddc.get_vcp_feature(VCP_ACTIVE_CONTROL)?.sl;
+            if code == 0 {
+                break;
+            }
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        Ok(codes)
+    }
+
+    /// Polls a monitor once, see [ChangePoller]. On the first poll, the New Control Value flag is
+    /// only probed if `supports_new_control_value` is set, e.g. because the monitor advertises it.
+    pub fn poll<D>(&mut self, ddc: &mut D, supports_new_control_value: bool) -> Result<Vec<ControlChange>, Error>
+    where
+        D: Ddc<Error = Error> + DdcHost,
+    {
+        if !self.is_started() {
+            if self.detection.is_none() {
+                let state = if supports_new_control_value {
+                    Self::new_control_value(ddc)?
+                } else {
+                    None
+                };
+                self.detection = Some(match state {
+                    Some(NewControlValue::None) | Some(NewControlValue::Present) => ChangeDetection::NewControlValue,
+                    _ => ChangeDetection::ReadAndDiff,
+                });
+            }
+            if self.detection == Some(ChangeDetection::NewControlValue) {
+                // Changes made before the first poll are picked up by reading the values below
+                ddc.sleep();
+                ddc.set_vcp_feature(VCP_NEW_CONTROL_VALUE, 0x01)?;
+            }
+            self.read_and_diff(ddc)?;
+            return Ok(Vec::new());
+        }
+
+        if self.detection == Some(ChangeDetection::ReadAndDiff) {
+            return self.read_and_diff(ddc);
+        }
+        if Self::new_control_value(ddc)? != Some(NewControlValue::Present) {
+            return Ok(Vec::new());
+        }
+        let codes = match Self::active_controls(ddc) {
+            Ok(codes) => codes,
+            // Something changed, but the monitor cannot tell what
+            Err(Error::UnsupportedFeature { .. } | Error::Ddc(_) | Error::NullMessage) => {
+                ddc.sleep();
+                ddc.set_vcp_feature(VCP_NEW_CONTROL_VALUE, 0x01)?;
+                return self.read_and_diff(ddc);
+            }
+            Err(error) => return Err(error),
+        };
+        ddc.sleep();
+        ddc.set_vcp_feature(VCP_NEW_CONTROL_VALUE, 0x01)?;
+        let mut changes = Vec::new();
+        for code in codes {
+            ddc.sleep();
+            let value = match ddc.get_vcp_feature(code) {
+                Ok(value) => value.value(),
+                Err(Error::UnsupportedFeature { .. }) => continue,
+                Err(error) => return Err(error),
+            };
+            let previous = self.values.insert(code, value);
+            if previous != Some(value) {
+                changes.push(ControlChange { code, previous, value });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Detection of changes made with the monitor's own controls
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Polls this [Monitor] once for controls changed since the previous poll, see [ChangePoller].
+    /// Call it periodically with the same poller to keep e.g. a brightness slider in sync. The New
+    /// Control Value flag is probed if the [cached capabilities](Monitor::cached_capabilities)
+    /// advertise it or could not be read.
+    pub fn poll_changes(&mut self, poller: &mut ChangePoller) -> Result<Vec<ControlChange>, Error> {
+        let supported = match poller.detection() {
+            Some(_) => true,
+            None => self
+                .cached_capabilities()
+                .map_or(true, |capabilities| capabilities.supports_vcp(VCP_NEW_CONTROL_VALUE)),
+        };
+        poller.poll(self, supported)
+    }
+}
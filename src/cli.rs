@@ -0,0 +1,467 @@
+#![deny(missing_docs)]
+
+use crate::backend::{select_display, DisplayBackend, ListedDisplay, MonitorSelector};
+use crate::capabilities::{Capabilities, MccsVersion};
+use crate::catalog::VCP_MCCS_VERSION;
+use crate::catalog::{describe_vcp_value, parse_feature_code, vcp_feature};
+use crate::controls::{FeatureValue, ValueArgument};
+use crate::edid::Edid;
+use crate::error::Error;
+use crate::info::MonitorInfo;
+use crate::profile::{apply_profile, ProfileChanges, ProfileOptions, Profiles};
+use crate::snapshot::{read_snapshot, restore_snapshot, snapshot_features, MonitorSnapshot, SettingChange};
+use ddc::FeatureCode;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+/// Usage text of the `ddc-macos` command line tool
+pub const CLI_USAGE: &str = "\
+Usage: ddc-macos [--display <monitor>] [--json] <command> [arguments]
+
+Commands:
+  list                      List connected monitors
+  info                      Show firmware, usage time and controller information
+  get <feature>             Read a VCP feature
+  set <feature> <value>     Write a VCP feature; relative values like +10 or -10 are added to the current one
+  caps                      Show the parsed capabilities string
+  edid                      Show the EDID in hex
+  dump                      Print all readable settings as a snapshot
+  restore [--dry-run] <file>
+                            Restore settings from a snapshot printed by dump, `-` reads standard input
+  profile [--dry-run] [--only <features>] <file> <name>
+                            Apply a profile from a TOML file to all monitors, or the selected one;
+                            --only applies just the given comma separated features
+
+Monitors are selected by their index in `list` (starting at 1), persistent ID (like 10ac:a0c4:12345),
+`serial=<serial number>` or `name=<product name>`; any other text is matched against the product name.
+Without a selection, the first monitor is used. Features are given as hex codes (like 10 or 0x10) or
+names (like brightness or input-source), values as numbers or names (like hdmi-1).
+
+With --json, every command prints a JSON object with the schema version, the command and its result.
+Errors, including invalid arguments, are printed as a JSON object with the schema version and the
+error message.
+";
+
+/// Command of the `ddc-macos` command line tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Print [CLI_USAGE]
+    Help,
+    /// List connected monitors
+    List,
+    /// Show asset information, see [MonitorInfo]
+    Info,
+    /// Read a VCP feature
+    Get {
+        /// VCP feature code
+        code: FeatureCode,
+    },
+    /// Write a VCP feature
+    Set {
+        /// VCP feature code
+        code: FeatureCode,
+        /// Value to write
+        value: ValueArgument,
+    },
+    /// Show the parsed capabilities string
+    Capabilities,
+    /// Show the EDID
+    Edid,
+    /// Print a snapshot of all readable settings
+    Dump,
+    /// Restore a snapshot printed by [CliCommand::Dump]
+    Restore {
+        /// Snapshot file, `-` for standard input
+        path: PathBuf,
+        /// Only print the changes
+        dry_run: bool,
+    },
+    /// Apply a profile, see [Profiles]
+    Profile {
+        /// TOML file holding the profiles
+        path: PathBuf,
+        /// Profile name
+        name: String,
+        /// Only apply these VCP features
+        features: Option<Vec<FeatureCode>>,
+        /// Only print the changes
+        dry_run: bool,
+    },
+}
+
+/// Parsed command line of the `ddc-macos` tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cli {
+    /// Selected monitor
+    pub display: Option<MonitorSelector>,
+    /// Command to run
+    pub command: CliCommand,
+    /// Print the output as JSON, see [JsonOutput]
+    pub json: bool,
+}
+
+impl Cli {
+    /// Parses the command line arguments, without the program name
+    pub fn parse<I, S>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut display = None;
+        let mut dry_run = false;
+        let mut json = false;
+        let mut features = None;
+        let mut positional = Vec::new();
+        let mut args = args.into_iter().map(|arg| arg.as_ref().to_string());
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    return Ok(Cli {
+                        display,
+                        command: CliCommand::Help,
+                        json,
+                    })
+                }
+                "-d" | "--display" => {
+                    let selector = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} needs a monitor", arg)))?;
+                    display = Some(selector.parse()?);
+                }
+                "--dry-run" => dry_run = true,
+                "--only" => {
+                    let list = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument("--only needs a list of features".into()))?;
+                    features = Some(list.split(',').map(parse_feature_code).collect::<Result<_, _>>()?);
+                }
+                "--json" => json = true,
+                _ => match arg.strip_prefix("--display=") {
+                    Some(selector) => display = Some(selector.parse()?),
+                    // Negative numbers are relative values and `-` is standard input rather than options
+                    None if arg.len() > 1
+                        && arg.starts_with('-')
+                        && !arg[1..].starts_with(|c: char| c.is_ascii_digit()) =>
+                    {
+                        return Err(Error::InvalidArgument(format!("unknown option {}", arg)))
+                    }
+                    None => positional.push(arg),
+                },
+            }
+        }
+
+        let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+        let command = match positional.as_slice() {
+            [] | ["help"] => CliCommand::Help,
+            ["list"] => CliCommand::List,
+            ["info"] => CliCommand::Info,
+            ["get", code] => CliCommand::Get {
+                code: parse_feature_code(code)?,
+            },
+            ["set", code, value] => {
+                let code = parse_feature_code(code)?;
+                CliCommand::Set {
+                    code,
+                    value: ValueArgument::parse(code, value)?,
+                }
+            }
+            ["caps"] => CliCommand::Capabilities,
+            ["edid"] => CliCommand::Edid,
+            ["dump"] => CliCommand::Dump,
+            ["restore", path] => CliCommand::Restore {
+                path: path.into(),
+                dry_run,
+            },
+            ["profile", path, name] => CliCommand::Profile {
+                path: path.into(),
+                name: name.to_string(),
+                features: features.take(),
+                dry_run,
+            },
+            [command, ..] => {
+                return Err(Error::InvalidArgument(format!(
+                    "unknown command or wrong number of arguments: {}",
+                    command
+                )))
+            }
+        };
+        if dry_run && !matches!(command, CliCommand::Restore { .. } | CliCommand::Profile { .. }) {
+            return Err(Error::InvalidArgument(
+                "--dry-run only applies to restore and profile".into(),
+            ));
+        }
+        if features.is_some() {
+            return Err(Error::InvalidArgument("--only only applies to profile".into()));
+        }
+        Ok(Cli { display, command, json })
+    }
+
+    /// Runs the command on `backend`, writing its output to `out` as text or, with `--json`, as a
+    /// [JsonOutput]
+    pub fn run(&self, backend: &mut impl DisplayBackend, out: &mut impl Write) -> Result<(), Error> {
+        if let CliCommand::Help = self.command {
+            return Ok(out.write_all(CLI_USAGE.as_bytes())?);
+        }
+        let output = self.execute(backend)?;
+        if self.json {
+            serde_json::to_writer_pretty(&mut *out, &JsonOutput::new(output)).map_err(io::Error::from)?;
+            writeln!(out)?;
+        } else {
+            write!(out, "{}", output)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the command on `backend` and returns its result
+    pub fn execute(&self, backend: &mut impl DisplayBackend) -> Result<CliOutput, Error> {
+        let displays = backend.displays()?;
+        match &self.command {
+            CliCommand::List => return Ok(CliOutput::List(ListedDisplay::list(displays))),
+            CliCommand::Profile {
+                path,
+                name,
+                features,
+                dry_run,
+            } => {
+                let profiles = Profiles::from_toml(&read_file(path)?)?;
+                let options = ProfileOptions {
+                    display: self.display.clone(),
+                    features: features.clone(),
+                    dry_run: *dry_run,
+                };
+                return Ok(CliOutput::Profile {
+                    dry_run: *dry_run,
+                    monitors: apply_profile(backend, profiles.get(name)?, &options)?,
+                });
+            }
+            _ => {}
+        }
+
+        let index = select_display(&displays, self.display.as_ref())?;
+        Ok(match &self.command {
+            CliCommand::Help | CliCommand::List | CliCommand::Profile { .. } => unreachable!("handled above"),
+            CliCommand::Info => {
+                let mccs_version = match backend.get_vcp_feature(index, VCP_MCCS_VERSION) {
+                    Ok(value) if value.value() != 0 => Some(MccsVersion::new(value.sh, value.sl)),
+                    _ => read_capabilities(backend, index)
+                        .ok()
+                        .and_then(|caps| caps.mccs_version),
+                };
+                CliOutput::Info(MonitorInfo::read(
+                    |code| backend.get_vcp_feature(index, code).ok(),
+                    mccs_version,
+                ))
+            }
+            CliCommand::Get { code } => {
+                CliOutput::Get(FeatureValue::new(*code, &backend.get_vcp_feature(index, *code)?))
+            }
+            CliCommand::Set { code, value } => {
+                let (from, to) = match value {
+                    ValueArgument::Absolute(value) => (None, *value),
+                    relative => {
+                        let current = backend.get_vcp_feature(index, *code)?;
+                        (Some(current.value()), relative.resolve(&current))
+                    }
+                };
+                backend.set_vcp_feature(index, *code, to)?;
+                CliOutput::Set(SettingChange { code: *code, from, to })
+            }
+            CliCommand::Capabilities => {
+                let raw = backend.capabilities_string(index)?;
+                CliOutput::Capabilities {
+                    parsed: Capabilities::parse(&raw)?,
+                    raw: String::from_utf8_lossy(&raw).trim_end_matches('\0').to_string(),
+                }
+            }
+            CliCommand::Edid => {
+                let data = backend.edid(index)?;
+                CliOutput::Edid {
+                    parsed: data.as_deref().and_then(|data| Edid::parse(data).ok()),
+                    data: data.map(|data| data.iter().map(|byte| format!("{:02x}", byte)).collect()),
+                }
+            }
+            CliCommand::Dump => {
+                let codes = snapshot_features(&read_capabilities(backend, index)?);
+                CliOutput::Dump(read_snapshot(backend, index, displays[index].identity.clone(), &codes))
+            }
+            CliCommand::Restore { path, dry_run } => {
+                let snapshot: MonitorSnapshot = read_file(path)?.parse()?;
+                let changes = restore_snapshot(backend, index, &snapshot, *dry_run)?;
+                CliOutput::Restore {
+                    dry_run: *dry_run,
+                    changes,
+                }
+            }
+        })
+    }
+}
+
+/// Result of a [CliCommand]. Its `Display` implementation is the text output of the tool.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "command", content = "result", rename_all = "snake_case")]
+pub enum CliOutput {
+    /// Connected monitors
+    List(Vec<ListedDisplay>),
+    /// Asset information
+    Info(MonitorInfo),
+    /// Value read
+    Get(FeatureValue),
+    /// Value written, along with the previous value if it was read
+    Set(SettingChange),
+    /// Capabilities string
+    Capabilities {
+        /// Raw capabilities string
+        raw: String,
+        /// Parsed capabilities
+        parsed: Capabilities,
+    },
+    /// EDID of the monitor
+    Edid {
+        /// Raw EDID in hex, `None` if the monitor has none
+        data: Option<String>,
+        /// Parsed identification fields, `None` if the EDID is missing or invalid
+        parsed: Option<Edid>,
+    },
+    /// Snapshot of all readable settings
+    Dump(MonitorSnapshot),
+    /// Settings changed by a restore, or that would be changed in a dry run
+    Restore {
+        /// Nothing was written
+        dry_run: bool,
+        /// Changed settings, in the order they were written
+        changes: Vec<SettingChange>,
+    },
+    /// Settings changed by a profile, or that would be changed in a dry run
+    Profile {
+        /// Nothing was written
+        dry_run: bool,
+        /// Changes of each monitor the profile applied to
+        monitors: Vec<ProfileChanges>,
+    },
+}
+
+impl fmt::Display for CliOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliOutput::List(displays) => {
+                for listed in displays {
+                    write!(f, "{}: {} (id {}", listed.index, listed.display.description, listed.id)?;
+                    if let Some(serial) = &listed.display.serial_number {
+                        write!(f, ", serial {}", serial)?;
+                    }
+                    writeln!(f, ")")?;
+                }
+                Ok(())
+            }
+            CliOutput::Info(info) => write!(f, "{}", info),
+            CliOutput::Get(value) => writeln!(f, "{}", describe_vcp_value(value.code, &value.vcp_value())),
+            CliOutput::Set(_) => Ok(()),
+            CliOutput::Capabilities { parsed, .. } => write_capabilities(parsed, f),
+            CliOutput::Edid { data: None, .. } => writeln!(f, "No EDID available"),
+            CliOutput::Edid { data: Some(data), .. } => {
+                let bytes: Vec<&str> = (0..data.len())
+                    .step_by(2)
+                    .map(|start| &data[start..start + 2])
+                    .collect();
+                for line in bytes.chunks(16) {
+                    writeln!(f, "{}", line.join(" "))?;
+                }
+                Ok(())
+            }
+            CliOutput::Dump(snapshot) => write!(f, "{}", snapshot),
+            CliOutput::Restore { changes, .. } => changes.iter().try_for_each(|change| writeln!(f, "{}", change)),
+            CliOutput::Profile { monitors, .. } => monitors.iter().try_for_each(|monitor| write!(f, "{}", monitor)),
+        }
+    }
+}
+
+/// Version of the JSON output of the command line tool. It changes whenever a field is removed,
+/// renamed or changes its meaning; new fields can be added within a version.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// JSON output of the command line tool: `{"schema": 1, "command": "get", "result": {...}}`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct JsonOutput {
+    /// Schema version, see [JSON_SCHEMA_VERSION]
+    pub schema: u32,
+    /// Command and its result
+    #[serde(flatten)]
+    pub output: CliOutput,
+}
+
+impl JsonOutput {
+    /// Wraps a result in the current schema version
+    pub fn new(output: CliOutput) -> Self {
+        JsonOutput {
+            schema: JSON_SCHEMA_VERSION,
+            output,
+        }
+    }
+}
+
+/// JSON output of the command line tool when a command fails: `{"schema": 1, "error": "..."}`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct JsonError {
+    /// Schema version, see [JSON_SCHEMA_VERSION]
+    pub schema: u32,
+    /// Error message
+    pub error: String,
+}
+
+impl From<&Error> for JsonError {
+    fn from(error: &Error) -> Self {
+        JsonError {
+            schema: JSON_SCHEMA_VERSION,
+            error: error.to_string(),
+        }
+    }
+}
+
+fn read_capabilities(backend: &mut impl DisplayBackend, index: usize) -> Result<Capabilities, Error> {
+    Capabilities::parse(&backend.capabilities_string(index)?)
+}
+
+fn read_file(path: &PathBuf) -> Result<String, Error> {
+    let mut text = String::new();
+    let result = match path.to_str() {
+        Some("-") => io::stdin().read_to_string(&mut text).map(|_| ()),
+        _ => std::fs::File::open(path).and_then(|mut file| file.read_to_string(&mut text).map(|_| ())),
+    };
+    result.map_err(|error| Error::InvalidArgument(format!("cannot read {}: {}", path.display(), error)))?;
+    Ok(text)
+}
+
+fn write_capabilities(caps: &Capabilities, out: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(model) = &caps.model {
+        writeln!(out, "Model: {}", model)?;
+    }
+    if let Some(version) = caps.mccs_version {
+        writeln!(out, "MCCS version: {}", version)?;
+    }
+    let commands: Vec<String> = caps.commands.iter().map(|opcode| format!("{:02x}", opcode)).collect();
+    writeln!(out, "Commands: {}", commands.join(" "))?;
+    writeln!(out, "VCP features:")?;
+    for (code, capability) in &caps.vcp_features {
+        let feature = vcp_feature(*code);
+        write!(out, "  0x{:02x}", code)?;
+        if let Some(feature) = feature {
+            write!(out, " {}", feature.name)?;
+        }
+        if !capability.values.is_empty() {
+            let values: Vec<String> = capability
+                .values
+                .iter()
+                .map(
+                    |value| match feature.and_then(|feature| feature.value_name((*value).into())) {
+                        Some(name) => name.to_string(),
+                        None => format!("0x{:02x}", value),
+                    },
+                )
+                .collect();
+            write!(out, ": {}", values.join(", "))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+#![deny(missing_docs)]
+
+use crate::controls::{ColorPreset, VCP_COLOR_PRESET};
+#[cfg(target_os = "macos")]
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::FeatureCode;
+#[cfg(target_os = "macos")]
+use ddc::{Ddc, DdcHost};
+
+/// VCP code of the color temperature increment
+pub const VCP_COLOR_TEMPERATURE_INCREMENT: FeatureCode = 0x0b;
+/// VCP code of the color temperature request
+pub const VCP_COLOR_TEMPERATURE_REQUEST: FeatureCode = 0x0c;
+/// VCP codes of the red, green and blue video gains
+pub const VCP_VIDEO_GAIN: [FeatureCode; 3] = [0x16, 0x18, 0x1a];
+/// VCP codes of the red, green and blue video black levels
+pub const VCP_BLACK_LEVEL: [FeatureCode; 3] = [0x6c, 0x6e, 0x70];
+
+/// Color temperature the color temperature request values start from, in Kelvin
+pub const COLOR_TEMPERATURE_BASE: u32 = 3000;
+
+/// Red, green and blue values of a color control
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RgbValues {
+    /// Red channel
+    pub red: u16,
+    /// Green channel
+    pub green: u16,
+    /// Blue channel
+    pub blue: u16,
+}
+
+impl RgbValues {
+    fn channels(&self) -> [u16; 3] {
+        [self.red, self.green, self.blue]
+    }
+}
+
+/// Color temperature request (VCP 0x0C) along with the step size reported in VCP 0x0B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorTemperature {
+    /// Requested value: the number of increments above [COLOR_TEMPERATURE_BASE]
+    pub request: u16,
+    /// Size of an increment in Kelvin, if the monitor reports it
+    pub increment: Option<u16>,
+}
+
+impl ColorTemperature {
+    /// Requested color temperature in Kelvin, if the increment is known
+    pub fn kelvin(&self) -> Option<u32> {
+        self.increment
+            .map(|increment| COLOR_TEMPERATURE_BASE + u32::from(self.request) * u32::from(increment))
+    }
+
+    /// Request value for the color temperature closest to `kelvin`, given the increment of the monitor
+    pub fn for_kelvin(kelvin: u32, increment: u16) -> Self {
+        let steps = kelvin.saturating_sub(COLOR_TEMPERATURE_BASE) + u32::from(increment) / 2;
+        ColorTemperature {
+            request: (steps / u32::from(increment.max(1))).min(u32::from(u16::MAX)) as u16,
+            increment: Some(increment),
+        }
+    }
+}
+
+/// Color calibration settings of a monitor. Settings the monitor does not support are `None`
+/// when read, and settings left `None` are not written when applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorSettings {
+    /// Selected color preset (VCP 0x14)
+    pub preset: Option<ColorPreset>,
+    /// Color temperature request (VCP 0x0C)
+    pub color_temperature: Option<ColorTemperature>,
+    /// Video gains (VCP 0x16, 0x18, 0x1A)
+    pub gain: Option<RgbValues>,
+    /// Video black levels (VCP 0x6C, 0x6E, 0x70)
+    pub black_level: Option<RgbValues>,
+}
+
+impl ColorSettings {
+    /// VCP writes needed to apply these settings, in order: the preset first since selecting it can
+    /// reset the other settings, then the color temperature, gains and black levels.
+    pub fn writes(&self) -> Vec<(FeatureCode, u16)> {
+        let mut writes = Vec::new();
+        if let Some(preset) = self.preset {
+            writes.push((VCP_COLOR_PRESET, preset.value()));
+        }
+        if let Some(temperature) = self.color_temperature {
+            writes.push((VCP_COLOR_TEMPERATURE_REQUEST, temperature.request));
+        }
+        for (codes, values) in [(VCP_VIDEO_GAIN, self.gain), (VCP_BLACK_LEVEL, self.black_level)] {
+            if let Some(values) = values {
+                writes.extend(codes.into_iter().zip(values.channels()));
+            }
+        }
+        writes
+    }
+}
+
+/// Color calibration
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Reads the color calibration settings, leaving out the ones the monitor does not support
+    pub fn color_settings(&mut self) -> Result<ColorSettings, Error> {
+        let preset = self.color_preset().ok();
+        let increment = self.read_optional(VCP_COLOR_TEMPERATURE_INCREMENT);
+        let color_temperature = self
+            .read_optional(VCP_COLOR_TEMPERATURE_REQUEST)
+            .map(|request| ColorTemperature { request, increment });
+        let gain = self.read_rgb(VCP_VIDEO_GAIN);
+        let black_level = self.read_rgb(VCP_BLACK_LEVEL);
+        Ok(ColorSettings {
+            preset,
+            color_temperature,
+            gain,
+            black_level,
+        })
+    }
+
+    /// Applies color calibration settings as a whole: if any write fails, the settings read before
+    /// applying are written back on a best effort basis and the error is returned.
+    pub fn apply_color_settings(&mut self, settings: &ColorSettings) -> Result<(), Error> {
+        let previous = self.color_settings()?;
+        for (code, value) in settings.writes() {
+            self.sleep();
+            if let Err(error) = self.set_vcp_feature(code, value) {
+                for (code, value) in previous.writes() {
+                    self.sleep();
+                    let _ = self.set_vcp_feature(code, value);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_optional(&mut self, code: FeatureCode) -> Option<u16> {
+        self.sleep();
+        self.get_vcp_feature(code).ok().map(|value| value.value())
+    }
+
+    fn read_rgb(&mut self, codes: [FeatureCode; 3]) -> Option<RgbValues> {
+        Some(RgbValues {
+            red: self.read_optional(codes[0])?,
+            green: self.read_optional(codes[1])?,
+            blue: self.read_optional(codes[2])?,
+        })
+    }
+}
@@ -0,0 +1,195 @@
+#![deny(missing_docs)]
+
+#[cfg(target_os = "macos")]
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::commands::{Command, CommandResult};
+use ddc::ErrorCode;
+#[cfg(target_os = "macos")]
+use ddc::{DdcCommand, DdcHost};
+use std::fmt;
+
+/// Get Timing Report request (opcode 0x07)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TimingReportRequest;
+
+impl Command for TimingReportRequest {
+    type Ok = TimingReport;
+    const MIN_LEN: usize = 1;
+    const MAX_LEN: usize = 1;
+    const DELAY_RESPONSE_MS: u64 = 40;
+    const DELAY_COMMAND_MS: u64 = 50;
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, ErrorCode> {
+        if data.is_empty() {
+            return Err(ErrorCode::InvalidLength);
+        }
+        data[0] = 0x07;
+        Ok(1)
+    }
+}
+
+/// Sync status flags of a [TimingReport]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TimingStatus(pub u8);
+
+impl TimingStatus {
+    /// Sync frequency is out of the range the monitor supports
+    pub fn out_of_range(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// Sync timing is unstable
+    pub fn unstable(&self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// Horizontal sync polarity is positive
+    pub fn positive_horizontal_sync(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    /// Vertical sync polarity is positive
+    pub fn positive_vertical_sync(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+}
+
+impl fmt::Display for TimingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let polarity = |positive| if positive { '+' } else { '-' };
+        write!(
+            f,
+            "{}{}, H sync {}, V sync {}",
+            if self.out_of_range() {
+                "out of range"
+            } else {
+                "in range"
+            },
+            if self.unstable() { ", unstable" } else { "" },
+            polarity(self.positive_horizontal_sync()),
+            polarity(self.positive_vertical_sync()),
+        )
+    }
+}
+
+/// Timing Report reply (opcode 0x4E): the video timing the monitor currently sees
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TimingReport {
+    /// Sync status flags
+    pub status: TimingStatus,
+    /// Horizontal frequency in units of 10 Hz
+    pub horizontal_frequency: u16,
+    /// Vertical frequency in units of 0.01 Hz
+    pub vertical_frequency: u16,
+}
+
+impl TimingReport {
+    /// Horizontal frequency in kHz
+    pub fn horizontal_frequency_khz(&self) -> f64 {
+        f64::from(self.horizontal_frequency) / 100.0
+    }
+
+    /// Vertical frequency in Hz
+    pub fn vertical_frequency_hz(&self) -> f64 {
+        f64::from(self.vertical_frequency) / 100.0
+    }
+}
+
+impl CommandResult for TimingReport {
+    const MAX_LEN: usize = 6;
+
+    fn decode(data: &[u8]) -> Result<Self, ErrorCode> {
+        if data.len() != Self::MAX_LEN {
+            return Err(ErrorCode::InvalidLength);
+        }
+        if data[0] != 0x4e {
+            return Err(ErrorCode::InvalidOpcode);
+        }
+        Ok(TimingReport {
+            status: TimingStatus(data[1]),
+            horizontal_frequency: u16::from_be_bytes([data[2], data[3]]),
+            vertical_frequency: u16::from_be_bytes([data[4], data[5]]),
+        })
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} kHz / {:.2} Hz ({})",
+            self.horizontal_frequency_khz(),
+            self.vertical_frequency_hz(),
+            self.status
+        )
+    }
+}
+
+/// Identification Request (opcode 0xF1)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IdentificationRequest;
+
+impl Command for IdentificationRequest {
+    type Ok = IdentificationReply;
+    const MIN_LEN: usize = 1;
+    const MAX_LEN: usize = 1;
+    const DELAY_RESPONSE_MS: u64 = 40;
+    const DELAY_COMMAND_MS: u64 = 50;
+
+    fn len(&self) -> usize {
+        1
+    }
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, ErrorCode> {
+        if data.is_empty() {
+            return Err(ErrorCode::InvalidLength);
+        }
+        data[0] = 0xf1;
+        Ok(1)
+    }
+}
+
+/// Identification Reply (opcode 0xE1): display identification data
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdentificationReply {
+    /// Identification data as sent by the monitor
+    pub data: Vec<u8>,
+}
+
+impl CommandResult for IdentificationReply {
+    const MAX_LEN: usize = 33;
+
+    fn decode(data: &[u8]) -> Result<Self, ErrorCode> {
+        if data.is_empty() || data.len() > Self::MAX_LEN {
+            return Err(ErrorCode::InvalidLength);
+        }
+        if data[0] != 0xe1 {
+            return Err(ErrorCode::InvalidOpcode);
+        }
+        Ok(IdentificationReply {
+            data: data[1..].to_vec(),
+        })
+    }
+}
+
+/// Diagnostic DDC/CI requests
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Requests the video timing the monitor currently sees
+    pub fn timing_report(&mut self) -> Result<TimingReport, Error> {
+        self.sleep();
+        self.execute(TimingReportRequest)
+    }
+
+    /// Requests the monitor's identification data
+    pub fn identification(&mut self) -> Result<IdentificationReply, Error> {
+        self.sleep();
+        self.execute(IdentificationRequest)
+    }
+}
@@ -0,0 +1,472 @@
+#![deny(missing_docs)]
+
+#[cfg(target_os = "macos")]
+use crate::catalog::mccs_value;
+use crate::catalog::vcp_feature;
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+#[cfg(target_os = "macos")]
+use ddc::{Ddc, DdcHost};
+use ddc::{FeatureCode, VcpValue};
+use std::fmt;
+
+/// VCP code of the luminance (brightness) control
+pub const VCP_BRIGHTNESS: FeatureCode = 0x10;
+/// VCP code of the contrast control
+pub const VCP_CONTRAST: FeatureCode = 0x12;
+/// VCP code of the color preset selection
+pub const VCP_COLOR_PRESET: FeatureCode = 0x14;
+/// VCP code of the input source selection
+pub const VCP_INPUT_SOURCE: FeatureCode = 0x60;
+/// VCP code of the speaker volume control
+pub const VCP_VOLUME: FeatureCode = 0x62;
+/// VCP code of the audio mute control
+pub const VCP_AUDIO_MUTE: FeatureCode = 0x8d;
+/// VCP code of the power mode control
+pub const VCP_POWER_MODE: FeatureCode = 0xd6;
+
+/// Current and maximum values of a continuous VCP feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContinuousValue {
+    /// Current value
+    pub value: u16,
+    /// Maximum value reported by the monitor
+    pub maximum: u16,
+}
+
+impl ContinuousValue {
+    /// Current value as a percentage of the maximum, rounded to the nearest integer
+    pub fn percent(&self) -> u8 {
+        if self.maximum == 0 {
+            return 0;
+        }
+        ((u32::from(self.value) * 100 + u32::from(self.maximum) / 2) / u32::from(self.maximum)).min(100) as u8
+    }
+
+    /// Raw value corresponding to `percent` of the maximum, rounded to the nearest integer.
+    /// Returns `None` if `percent` is above 100.
+    pub fn value_for_percent(&self, percent: u8) -> Option<u16> {
+        if percent > 100 {
+            return None;
+        }
+        Some(((u32::from(percent) * u32::from(self.maximum) + 50) / 100) as u16)
+    }
+}
+
+impl From<VcpValue> for ContinuousValue {
+    fn from(value: VcpValue) -> Self {
+        ContinuousValue {
+            value: value.value(),
+            maximum: value.maximum(),
+        }
+    }
+}
+
+impl fmt::Display for ContinuousValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {}", self.value, self.maximum)
+    }
+}
+
+/// A VCP feature value read from a monitor, in a form that can be stored or serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureValue {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// VCP type code reported by the monitor
+    pub ty: u8,
+    /// Current value
+    pub value: u16,
+    /// Maximum value reported by the monitor
+    pub maximum: u16,
+}
+
+impl FeatureValue {
+    /// Value of the VCP feature `code` as read from the monitor
+    pub fn new(code: FeatureCode, value: &VcpValue) -> Self {
+        FeatureValue {
+            code,
+            ty: value.ty,
+            value: value.value(),
+            maximum: value.maximum(),
+        }
+    }
+
+    /// Value in the form returned by [Ddc::get_vcp_feature]
+    pub fn vcp_value(&self) -> VcpValue {
+        VcpValue {
+            ty: self.ty,
+            mh: (self.maximum >> 8) as u8,
+            ml: self.maximum as u8,
+            sh: (self.value >> 8) as u8,
+            sl: self.value as u8,
+        }
+    }
+}
+
+/// Value to write to a VCP feature, as given by a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueArgument {
+    /// Value to write as is
+    Absolute(u16),
+    /// Amount added to the current value
+    Relative(i32),
+}
+
+impl ValueArgument {
+    /// Parses a value of the VCP feature `code`: a decimal or `0x` prefixed hex number, a value
+    /// name of the feature, or a signed amount like `+10` or `-10`
+    pub fn parse(code: FeatureCode, s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidArgument(format!("invalid value {:?} for VCP feature 0x{:02x}", s, code));
+        if s.starts_with(['+', '-']) {
+            return s.parse().map(ValueArgument::Relative).map_err(|_| invalid());
+        }
+        let value = match s.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        };
+        value
+            .or_else(|| vcp_feature(code)?.value_by_name(s).map(u16::from))
+            .map(ValueArgument::Absolute)
+            .ok_or_else(invalid)
+    }
+
+    /// Value to write given the `current` one. Relative values are kept between zero and the
+    /// maximum the monitor reports.
+    pub fn resolve(self, current: &VcpValue) -> u16 {
+        match self {
+            ValueArgument::Absolute(value) => value,
+            ValueArgument::Relative(amount) => {
+                let maximum = match current.maximum() {
+                    0 => u16::MAX,
+                    maximum => maximum,
+                };
+                (i32::from(current.value()) + amount).clamp(0, i32::from(maximum)) as u16
+            }
+        }
+    }
+}
+
+/// A value given as a number, or as text as parsed by [ValueArgument::parse]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum ValueParam {
+    /// Value to write
+    Value(u16),
+    /// Value name or relative amount, such as `hdmi-1` or `+10`
+    Text(String),
+}
+
+impl ValueParam {
+    /// Value to write to the VCP feature `code`
+    pub fn argument(&self, code: FeatureCode) -> Result<ValueArgument, Error> {
+        match self {
+            ValueParam::Value(value) => Ok(ValueArgument::Absolute(*value)),
+            ValueParam::Text(text) => ValueArgument::parse(code, text),
+        }
+    }
+}
+
+/// Declares an enumeration of the values of a non-continuous VCP feature
+macro_rules! vcp_values {
+    (
+        $(#[$meta:meta])*
+        $name:ident($code:expr) {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A value not defined by the MCCS specification
+            Other(u16),
+        }
+
+        impl $name {
+            /// VCP feature code this value belongs to
+            pub const CODE: FeatureCode = $code;
+
+            /// Raw VCP value
+            pub fn value(self) -> u16 {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<u16> for $name {
+            fn from(value: u16) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    value => $name::Other(value),
+                }
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(value: $name) -> Self {
+                value.value()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match vcp_feature(Self::CODE).and_then(|feature| feature.value_name(self.value())) {
+                    Some(name) if !matches!(self, $name::Other(_)) => write!(f, "{}", name),
+                    _ => write!(f, "0x{:02x}", self.value()),
+                }
+            }
+        }
+    };
+}
+
+vcp_values! {
+    /// Input source (VCP code 0x60)
+    InputSource(VCP_INPUT_SOURCE) {
+        /// Analog video (R/G/B) 1
+        Vga1 = 0x01,
+        /// Analog video (R/G/B) 2
+        Vga2 = 0x02,
+        /// Digital video (TMDS) 1
+        Dvi1 = 0x03,
+        /// Digital video (TMDS) 2
+        Dvi2 = 0x04,
+        /// Composite video 1
+        Composite1 = 0x05,
+        /// Composite video 2
+        Composite2 = 0x06,
+        /// S-Video 1
+        SVideo1 = 0x07,
+        /// S-Video 2
+        SVideo2 = 0x08,
+        /// Tuner 1
+        Tuner1 = 0x09,
+        /// Tuner 2
+        Tuner2 = 0x0a,
+        /// Tuner 3
+        Tuner3 = 0x0b,
+        /// Component video (YPrPb / YCrCb) 1
+        Component1 = 0x0c,
+        /// Component video (YPrPb / YCrCb) 2
+        Component2 = 0x0d,
+        /// Component video (YPrPb / YCrCb) 3
+        Component3 = 0x0e,
+        /// DisplayPort 1
+        DisplayPort1 = 0x0f,
+        /// DisplayPort 2
+        DisplayPort2 = 0x10,
+        /// HDMI 1
+        Hdmi1 = 0x11,
+        /// HDMI 2
+        Hdmi2 = 0x12,
+    }
+}
+
+vcp_values! {
+    /// Display power mode (VCP code 0xD6)
+    PowerMode(VCP_POWER_MODE) {
+        /// Display on
+        On = 0x01,
+        /// DPM standby
+        Standby = 0x02,
+        /// DPM suspend
+        Suspend = 0x03,
+        /// DPM off
+        Off = 0x04,
+        /// Powered off with the power button
+        PowerOff = 0x05,
+    }
+}
+
+vcp_values! {
+    /// Color preset (VCP code 0x14)
+    ColorPreset(VCP_COLOR_PRESET) {
+        /// sRGB
+        Srgb = 0x01,
+        /// Display native
+        Native = 0x02,
+        /// 4000 K
+        Kelvin4000 = 0x03,
+        /// 5000 K
+        Kelvin5000 = 0x04,
+        /// 6500 K
+        Kelvin6500 = 0x05,
+        /// 7500 K
+        Kelvin7500 = 0x06,
+        /// 8200 K
+        Kelvin8200 = 0x07,
+        /// 9300 K
+        Kelvin9300 = 0x08,
+        /// 10000 K
+        Kelvin10000 = 0x09,
+        /// 11500 K
+        Kelvin11500 = 0x0a,
+        /// User defined 1
+        User1 = 0x0b,
+        /// User defined 2
+        User2 = 0x0c,
+        /// User defined 3
+        User3 = 0x0d,
+    }
+}
+
+/// Typed access to the most common monitor settings
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Reads a continuous VCP feature. The maximum is corrected if the monitor is known to report a wrong one.
+    pub fn continuous_value(&mut self, code: FeatureCode) -> Result<ContinuousValue, Error> {
+        self.sleep();
+        let mut value: ContinuousValue = self.get_vcp_feature(code)?.into();
+        if let Some(maximum) = self.quirks().maximum(code) {
+            value.maximum = maximum;
+        }
+        Ok(value)
+    }
+
+    /// Sets a continuous VCP feature, making sure the value does not exceed the reported maximum
+    pub fn set_continuous_value(&mut self, code: FeatureCode, value: u16) -> Result<(), Error> {
+        let current = self.continuous_value(code)?;
+        if value > current.maximum {
+            return Err(Error::ValueOutOfRange {
+                code,
+                value,
+                maximum: current.maximum,
+            });
+        }
+        self.sleep();
+        self.set_vcp_feature(code, value)
+    }
+
+    /// Sets a continuous VCP feature to a percentage of its reported maximum. Returns the raw value written.
+    pub fn set_continuous_percent(&mut self, code: FeatureCode, percent: u8) -> Result<u16, Error> {
+        let value = self
+            .continuous_value(code)?
+            .value_for_percent(percent)
+            .ok_or(Error::ValueOutOfRange {
+                code,
+                value: percent.into(),
+                maximum: 100,
+            })?;
+        self.sleep();
+        self.set_vcp_feature(code, value)?;
+        Ok(value)
+    }
+
+    /// Sets a non-continuous VCP feature, making sure the value is one the monitor advertises in its
+    /// capabilities. If the capabilities cannot be read, the value is written without validation.
+    pub fn set_non_continuous_value(&mut self, code: FeatureCode, value: u16) -> Result<(), Error> {
+        if let Ok(capabilities) = self.cached_capabilities() {
+            capabilities.check_value(code, value)?;
+        }
+        self.sleep();
+        self.set_vcp_feature(code, value)
+    }
+
+    /// Current brightness
+    pub fn brightness(&mut self) -> Result<ContinuousValue, Error> {
+        self.continuous_value(VCP_BRIGHTNESS)
+    }
+
+    /// Sets the brightness to a raw value
+    pub fn set_brightness(&mut self, value: u16) -> Result<(), Error> {
+        self.set_continuous_value(VCP_BRIGHTNESS, value)
+    }
+
+    /// Sets the brightness to a percentage of its maximum
+    pub fn set_brightness_percent(&mut self, percent: u8) -> Result<u16, Error> {
+        self.set_continuous_percent(VCP_BRIGHTNESS, percent)
+    }
+
+    /// Current contrast
+    pub fn contrast(&mut self) -> Result<ContinuousValue, Error> {
+        self.continuous_value(VCP_CONTRAST)
+    }
+
+    /// Sets the contrast to a raw value
+    pub fn set_contrast(&mut self, value: u16) -> Result<(), Error> {
+        self.set_continuous_value(VCP_CONTRAST, value)
+    }
+
+    /// Sets the contrast to a percentage of its maximum
+    pub fn set_contrast_percent(&mut self, percent: u8) -> Result<u16, Error> {
+        self.set_continuous_percent(VCP_CONTRAST, percent)
+    }
+
+    /// Current speaker volume
+    pub fn volume(&mut self) -> Result<ContinuousValue, Error> {
+        self.continuous_value(VCP_VOLUME)
+    }
+
+    /// Sets the speaker volume to a raw value
+    pub fn set_volume(&mut self, value: u16) -> Result<(), Error> {
+        self.set_continuous_value(VCP_VOLUME, value)
+    }
+
+    /// Sets the speaker volume to a percentage of its maximum
+    pub fn set_volume_percent(&mut self, percent: u8) -> Result<u16, Error> {
+        self.set_continuous_percent(VCP_VOLUME, percent)
+    }
+
+    /// Returns `true` if the audio is muted
+    pub fn is_muted(&mut self) -> Result<bool, Error> {
+        self.sleep();
+        Ok(self.get_vcp_feature(VCP_AUDIO_MUTE)?.value() == 0x01)
+    }
+
+    /// Mutes or unmutes the audio
+    pub fn set_muted(&mut self, muted: bool) -> Result<(), Error> {
+        self.set_non_continuous_value(VCP_AUDIO_MUTE, if muted { 0x01 } else { 0x02 })
+    }
+
+    /// Currently selected input source, interpreted according to the [MCCS version](Monitor::mccs_version)
+    pub fn input_source(&mut self) -> Result<InputSource, Error> {
+        let version = self.mccs_version().ok();
+        self.sleep();
+        let value = self.get_vcp_feature(VCP_INPUT_SOURCE)?.value();
+        let value = self.quirks().decode_value(VCP_INPUT_SOURCE, value);
+        Ok(mccs_value(VCP_INPUT_SOURCE, value, version).into())
+    }
+
+    /// Selects an input source
+    pub fn set_input_source(&mut self, input: InputSource) -> Result<(), Error> {
+        if let Ok(capabilities) = self.cached_capabilities() {
+            capabilities.check_value(VCP_INPUT_SOURCE, input.value())?;
+        }
+        let value = self.quirks().encode_value(VCP_INPUT_SOURCE, input.value());
+        self.sleep();
+        self.set_vcp_feature(VCP_INPUT_SOURCE, value)
+    }
+
+    /// Current power mode, interpreted according to the [MCCS version](Monitor::mccs_version)
+    pub fn power_mode(&mut self) -> Result<PowerMode, Error> {
+        let version = self.mccs_version().ok();
+        self.sleep();
+        let value = self.get_vcp_feature(VCP_POWER_MODE)?.value();
+        Ok(mccs_value(VCP_POWER_MODE, value, version).into())
+    }
+
+    /// Sets the power mode
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Error> {
+        self.set_non_continuous_value(VCP_POWER_MODE, mode.value())
+    }
+
+    /// Currently selected color preset, interpreted according to the [MCCS version](Monitor::mccs_version)
+    pub fn color_preset(&mut self) -> Result<ColorPreset, Error> {
+        let version = self.mccs_version().ok();
+        self.sleep();
+        let value = self.get_vcp_feature(VCP_COLOR_PRESET)?.value();
+        Ok(mccs_value(VCP_COLOR_PRESET, value, version).into())
+    }
+
+    /// Selects a color preset
+    pub fn set_color_preset(&mut self, preset: ColorPreset) -> Result<(), Error> {
+        self.set_non_continuous_value(VCP_COLOR_PRESET, preset.value())
+    }
+}
@@ -0,0 +1,169 @@
+#![deny(missing_docs)]
+
+use super::protocol::*;
+use crate::backend::ListedDisplay;
+use crate::capabilities::Capabilities;
+use crate::controls::{FeatureValue, ValueParam};
+use crate::error::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+/// Connection to a running daemon, see [Daemon](crate::Daemon).
+///
+/// Notifications that arrive while waiting for a response are kept until they are read with
+/// [DaemonClient::next_notification].
+#[derive(Debug)]
+pub struct DaemonClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+    notifications: VecDeque<RpcNotification>,
+}
+
+impl DaemonClient {
+    /// Connects to the daemon listening on the socket `path`
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let writer = UnixStream::connect(path).map_err(Error::DaemonConnection)?;
+        let reader = BufReader::new(writer.try_clone().map_err(Error::DaemonConnection)?);
+        Ok(DaemonClient {
+            reader,
+            writer,
+            next_id: 1,
+            notifications: VecDeque::new(),
+        })
+    }
+
+    /// Calls a method of the daemon and waits for its result
+    pub fn call<P: Serialize, R: DeserializeOwned>(&mut self, method: &str, params: P) -> Result<R, Error> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let params = serde_json::to_value(params).map_err(|error| Error::InvalidArgument(error.to_string()))?;
+        let mut line = serde_json::to_string(&RpcRequest::new(id, method, params)).expect("requests serialize");
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(Error::DaemonConnection)?;
+        self.reader
+            .get_ref()
+            .set_read_timeout(None)
+            .map_err(Error::DaemonConnection)?;
+        loop {
+            let message = self.read_message()?;
+            if message.get("method").is_some() {
+                self.notifications.push_back(parse_message(message)?);
+                continue;
+            }
+            let response: RpcResponse = parse_message(message)?;
+            if response.id != id {
+                continue;
+            }
+            if let Some(error) = response.error {
+                return Err(error.into());
+            }
+            return parse_message(response.result.unwrap_or(Value::Null));
+        }
+    }
+
+    /// Lists the displays of the daemon
+    pub fn list(&mut self) -> Result<Vec<ListedDisplay>, Error> {
+        self.call("list", Value::Null)
+    }
+
+    /// Reads a VCP feature, possibly from the cache of the daemon unless `refresh` is set
+    pub fn get(&mut self, display: Option<&str>, code: FeatureParam, refresh: bool) -> Result<FeatureValue, Error> {
+        self.call(
+            "get",
+            GetParams {
+                display: display.map(Into::into),
+                code,
+                refresh,
+            },
+        )
+    }
+
+    /// Writes a VCP feature and returns the value the monitor reports afterwards
+    pub fn set(&mut self, display: Option<&str>, code: FeatureParam, value: ValueParam) -> Result<FeatureValue, Error> {
+        self.call(
+            "set",
+            SetParams {
+                display: display.map(Into::into),
+                code,
+                value,
+            },
+        )
+    }
+
+    /// Reads the capabilities of a display
+    pub fn capabilities(&mut self, display: Option<&str>) -> Result<Capabilities, Error> {
+        self.call(
+            "capabilities",
+            DisplayParams {
+                display: display.map(Into::into),
+            },
+        )
+    }
+
+    /// Watches VCP features of a display. Returns the subscription ID found in the notifications.
+    pub fn subscribe(&mut self, display: Option<&str>, codes: Vec<FeatureParam>) -> Result<u64, Error> {
+        let result: SubscriptionParams = self.call(
+            "subscribe",
+            SubscribeParams {
+                display: display.map(Into::into),
+                codes,
+            },
+        )?;
+        Ok(result.subscription)
+    }
+
+    /// Stops a subscription. Returns `false` if there was no such subscription.
+    pub fn unsubscribe(&mut self, subscription: u64) -> Result<bool, Error> {
+        self.call("unsubscribe", SubscriptionParams { subscription })
+    }
+
+    /// Waits up to `timeout` for a change of a watched value. Returns `None` if there was none.
+    pub fn next_notification(&mut self, timeout: Duration) -> Result<Option<ChangeNotification>, Error> {
+        loop {
+            let notification = match self.notifications.pop_front() {
+                Some(notification) => notification,
+                None => {
+                    self.reader
+                        .get_ref()
+                        .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))
+                        .map_err(Error::DaemonConnection)?;
+                    match self.read_message() {
+                        Ok(message) if message.get("method").is_some() => parse_message(message)?,
+                        // A response to nothing we are waiting for
+                        Ok(_) => continue,
+                        Err(Error::DaemonConnection(error))
+                            if matches!(error.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+                        {
+                            return Ok(None)
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
+            };
+            if notification.method == CHANGED_NOTIFICATION {
+                return parse_message(notification.params).map(Some);
+            }
+        }
+    }
+
+    fn read_message(&mut self) -> Result<Value, Error> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).map_err(Error::DaemonConnection)? == 0 {
+            return Err(Error::DaemonConnection(io::ErrorKind::UnexpectedEof.into()));
+        }
+        serde_json::from_str(&line).map_err(|error| Error::DaemonConnection(error.into()))
+    }
+}
+
+fn parse_message<T: DeserializeOwned>(message: Value) -> Result<T, Error> {
+    serde_json::from_value(message).map_err(|error| Error::DaemonConnection(error.into()))
+}
@@ -0,0 +1,7 @@
+mod client;
+mod protocol;
+mod server;
+
+pub use client::*;
+pub use protocol::*;
+pub use server::*;
@@ -0,0 +1,220 @@
+#![deny(missing_docs)]
+
+use crate::catalog::parse_feature_code;
+use crate::controls::{FeatureValue, ValueParam};
+use crate::error::Error;
+use ddc::FeatureCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// JSON-RPC version spoken by the daemon
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// JSON-RPC error code of a request that is not valid JSON
+pub const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code of a request that is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code of an unknown method
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of invalid method parameters, including unknown monitors and features
+pub const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code of a failed DDC/CI operation
+pub const MONITOR_ERROR: i64 = -32000;
+
+/// Method name of the notification sent to subscribers when a value changes, see [ChangeNotification]
+pub const CHANGED_NOTIFICATION: &str = "changed";
+
+/// A JSON-RPC request, or a notification if it has no `id`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcRequest {
+    /// Always [JSONRPC_VERSION]
+    pub jsonrpc: String,
+    /// Request ID, echoed in the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    /// Method name
+    pub method: String,
+    /// Method parameters
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    /// Creates a request
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        RpcRequest {
+            jsonrpc: JSONRPC_VERSION.into(),
+            id: Some(id.into()),
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC response, holding either a result or an error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcResponse {
+    /// Always [JSONRPC_VERSION]
+    pub jsonrpc: String,
+    /// ID of the request, `null` if it could not be read
+    pub id: Value,
+    /// Result of a successful call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Error of a failed call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    /// Creates the response to a request
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        RpcResponse {
+            jsonrpc: JSONRPC_VERSION.into(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Error of a failed JSON-RPC call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    /// Error code, such as [MONITOR_ERROR]
+    pub code: i64,
+    /// Error message
+    pub message: String,
+}
+
+impl RpcError {
+    /// Creates an error
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Rpc { code, message } => RpcError { code, message },
+            Error::InvalidArgument(_) | Error::MonitorNotFound(_) | Error::AmbiguousMonitor(_) => {
+                RpcError::new(INVALID_PARAMS, error.to_string())
+            }
+            error => RpcError::new(MONITOR_ERROR, error.to_string()),
+        }
+    }
+}
+
+impl From<RpcError> for Error {
+    fn from(error: RpcError) -> Self {
+        Error::Rpc {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
+/// A JSON-RPC notification sent by the daemon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcNotification {
+    /// Always [JSONRPC_VERSION]
+    pub jsonrpc: String,
+    /// Notification name, such as [CHANGED_NOTIFICATION]
+    pub method: String,
+    /// Notification parameters
+    pub params: Value,
+}
+
+/// A VCP feature given by its code or name, see [parse_feature_code]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FeatureParam {
+    /// Numeric VCP code
+    Code(FeatureCode),
+    /// Feature name or hex code
+    Name(String),
+}
+
+impl FeatureParam {
+    /// VCP code of the feature
+    pub fn code(&self) -> Result<FeatureCode, Error> {
+        match self {
+            FeatureParam::Code(code) => Ok(*code),
+            FeatureParam::Name(name) => parse_feature_code(name),
+        }
+    }
+}
+
+/// Parameters of the methods addressing a single monitor, such as `capabilities`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayParams {
+    /// Monitor selector, see [MonitorSelector](crate::MonitorSelector); the first monitor if left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+/// Parameters of the `get` method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetParams {
+    /// Monitor selector, see [DisplayParams::display]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// Feature to read
+    pub code: FeatureParam,
+    /// Read the monitor even if the cached value is still fresh
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Parameters of the `set` method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetParams {
+    /// Monitor selector, see [DisplayParams::display]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// Feature to write
+    pub code: FeatureParam,
+    /// Value to write
+    pub value: ValueParam,
+}
+
+/// Parameters of the `subscribe` method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscribeParams {
+    /// Monitor selector, see [DisplayParams::display]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// Features to watch
+    pub codes: Vec<FeatureParam>,
+}
+
+/// Parameters of the `unsubscribe` method, and result of the `subscribe` method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionParams {
+    /// Subscription ID
+    pub subscription: u64,
+}
+
+/// Parameters of the [CHANGED_NOTIFICATION] sent when a watched value changes, whether through the
+/// daemon or on the monitor itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeNotification {
+    /// Subscription the change was watched by
+    pub subscription: u64,
+    /// Position of the monitor in the `list` result, starting at 1
+    pub display: usize,
+    /// Persistent ID of the monitor
+    pub id: String,
+    /// Previously known value, if any
+    pub previous: Option<u16>,
+    /// New value
+    pub value: FeatureValue,
+}
@@ -0,0 +1,490 @@
+#![deny(missing_docs)]
+
+use super::protocol::*;
+#[cfg(target_os = "macos")]
+use crate::backend::MonitorBackend;
+use crate::backend::{select_display, DisplayBackend, DisplayDescription, ListedDisplay, MonitorSelector};
+use crate::capabilities::Capabilities;
+use crate::controls::{FeatureValue, ValueArgument};
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::FeatureCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Name of the daemon socket in the temporary directory, see [default_socket_path]
+pub const DEFAULT_SOCKET_NAME: &str = "ddc-macos.sock";
+
+/// Socket the daemon listens on unless told otherwise: [DEFAULT_SOCKET_NAME] in the per-user
+/// temporary directory
+pub fn default_socket_path() -> PathBuf {
+    std::env::temp_dir().join(DEFAULT_SOCKET_NAME)
+}
+
+/// Tuning of a [Daemon]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DaemonOptions {
+    /// How long a value read from a monitor is served from the cache
+    pub value_ttl: Duration,
+    /// Interval between two reads of the subscribed values, to notice changes made on the monitors
+    pub poll_interval: Duration,
+}
+
+impl Default for DaemonOptions {
+    fn default() -> Self {
+        DaemonOptions {
+            value_ttl: Duration::from_millis(500),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Work sent to the thread owning a bus
+type Job = Box<dyn FnOnce(&mut dyn DisplayBackend) + Send>;
+
+/// A display of the daemon: the bus it is on and its position on that bus
+#[derive(Debug)]
+struct DaemonDisplay {
+    bus: usize,
+    index: usize,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    id: u64,
+    session: u64,
+    display: usize,
+    codes: Vec<FeatureCode>,
+    sink: mpsc::Sender<RpcNotification>,
+}
+
+/// A client connection of a [Daemon]: the notifications of its subscriptions are sent to the
+/// receiver returned along with it by [Daemon::open_session]
+#[derive(Debug)]
+pub struct Session {
+    id: u64,
+    sink: mpsc::Sender<RpcNotification>,
+}
+
+/// Owner of all displays, shared by any number of clients.
+///
+/// Every bus is driven by a thread of its own, so that commands to monitors on the same bus never
+/// collide while monitors on different buses are served concurrently. Values are cached for
+/// [DaemonOptions::value_ttl] and capabilities for the lifetime of the daemon. Requests are
+/// JSON-RPC 2.0 calls of the methods `list`, `get`, `set`, `capabilities`, `subscribe` and
+/// `unsubscribe`, see [RpcRequest] and the parameter types in this module.
+#[derive(Debug)]
+pub struct Daemon {
+    buses: Vec<Mutex<mpsc::Sender<Job>>>,
+    displays: Vec<DaemonDisplay>,
+    descriptions: Vec<DisplayDescription>,
+    values: Mutex<HashMap<(usize, FeatureCode), (FeatureValue, Instant)>>,
+    capabilities: Mutex<HashMap<usize, Capabilities>>,
+    subscriptions: Mutex<Vec<Subscription>>,
+    next_id: AtomicU64,
+    options: DaemonOptions,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Daemon {
+    /// Creates a daemon driving one backend per bus
+    pub fn new<B>(backends: Vec<B>, options: DaemonOptions) -> Result<Self, Error>
+    where
+        B: DisplayBackend + Send + 'static,
+    {
+        let mut buses = Vec::new();
+        let mut displays = Vec::new();
+        let mut descriptions = Vec::new();
+        for (bus, mut backend) in backends.into_iter().enumerate() {
+            for (index, description) in backend.displays()?.into_iter().enumerate() {
+                displays.push(DaemonDisplay { bus, index });
+                descriptions.push(description);
+            }
+            let (sender, jobs) = mpsc::channel::<Job>();
+            thread::spawn(move || {
+                for job in jobs {
+                    job(&mut backend);
+                }
+            });
+            buses.push(Mutex::new(sender));
+        }
+        Ok(Daemon {
+            buses,
+            displays,
+            descriptions,
+            values: Mutex::new(HashMap::new()),
+            capabilities: Mutex::new(HashMap::new()),
+            subscriptions: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            options,
+        })
+    }
+
+    /// Creates a daemon driving the given monitors, grouped by bus
+    #[cfg(target_os = "macos")]
+    pub fn with_monitors(monitors: Vec<Monitor>, options: DaemonOptions) -> Result<Self, Error> {
+        Daemon::new(MonitorBackend::new(monitors).into_buses(), options)
+    }
+
+    /// Displays driven by this daemon
+    pub fn displays(&self) -> Vec<ListedDisplay> {
+        ListedDisplay::list(self.descriptions.clone())
+    }
+
+    /// Picks a display by a selector, see [MonitorSelector]; the first display without one
+    pub fn select(&self, selector: Option<&str>) -> Result<usize, Error> {
+        let selector = selector.map(str::parse::<MonitorSelector>).transpose()?;
+        select_display(&self.descriptions, selector.as_ref())
+    }
+
+    /// Runs `operation` on the thread of the bus of `display`, with the position of the display
+    /// within that bus, and waits for its result
+    fn on_bus<T, F>(&self, display: usize, operation: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn DisplayBackend, usize) -> Result<T, Error> + Send + 'static,
+    {
+        let target = self
+            .displays
+            .get(display)
+            .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))?;
+        let index = target.index;
+        let (sender, result) = mpsc::channel();
+        let job: Job = Box::new(move |backend| {
+            let _ = sender.send(operation(backend, index));
+        });
+        let stopped = || Error::MonitorNotFound(format!("display {}: its bus is no longer served", display + 1));
+        lock(&self.buses[target.bus]).send(job).map_err(|_| stopped())?;
+        result.recv().map_err(|_| stopped())?
+    }
+
+    /// Reads a VCP feature, from the cache if it was read less than [DaemonOptions::value_ttl]
+    /// ago and `refresh` is not set
+    pub fn get(&self, display: usize, code: FeatureCode, refresh: bool) -> Result<FeatureValue, Error> {
+        if !refresh {
+            if let Some((value, read)) = lock(&self.values).get(&(display, code)) {
+                if read.elapsed() < self.options.value_ttl {
+                    return Ok(*value);
+                }
+            }
+        }
+        let value = self.on_bus(display, move |backend, index| {
+            Ok(FeatureValue::new(code, &backend.get_vcp_feature(index, code)?))
+        })?;
+        self.update(display, value, false);
+        Ok(value)
+    }
+
+    /// Writes a VCP feature and returns the value the monitor reports afterwards. Relative values
+    /// are applied to a fresh read of the current value.
+    pub fn set(&self, display: usize, code: FeatureCode, value: ValueArgument) -> Result<FeatureValue, Error> {
+        // A relative value is read, resolved and written in one bus job, so that no other request
+        // changes the value in between
+        let (current, value) = self.on_bus(display, move |backend, index| {
+            let (current, value) = match value {
+                ValueArgument::Absolute(value) => (None, value),
+                relative => {
+                    let current = backend.get_vcp_feature(index, code)?;
+                    (Some(FeatureValue::new(code, &current)), relative.resolve(&current))
+                }
+            };
+            backend.set_vcp_feature(index, code, value)?;
+            Ok((current, FeatureValue::new(code, &backend.get_vcp_feature(index, code)?)))
+        })?;
+        if let Some(current) = current {
+            self.update(display, current, false);
+        }
+        self.update(display, value, true);
+        Ok(value)
+    }
+
+    /// Returns the parsed capabilities of a display, reading them only on first use
+    pub fn capabilities(&self, display: usize) -> Result<Capabilities, Error> {
+        if let Some(capabilities) = lock(&self.capabilities).get(&display) {
+            return Ok(capabilities.clone());
+        }
+        let capabilities = self.on_bus(display, |backend, index| {
+            Capabilities::parse(&backend.capabilities_string(index)?)
+        })?;
+        lock(&self.capabilities).insert(display, capabilities.clone());
+        Ok(capabilities)
+    }
+
+    /// Caches a value read from or written to a display and notifies the subscribers if it changed.
+    /// A value seen for the first time only counts as a change if it was `written`.
+    fn update(&self, display: usize, value: FeatureValue, written: bool) {
+        let previous = lock(&self.values)
+            .insert((display, value.code), (value, Instant::now()))
+            .map(|(previous, _)| previous.value);
+        if previous == Some(value.value) || (previous.is_none() && !written) {
+            return;
+        }
+        lock(&self.subscriptions).retain(|subscription| {
+            if subscription.display != display || !subscription.codes.contains(&value.code) {
+                return true;
+            }
+            let notification = ChangeNotification {
+                subscription: subscription.id,
+                display: display + 1,
+                id: self.descriptions[display].identity.to_string(),
+                previous,
+                value,
+            };
+            // A failed send means the session is gone
+            subscription.sink.send(notification_of(&notification)).is_ok()
+        });
+    }
+
+    /// Opens a session for a client, see [Session]
+    pub fn open_session(&self) -> (Session, mpsc::Receiver<RpcNotification>) {
+        let (sink, notifications) = mpsc::channel();
+        let session = Session {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            sink,
+        };
+        (session, notifications)
+    }
+
+    /// Closes a session, dropping its subscriptions
+    pub fn close_session(&self, session: Session) {
+        lock(&self.subscriptions).retain(|subscription| subscription.session != session.id);
+    }
+
+    /// Watches VCP features of a display for changes. Their current values are read right away, so
+    /// that later changes can be told apart.
+    pub fn subscribe(&self, session: &Session, display: usize, codes: Vec<FeatureCode>) -> Result<u64, Error> {
+        if display >= self.displays.len() {
+            return Err(Error::MonitorNotFound(format!("display {}", display + 1)));
+        }
+        for code in &codes {
+            let _ = self.get(display, *code, false);
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        lock(&self.subscriptions).push(Subscription {
+            id,
+            session: session.id,
+            display,
+            codes,
+            sink: session.sink.clone(),
+        });
+        Ok(id)
+    }
+
+    /// Stops a subscription. Returns `false` if the session has no such subscription.
+    pub fn unsubscribe(&self, session: &Session, subscription: u64) -> bool {
+        let mut subscriptions = lock(&self.subscriptions);
+        let count = subscriptions.len();
+        subscriptions.retain(|existing| existing.id != subscription || existing.session != session.id);
+        subscriptions.len() != count
+    }
+
+    /// Reads all subscribed values from the monitors, notifying the subscribers of values that were
+    /// changed on the monitors themselves. Values that fail to read are skipped.
+    pub fn poll_subscriptions(&self) {
+        let mut watched: Vec<(usize, FeatureCode)> = lock(&self.subscriptions)
+            .iter()
+            .flat_map(|subscription| subscription.codes.iter().map(|code| (subscription.display, *code)))
+            .collect();
+        watched.sort_unstable();
+        watched.dedup();
+        for (display, code) in watched {
+            let _ = self.get(display, code, true);
+        }
+    }
+
+    /// Handles a request of a session. Returns the response, or `None` for a notification.
+    pub fn handle(&self, session: &Session, request: RpcRequest) -> Option<RpcResponse> {
+        let result = if request.jsonrpc != JSONRPC_VERSION {
+            Err(RpcError::new(INVALID_REQUEST, "unsupported JSON-RPC version"))
+        } else {
+            self.call(session, &request.method, request.params)
+        };
+        request.id.map(|id| RpcResponse::new(id, result))
+    }
+
+    /// Handles a request of a session given as a line of JSON. Returns the response line, or `None`
+    /// for a notification.
+    pub fn handle_line(&self, session: &Session, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Value>(line) {
+            Err(error) => Some(RpcResponse::new(
+                Value::Null,
+                Err(RpcError::new(PARSE_ERROR, error.to_string())),
+            )),
+            Ok(request) => {
+                let id = request.get("id").cloned().unwrap_or(Value::Null);
+                match serde_json::from_value::<RpcRequest>(request) {
+                    Ok(request) => self.handle(session, request),
+                    Err(error) => Some(RpcResponse::new(
+                        id,
+                        Err(RpcError::new(INVALID_REQUEST, error.to_string())),
+                    )),
+                }
+            }
+        };
+        response.map(|response| serde_json::to_string(&response).expect("responses serialize"))
+    }
+
+    fn call(&self, session: &Session, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "list" => to_result(self.displays()),
+            "get" => {
+                let params: GetParams = from_params(params)?;
+                let display = self.select(params.display.as_deref())?;
+                to_result(self.get(display, params.code.code()?, params.refresh)?)
+            }
+            "set" => {
+                let params: SetParams = from_params(params)?;
+                let display = self.select(params.display.as_deref())?;
+                let code = params.code.code()?;
+                to_result(self.set(display, code, params.value.argument(code)?)?)
+            }
+            "capabilities" => {
+                let params: DisplayParams = from_params(params)?;
+                to_result(self.capabilities(self.select(params.display.as_deref())?)?)
+            }
+            "subscribe" => {
+                let params: SubscribeParams = from_params(params)?;
+                let display = self.select(params.display.as_deref())?;
+                let codes = params.codes.iter().map(FeatureParam::code).collect::<Result<_, _>>()?;
+                to_result(SubscriptionParams {
+                    subscription: self.subscribe(session, display, codes)?,
+                })
+            }
+            "unsubscribe" => {
+                let params: SubscriptionParams = from_params(params)?;
+                to_result(self.unsubscribe(session, params.subscription))
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method {:?}", method))),
+        }
+    }
+}
+
+fn from_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Methods without required parameters can be called without any
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|error| RpcError::new(INVALID_PARAMS, error.to_string()))
+}
+
+fn to_result(result: impl Serialize) -> Result<Value, RpcError> {
+    Ok(serde_json::to_value(result).expect("results serialize"))
+}
+
+fn notification_of(change: &ChangeNotification) -> RpcNotification {
+    RpcNotification {
+        jsonrpc: JSONRPC_VERSION.into(),
+        method: CHANGED_NOTIFICATION.into(),
+        params: serde_json::to_value(change).expect("notifications serialize"),
+    }
+}
+
+/// Serves a [Daemon] on a Unix domain socket, one JSON-RPC message per line
+#[derive(Debug)]
+pub struct DaemonServer {
+    daemon: Arc<Daemon>,
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl DaemonServer {
+    /// Listens on the socket `path`. A socket file left behind by a daemon that is no longer
+    /// running is replaced.
+    pub fn bind(daemon: Arc<Daemon>, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() && UnixStream::connect(&path).is_err() {
+            std::fs::remove_file(&path).map_err(Error::DaemonConnection)?;
+        }
+        let listener = UnixListener::bind(&path).map_err(Error::DaemonConnection)?;
+        Ok(DaemonServer { daemon, listener, path })
+    }
+
+    /// Socket path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accepts clients until the listener fails, serving each on a thread of its own, and polls
+    /// the subscribed values every [DaemonOptions::poll_interval]
+    pub fn serve(&self) -> Result<(), Error> {
+        let poller = Arc::downgrade(&self.daemon);
+        let interval = self.daemon.options.poll_interval;
+        thread::spawn(move || poll(poller, interval));
+        for stream in self.listener.incoming() {
+            let stream = stream.map_err(Error::DaemonConnection)?;
+            let daemon = self.daemon.clone();
+            thread::spawn(move || serve_client(&daemon, stream));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for DaemonServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn poll(daemon: Weak<Daemon>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        match daemon.upgrade() {
+            Some(daemon) => daemon.poll_subscriptions(),
+            None => return,
+        }
+    }
+}
+
+fn serve_client(daemon: &Daemon, stream: UnixStream) {
+    let Ok(writer) = stream.try_clone() else {
+        return;
+    };
+    let writer = Arc::new(Mutex::new(writer));
+    let (session, notifications) = daemon.open_session();
+    let notification_writer = writer.clone();
+    // Ends once the session is closed and its subscriptions are dropped
+    thread::spawn(move || {
+        for notification in notifications {
+            let line = serde_json::to_string(&notification).expect("notifications serialize");
+            if write_line(&notification_writer, &line).is_err() {
+                return;
+            }
+        }
+    });
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = daemon.handle_line(&session, &line) {
+            if write_line(&writer, &response).is_err() {
+                break;
+            }
+        }
+    }
+    daemon.close_session(session);
+}
+
+fn write_line(writer: &Mutex<UnixStream>, line: &str) -> io::Result<()> {
+    let mut writer = lock(writer);
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
@@ -0,0 +1,86 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+
+/// Fixed header every EDID base block starts with
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+/// Length of the EDID base block
+const EDID_BLOCK_LENGTH: usize = 128;
+/// Offsets of the four 18 byte descriptors in the base block
+const DESCRIPTOR_OFFSETS: [usize; 4] = [54, 72, 90, 108];
+/// Display descriptor tag of the product name
+const DESCRIPTOR_PRODUCT_NAME: u8 = 0xfc;
+/// Display descriptor tag of the serial number string
+const DESCRIPTOR_SERIAL_NUMBER: u8 = 0xff;
+
+/// Identification fields of an EDID base block
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edid {
+    /// Three letter PNP manufacturer ID, such as `DEL`
+    pub manufacturer: String,
+    /// Manufacturer's product code
+    pub product_code: u16,
+    /// Numeric serial number, zero if not used
+    pub serial: u32,
+    /// Week of manufacture, zero if not specified
+    pub week: u8,
+    /// Year of manufacture, or model year if the week is 0xFF
+    pub year: u16,
+    /// EDID version
+    pub version: u8,
+    /// EDID revision
+    pub revision: u8,
+    /// Product name from the display descriptors
+    pub name: Option<String>,
+    /// Serial number string from the display descriptors
+    pub serial_number: Option<String>,
+}
+
+impl Edid {
+    /// Parses the identification fields of an EDID. Only the base block is read; extension blocks
+    /// are ignored.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < EDID_BLOCK_LENGTH {
+            return Err(Error::InvalidEdid(format!(
+                "{} bytes, expected at least {}",
+                data.len(),
+                EDID_BLOCK_LENGTH
+            )));
+        }
+        if data[..8] != EDID_HEADER {
+            return Err(Error::InvalidEdid("missing header".into()));
+        }
+        let manufacturer = u16::from_be_bytes([data[8], data[9]]);
+        let manufacturer = [10, 5, 0]
+            .iter()
+            .map(|shift| char::from(b'A' - 1 + ((manufacturer >> shift) & 0x1f) as u8))
+            .collect();
+        Ok(Edid {
+            manufacturer,
+            product_code: u16::from_le_bytes([data[10], data[11]]),
+            serial: u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
+            week: data[16],
+            year: 1990 + u16::from(data[17]),
+            version: data[18],
+            revision: data[19],
+            name: descriptor_text(data, DESCRIPTOR_PRODUCT_NAME),
+            serial_number: descriptor_text(data, DESCRIPTOR_SERIAL_NUMBER),
+        })
+    }
+}
+
+/// Text of the first display descriptor with the given tag. The text is at most 13 bytes long and
+/// terminated by a line feed if shorter.
+fn descriptor_text(data: &[u8], tag: u8) -> Option<String> {
+    DESCRIPTOR_OFFSETS
+        .iter()
+        .map(|offset| &data[*offset..*offset + 18])
+        .find(|descriptor| descriptor[..3] == [0, 0, 0] && descriptor[3] == tag)
+        .map(|descriptor| {
+            let text = &descriptor[5..];
+            let end = text.iter().position(|byte| *byte == b'\n').unwrap_or(text.len());
+            String::from_utf8_lossy(&text[..end]).trim().to_string()
+        })
+        .filter(|text| !text.is_empty())
+}
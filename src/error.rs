@@ -1,9 +1,22 @@
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
 use core_graphics::base::CGError;
-use ddc::ErrorCode;
+use ddc::{ErrorCode, FeatureCode};
+#[cfg(target_os = "macos")]
 use io_kit_sys::ret::kIOReturnSuccess;
+#[cfg(target_os = "macos")]
 use mach2::kern_return::{kern_return_t, KERN_FAILURE};
 use thiserror::Error;
 
+// The same types as on MacOS, so that errors look alike on all platforms
+#[cfg(not(target_os = "macos"))]
+type CGError = i32;
+#[cfg(not(target_os = "macos"))]
+#[allow(non_camel_case_types)]
+type kern_return_t = i32;
+#[cfg(not(target_os = "macos"))]
+const KERN_FAILURE: kern_return_t = 5;
+
 /// An error that can occur during DDC/CI communication with a monitor
 #[derive(Error, Debug)]
 pub enum Error {
@@ -22,8 +35,122 @@ pub enum Error {
     /// Display location not found
     #[error("Display location not found")]
     DisplayLocationNotFound,
+    /// Capabilities string could not be parsed
+    #[error("Invalid capabilities string: {0}")]
+    InvalidCapabilities(String),
+    /// Capabilities string could not be read earlier; the failure is kept until `Monitor::clear_cache`
+    #[error("Capabilities could not be read: {0}")]
+    CapabilitiesUnavailable(String),
+    /// Value is not among the values the monitor advertises for a VCP feature
+    #[error("Value 0x{value:04x} is not supported for VCP feature 0x{code:02x}")]
+    UnsupportedValue {
+        /// VCP feature code
+        code: FeatureCode,
+        /// Rejected value
+        value: u16,
+    },
+    /// VCP feature is not supported: the monitor replied so when it was read, or it is not advertised
+    /// in the monitor's capabilities
+    #[error("VCP feature 0x{code:02x} is not supported by the monitor")]
+    UnsupportedFeature {
+        /// VCP feature code
+        code: FeatureCode,
+    },
+    /// Write to a destructive VCP feature that was not unlocked
+    #[error("VCP feature 0x{code:02x} resets monitor settings and has to be unlocked first")]
+    DestructiveFeature {
+        /// VCP feature code
+        code: FeatureCode,
+    },
+    /// Value exceeds the maximum reported by the monitor for a continuous VCP feature
+    #[error("Value {value} exceeds maximum {maximum} of VCP feature 0x{code:02x}")]
+    ValueOutOfRange {
+        /// VCP feature code
+        code: FeatureCode,
+        /// Rejected value
+        value: u16,
+        /// Maximum reported by the monitor
+        maximum: u16,
+    },
+    /// Table read or write failed on a specific fragment
+    #[error("Table fragment at offset {offset} of VCP feature 0x{code:02x} failed: {source}")]
+    TableFragment {
+        /// VCP feature code
+        code: FeatureCode,
+        /// Offset of the failed fragment
+        offset: u16,
+        /// Error of the last attempt
+        #[source]
+        source: Box<Error>,
+    },
+    /// Snapshot was taken from a different monitor model
+    #[error("Snapshot of monitor {snapshot} cannot be restored to monitor {monitor}")]
+    SnapshotMismatch {
+        /// Monitor the snapshot was taken from
+        snapshot: MonitorIdentity,
+        /// Monitor the snapshot was to be restored to
+        monitor: MonitorIdentity,
+    },
+    /// Monitor replied with a null message: it is busy and not ready to answer
+    #[error("Monitor replied with a null message")]
+    NullMessage,
+    /// Monitor did not apply a written value
+    #[error("VCP feature 0x{code:02x} was set to {requested} but the monitor reports {actual}")]
+    VerificationFailed {
+        /// VCP feature code
+        code: FeatureCode,
+        /// Value that was written
+        requested: u16,
+        /// Value the monitor reported after the last attempt
+        actual: u16,
+    },
+    /// Confirmation was given for a different monitor
+    #[error("Confirmation for monitor {confirmed} does not apply to monitor {monitor}")]
+    ConfirmationMismatch {
+        /// Monitor the confirmation was given for
+        confirmed: MonitorIdentity,
+        /// Monitor the operation was to be run on
+        monitor: MonitorIdentity,
+    },
+    /// Confirmation is not for the reset last prepared on the monitor handle
+    #[error("Reset was not prepared on this monitor, or another one was prepared since")]
+    ResetNotPrepared,
+    /// Operation did not complete in time
+    #[error("Timed out after {0:?}")]
+    Timeout(std::time::Duration),
+    /// Configuration file could not be parsed
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(String),
+    /// EDID could not be parsed
+    #[error("Invalid EDID: {0}")]
+    InvalidEdid(String),
+    /// Saved snapshot could not be parsed
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+    /// No monitor matches a selection
+    #[error("No monitor matches {0}")]
+    MonitorNotFound(String),
+    /// Several connected monitors match a selection or have the same identity, e.g. monitors of the
+    /// same model without serial numbers, so that the one asked for cannot be told apart
+    #[error("Several monitors match {0}")]
+    AmbiguousMonitor(String),
+    /// Command line argument could not be understood
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// Daemon answered a request with an error
+    #[error("Daemon error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code
+        code: i64,
+        /// Error message
+        message: String,
+    },
+    /// Connection to the daemon failed
+    #[error("Daemon connection failed: {0}")]
+    DaemonConnection(#[source] std::io::Error),
 }
 
+#[cfg(target_os = "macos")]
 pub fn verify_io(result: kern_return_t) -> Result<(), Error> {
     if result == kIOReturnSuccess {
         Ok(())
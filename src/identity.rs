@@ -0,0 +1,115 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Identity of a physical monitor, as reported in its EDID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorIdentity {
+    /// Vendor number
+    pub vendor: u32,
+    /// Model number
+    pub model: u32,
+    /// Serial number, zero if the monitor does not report one
+    pub serial: u32,
+    /// Product name, if available
+    pub name: Option<String>,
+}
+
+impl MonitorIdentity {
+    /// Returns `true` if both identities describe the same monitor model
+    pub fn same_model(&self, other: &MonitorIdentity) -> bool {
+        self.vendor == other.vendor && self.model == other.model
+    }
+
+    /// Returns `true` if both identities describe the same physical monitor: the same model and
+    /// serial number. Monitors that do not report a serial number are told apart by name only.
+    pub fn same_monitor(&self, other: &MonitorIdentity) -> bool {
+        self.same_model(other) && self.serial == other.serial && (self.serial != 0 || self.name == other.name)
+    }
+
+    /// Position of the monitor with this identity among `identities`, see
+    /// [MonitorIdentity::same_monitor]. Fails if several of them match, as the monitor then cannot
+    /// be told apart from the others.
+    pub fn position_in<'a>(
+        &self,
+        identities: impl IntoIterator<Item = &'a MonitorIdentity>,
+    ) -> Result<Option<usize>, Error> {
+        let mut matching = identities
+            .into_iter()
+            .enumerate()
+            .filter(|(_, identity)| identity.same_monitor(self))
+            .map(|(index, _)| index);
+        match (matching.next(), matching.next()) {
+            (Some(_), Some(_)) => Err(Error::AmbiguousMonitor(self.to_string())),
+            (index, _) => Ok(index),
+        }
+    }
+}
+
+impl fmt::Display for MonitorIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}:{:04x}:{}", self.vendor, self.model, self.serial)
+    }
+}
+
+impl FromStr for MonitorIdentity {
+    type Err = Error;
+
+    /// Parses the persistent ID printed by [MonitorIdentity]'s `Display` implementation, such as
+    /// `10ac:a0c4:12345`. The product name is left out.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidArgument(format!("invalid monitor ID: {:?}", s));
+        let mut parts = s.trim().split(':');
+        let (Some(vendor), Some(model), Some(serial), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(MonitorIdentity {
+            vendor: u32::from_str_radix(vendor, 16).map_err(|_| invalid())?,
+            model: u32::from_str_radix(model, 16).map_err(|_| invalid())?,
+            serial: serial.parse().map_err(|_| invalid())?,
+            name: None,
+        })
+    }
+}
+
+/// Way DDC/CI commands reach a monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transport {
+    /// I2C interface of the IOFramebuffer (Intel Macs)
+    Intel,
+    /// IOAVService of the DCP (Apple Silicon Macs)
+    Arm,
+}
+
+/// How a monitor is connected to this computer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Connection {
+    /// CoreGraphics display ID, which may change when the display reconnects
+    pub display_id: u32,
+    /// Way DDC/CI commands reach the monitor
+    pub transport: Transport,
+    /// I2C address DDC/CI commands are sent to
+    pub i2c_address: u16,
+    /// Bus the monitor is driven through
+    pub bus: BusId,
+}
+
+/// Identifies the bus a monitor is driven through: the IOKit registry entry ID of its IOFramebuffer
+/// (Intel) or of the framebuffer entry its DCP AV service is found under (Apple Silicon). Monitors on
+/// the same bus must not be driven concurrently.
+/// Apple Silicon drives each external display through a framebuffer and DCP of its own, so displays
+/// there are assumed to be independent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BusId(pub u64);
+
+impl BusId {
+    /// Bus of monitors whose service could not be identified. They are all assumed to share it.
+    pub const UNKNOWN: BusId = BusId(0);
+}
@@ -0,0 +1,185 @@
+#![deny(missing_docs)]
+
+use crate::capabilities::MccsVersion;
+use crate::catalog::vcp_feature;
+use crate::catalog::VCP_MCCS_VERSION;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use crate::quirks::VCP_FIRMWARE_LEVEL;
+#[cfg(target_os = "macos")]
+use ddc::{Ddc, DdcHost};
+use ddc::{FeatureCode, VcpValue};
+use std::fmt;
+
+/// VCP code of the display usage time
+pub const VCP_DISPLAY_USAGE_TIME: FeatureCode = 0xc0;
+/// VCP code of the display technology type
+pub const VCP_DISPLAY_TECHNOLOGY: FeatureCode = 0xb6;
+/// VCP code of the application enable key
+pub const VCP_APPLICATION_ENABLE_KEY: FeatureCode = 0xc6;
+/// VCP code of the display controller type
+pub const VCP_DISPLAY_CONTROLLER: FeatureCode = 0xc8;
+
+/// VCP codes gathered by [Monitor::info]
+pub const INFO_CODES: &[FeatureCode] = &[
+    VCP_DISPLAY_USAGE_TIME,
+    VCP_FIRMWARE_LEVEL,
+    VCP_DISPLAY_CONTROLLER,
+    VCP_DISPLAY_TECHNOLOGY,
+    VCP_APPLICATION_ENABLE_KEY,
+    VCP_MCCS_VERSION,
+];
+
+/// Display firmware level (VCP 0xC9)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirmwareLevel {
+    /// Version, from the high byte
+    pub version: u8,
+    /// Revision, from the low byte
+    pub revision: u8,
+}
+
+impl From<VcpValue> for FirmwareLevel {
+    fn from(value: VcpValue) -> Self {
+        FirmwareLevel {
+            version: value.sh,
+            revision: value.sl,
+        }
+    }
+}
+
+impl fmt::Display for FirmwareLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.version, self.revision)
+    }
+}
+
+/// Display controller type (VCP 0xC8)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayController {
+    /// Controller manufacturer code, from the low byte
+    pub manufacturer: u8,
+    /// Manufacturer specific controller type, from the maximum bytes
+    pub controller_type: u16,
+}
+
+impl DisplayController {
+    /// Name of the controller manufacturer, if it is a known one
+    pub fn manufacturer_name(&self) -> Option<&'static str> {
+        vcp_feature(VCP_DISPLAY_CONTROLLER)?.value_name(self.manufacturer.into())
+    }
+}
+
+impl From<VcpValue> for DisplayController {
+    fn from(value: VcpValue) -> Self {
+        DisplayController {
+            manufacturer: value.sl,
+            controller_type: value.maximum(),
+        }
+    }
+}
+
+impl fmt::Display for DisplayController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.manufacturer_name() {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "manufacturer 0x{:02x}", self.manufacturer)?,
+        }
+        write!(f, " (type 0x{:04x})", self.controller_type)
+    }
+}
+
+/// Decodes the display usage time (VCP 0xC0) in hours. The hours are in the current value bytes,
+/// some monitors extend them with the low maximum byte.
+pub fn usage_hours(value: &VcpValue) -> u32 {
+    (u32::from(value.ml) << 16) | u32::from(value.value())
+}
+
+/// Name of the display technology type (VCP 0xB6) value, if it is a known one
+pub fn display_technology_name(value: u16) -> Option<&'static str> {
+    vcp_feature(VCP_DISPLAY_TECHNOLOGY)?.value_name(value)
+}
+
+/// Asset information of a monitor. Every item is `None` if the monitor does not support it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorInfo {
+    /// Display usage time in hours (VCP 0xC0)
+    pub usage_hours: Option<u32>,
+    /// Firmware level (VCP 0xC9)
+    pub firmware: Option<FirmwareLevel>,
+    /// Display controller (VCP 0xC8)
+    pub controller: Option<DisplayController>,
+    /// Display technology type (VCP 0xB6)
+    pub technology: Option<u16>,
+    /// Application enable key (VCP 0xC6)
+    pub application_enable_key: Option<u16>,
+    /// MCCS version (VCP 0xDF or the capabilities string)
+    pub mccs_version: Option<MccsVersion>,
+}
+
+impl fmt::Display for MonitorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn line(f: &mut fmt::Formatter<'_>, name: &str, value: Option<impl fmt::Display>) -> fmt::Result {
+            match value {
+                Some(value) => writeln!(f, "{}: {}", name, value),
+                None => writeln!(f, "{}: not supported", name),
+            }
+        }
+        line(
+            f,
+            "Display usage time",
+            self.usage_hours.map(|hours| format!("{} h", hours)),
+        )?;
+        line(f, "Firmware level", self.firmware)?;
+        line(f, "Display controller", self.controller)?;
+        line(
+            f,
+            "Display technology",
+            self.technology.map(|value| match display_technology_name(value) {
+                Some(name) => name.to_string(),
+                None => format!("0x{:02x}", value),
+            }),
+        )?;
+        line(
+            f,
+            "Application enable key",
+            self.application_enable_key.map(|key| format!("0x{:04x}", key)),
+        )?;
+        line(f, "MCCS version", self.mccs_version)
+    }
+}
+
+impl MonitorInfo {
+    /// Gathers asset information through `read`, which returns the value of a VCP feature or `None`
+    /// if the monitor does not support it. The MCCS version is determined by the caller.
+    pub fn read(mut read: impl FnMut(FeatureCode) -> Option<VcpValue>, mccs_version: Option<MccsVersion>) -> Self {
+        MonitorInfo {
+            usage_hours: read(VCP_DISPLAY_USAGE_TIME).map(|value| usage_hours(&value)),
+            firmware: read(VCP_FIRMWARE_LEVEL).map(FirmwareLevel::from),
+            controller: read(VCP_DISPLAY_CONTROLLER).map(DisplayController::from),
+            technology: read(VCP_DISPLAY_TECHNOLOGY).map(|value| value.value()),
+            application_enable_key: read(VCP_APPLICATION_ENABLE_KEY).map(|value| value.value()),
+            mccs_version,
+        }
+    }
+}
+
+/// Asset information
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Gathers the asset information of this [Monitor]. Items the monitor does not support are left
+    /// out rather than failing the whole call.
+    pub fn info(&mut self) -> MonitorInfo {
+        let mccs_version = self.mccs_version().ok();
+        MonitorInfo::read(
+            |code| {
+                self.sleep();
+                self.get_vcp_feature(code).ok()
+            },
+            mccs_version,
+        )
+    }
+}
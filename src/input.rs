@@ -0,0 +1,83 @@
+#![deny(missing_docs)]
+
+#[cfg(target_os = "macos")]
+use crate::controls::InputSource;
+#[cfg(target_os = "macos")]
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+#[cfg(target_os = "macos")]
+use core_graphics::display::CGDisplay;
+#[cfg(target_os = "macos")]
+use std::thread;
+use std::time::Duration;
+#[cfg(target_os = "macos")]
+use std::time::Instant;
+
+/// Interval between two checks while waiting for a display to disappear or reappear
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Calls `poll` every [POLL_INTERVAL] until it returns a value or `timeout` expires, in which case
+/// `None` is returned. `poll` is called at least once.
+#[cfg(target_os = "macos")]
+pub(crate) fn poll_until<T>(
+    timeout: Duration,
+    mut poll: impl FnMut() -> Result<Option<T>, Error>,
+) -> Result<Option<T>, Error> {
+    let started = Instant::now();
+    loop {
+        if let Some(value) = poll()? {
+            return Ok(Some(value));
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL.min(timeout - elapsed));
+    }
+}
+
+/// Input switching and re-acquiring monitors by identity
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Switches this [Monitor] to another input source.
+    ///
+    /// A monitor showing another input usually vanishes from the active display list, which makes
+    /// this handle stale. With a `timeout`, the call waits for that to happen and returns `true` if
+    /// the display disappeared in time; without one it returns right after sending the command. Use
+    /// [Monitor::reacquire] to get a fresh handle once the monitor is switched back.
+    pub fn switch_input(&mut self, input: InputSource, timeout: Option<Duration>) -> Result<bool, Error> {
+        self.set_input_source(input)?;
+        let Some(timeout) = timeout else {
+            return Ok(false);
+        };
+        let id = self.handle().id;
+        let disappeared = poll_until(timeout, || {
+            let active = CGDisplay::active_displays().map_err(Error::from)?;
+            Ok((!active.contains(&id)).then_some(()))
+        })?;
+        Ok(disappeared.is_some())
+    }
+
+    /// Finds the connected monitor with the given identity, see [MonitorIdentity::position_in]
+    pub fn find(identity: &MonitorIdentity) -> Result<Option<Monitor>, Error> {
+        let mut monitors = Monitor::enumerate()?;
+        let identities: Vec<MonitorIdentity> = monitors.iter().map(Monitor::identity).collect();
+        Ok(identity
+            .position_in(&identities)?
+            .map(|index| monitors.swap_remove(index)))
+    }
+
+    /// Waits up to `timeout` for this monitor to (re)appear, e.g. after it was switched back to this
+    /// computer's input, and returns a fresh handle to it. The display usually comes back with a new
+    /// display ID, so it is matched by identity rather than by ID. The new handle keeps the quirks,
+    /// vendor extensions, retry policy, safe mode and cached capabilities of this one.
+    pub fn reacquire(&self, timeout: Duration) -> Result<Monitor, Error> {
+        let mut monitor =
+            poll_until(timeout, || Monitor::find(self.cached_identity()))?.ok_or(Error::Timeout(timeout))?;
+        monitor.adopt_settings(self);
+        Ok(monitor)
+    }
+}
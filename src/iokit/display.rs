@@ -2,6 +2,7 @@
 
 /// Selective translation of IOKit/graphics/IOGraphicsLib.h
 use core_foundation::dictionary::CFDictionaryRef;
+use core_graphics::base::CGError;
 use core_graphics::display::CGDirectDisplayID;
 use io_kit_sys::types::{io_service_t, IOOptionBits};
 use mach2::port::mach_port_t;
@@ -19,4 +20,28 @@ extern "C" {
     #[link(name = "CoreDisplay", kind = "framework")]
     // Creates a display info dictionary for a specified display ID
     pub fn CoreDisplay_DisplayCreateInfoDictionary(display_id: CGDirectDisplayID) -> CFDictionaryRef;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    // Lists displays that are connected, including ones that are asleep or mirrored and therefore not active
+    pub fn CGGetOnlineDisplayList(
+        max_displays: u32,
+        online_displays: *mut CGDirectDisplayID,
+        display_count: *mut u32,
+    ) -> CGError;
+}
+
+/// Returns the IDs of all online displays, active or not
+pub fn online_displays() -> Result<Vec<CGDirectDisplayID>, CGError> {
+    let mut count = 0;
+    let result = unsafe { CGGetOnlineDisplayList(0, std::ptr::null_mut(), &mut count) };
+    if result != 0 {
+        return Err(result);
+    }
+    let mut displays = vec![0; count as usize];
+    let result = unsafe { CGGetOnlineDisplayList(count, displays.as_mut_ptr(), &mut count) };
+    if result != 0 {
+        return Err(result);
+    }
+    displays.truncate(count as usize);
+    Ok(displays)
 }
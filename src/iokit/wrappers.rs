@@ -6,8 +6,8 @@ use io_kit_sys::keys::kIOServicePlane;
 use io_kit_sys::types::{io_iterator_t, io_object_t};
 use io_kit_sys::{
     kIOMasterPortDefault, kIORegistryIterateRecursively, IOIteratorNext, IOObjectRelease,
-    IORegistryEntryCreateCFProperties, IORegistryEntryCreateIterator, IORegistryGetRootEntry,
-    IOServiceGetMatchingServices, IOServiceMatching, IOServiceNameMatching,
+    IORegistryEntryCreateCFProperties, IORegistryEntryCreateIterator, IORegistryEntryGetRegistryEntryID,
+    IORegistryGetRootEntry, IOServiceGetMatchingServices, IOServiceMatching, IOServiceNameMatching,
 };
 use std::ops::{Deref, DerefMut};
 
@@ -28,6 +28,15 @@ impl IoObject {
             Ok(CFMutableDictionary::wrap_under_create_rule(props as _).to_immutable())
         }
     }
+
+    /// Returns the system-wide unique ID of this registry entry.
+    pub fn registry_entry_id(&self) -> Result<u64, std::io::Error> {
+        let mut id = 0;
+        unsafe {
+            kern_try!(IORegistryEntryGetRegistryEntryID(self.0, &mut id));
+        }
+        Ok(id)
+    }
 }
 
 impl From<io_object_t> for IoObject {
@@ -1,13 +1,19 @@
 #![doc(html_root_url = "https://haimgel.github.io/ddc-macos-rs/")]
+// The DDC/CI helpers behind Monitor are unused where Monitor is not available
 
 //! Implementation of DDC/CI traits on MacOS.
 //!
+//! Monitors can only be driven on MacOS. Everything built on [DisplayBackend], such as the
+//! capabilities parser, the VCP feature catalog and the command-line tool, also builds on other
+//! platforms so it can be tested there with a [SimulatedBackend].
+//!
 //! # Example
 //!
 //! ```rust,no_run
 //! extern crate ddc;
 //! extern crate ddc_macos;
 //!
+//! # #[cfg(target_os = "macos")]
 //! # fn main() {
 //! use ddc::Ddc;
 //! use ddc_macos::Monitor;
@@ -17,13 +23,119 @@
 //!     println!("Current input: {:04x}", input.value());
 //! }
 //! # }
+//! # #[cfg(not(target_os = "macos"))]
+//! # fn main() {}
 //! ```
 
+#[cfg(target_os = "macos")]
 mod arm;
+mod backend;
+mod capabilities;
+mod catalog;
+mod changes;
+#[cfg(feature = "cli")]
+mod cli;
+mod color;
+mod commands;
+mod controls;
+#[cfg(feature = "daemon")]
+mod daemon;
+mod edid;
 mod error;
+mod identity;
+mod info;
+mod input;
+#[cfg(target_os = "macos")]
 mod intel;
+#[cfg(target_os = "macos")]
 mod iokit;
+#[cfg(target_os = "macos")]
 mod monitor;
+#[cfg(target_os = "macos")]
+mod monitor_set;
+mod power;
+mod profile;
+mod quirks;
+mod reply;
+mod reset;
+mod safe_mode;
+mod schedule;
+mod snapshot;
+mod table;
+mod transition;
+mod vendor;
+mod verify;
 
-pub use error::*;
-pub use monitor::*;
+#[cfg(target_os = "macos")]
+pub use backend::MonitorBackend;
+pub use backend::{
+    select_display, DisplayBackend, DisplayDescription, ListedDisplay, MonitorSelector, SimulatedBackend,
+    SimulatedDisplay,
+};
+pub use capabilities::{
+    read_capabilities_string, Capabilities, DisplayType, MccsVersion, Protocol, VcpCapability,
+    CAPABILITIES_FRAGMENT_RETRIES, CAPABILITIES_MAX_LENGTH,
+};
+pub use catalog::{
+    describe_vcp_value, mccs_value, names_match, parse_feature_code, vcp_feature, vcp_feature_by_name,
+    vcp_feature_for_version, Access, VcpFeature, VcpFeatureType, VCP_FEATURES, VCP_MCCS_VERSION,
+};
+pub use changes::{
+    ChangeDetection, ChangePoller, ControlChange, NewControlValue, MAX_ACTIVE_CONTROLS, VCP_ACTIVE_CONTROL,
+    VCP_NEW_CONTROL_VALUE,
+};
+#[cfg(feature = "cli")]
+pub use cli::{Cli, CliCommand, CliOutput, JsonError, JsonOutput, CLI_USAGE, JSON_SCHEMA_VERSION};
+pub use color::{
+    ColorSettings, ColorTemperature, RgbValues, COLOR_TEMPERATURE_BASE, VCP_BLACK_LEVEL,
+    VCP_COLOR_TEMPERATURE_INCREMENT, VCP_COLOR_TEMPERATURE_REQUEST, VCP_VIDEO_GAIN,
+};
+pub use commands::{IdentificationReply, IdentificationRequest, TimingReport, TimingReportRequest, TimingStatus};
+pub use controls::{
+    ColorPreset, ContinuousValue, FeatureValue, InputSource, PowerMode, ValueArgument, ValueParam, VCP_AUDIO_MUTE,
+    VCP_BRIGHTNESS, VCP_COLOR_PRESET, VCP_CONTRAST, VCP_INPUT_SOURCE, VCP_POWER_MODE, VCP_VOLUME,
+};
+#[cfg(feature = "daemon")]
+pub use daemon::{
+    default_socket_path, ChangeNotification, Daemon, DaemonClient, DaemonOptions, DaemonServer, DisplayParams,
+    FeatureParam, GetParams, RpcError, RpcNotification, RpcRequest, RpcResponse, Session, SetParams, SubscribeParams,
+    SubscriptionParams, CHANGED_NOTIFICATION, DEFAULT_SOCKET_NAME, INVALID_PARAMS, INVALID_REQUEST, JSONRPC_VERSION,
+    METHOD_NOT_FOUND, MONITOR_ERROR, PARSE_ERROR,
+};
+pub use edid::Edid;
+#[cfg(target_os = "macos")]
+pub use error::verify_io;
+pub use error::Error;
+pub use identity::{BusId, Connection, MonitorIdentity, Transport};
+pub use info::{
+    display_technology_name, usage_hours, DisplayController, FirmwareLevel, MonitorInfo, INFO_CODES,
+    VCP_APPLICATION_ENABLE_KEY, VCP_DISPLAY_CONTROLLER, VCP_DISPLAY_TECHNOLOGY, VCP_DISPLAY_USAGE_TIME,
+};
+pub use input::POLL_INTERVAL;
+#[cfg(target_os = "macos")]
+pub use monitor::Monitor;
+#[cfg(target_os = "macos")]
+pub use monitor_set::MonitorSet;
+pub use profile::{apply_profile, Profile, ProfileChanges, ProfileOptions, ProfileOverride, Profiles};
+pub use quirks::{MaximumOverride, QuirkEntry, QuirkRegistry, Quirks, VCP_FIRMWARE_LEVEL};
+pub use reply::RetryPolicy;
+pub use reset::{Confirmation, ResetOperation, ResetPlan, ResetReport};
+pub use safe_mode::{SafeMode, DESTRUCTIVE_CODES};
+#[cfg(target_os = "macos")]
+pub use schedule::run_schedule;
+pub use schedule::{
+    sun_times, Interpolation, LocalTime, Location, Schedule, SchedulePoint, ScheduleTarget, ScheduleTime, SunTimes,
+    TimeOfDay, SCHEDULE_RETRY_INTERVAL,
+};
+pub use snapshot::{
+    is_restorable, restore_snapshot, snapshot_features, MonitorSnapshot, SettingChange, SnapshotValue,
+    RESTORE_EXCLUDED_CODES,
+};
+pub use table::{
+    table_fragments, TableFragment, TableReadFragment, TABLE_FRAGMENT_RETRIES, TABLE_FRAGMENT_SIZE, TABLE_MAX_SIZE,
+};
+pub use transition::{
+    plan_transition, Easing, TransitionControl, TransitionOptions, TransitionOutcome, TransitionStep, MIN_STEP_INTERVAL,
+};
+pub use vendor::{VendorExtension, VendorRegistry, BENQ, DELL, LG, VENDOR_CODES};
+pub use verify::{set_and_verify, VerifiedWrite, VerifyOptions};
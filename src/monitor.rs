@@ -1,17 +1,24 @@
 #![deny(missing_docs)]
 
+use crate::capabilities::{self, Capabilities, MccsVersion};
+use crate::catalog::VCP_MCCS_VERSION;
 use crate::error::Error;
-use crate::iokit::CoreDisplay_DisplayCreateInfoDictionary;
+use crate::identity::{BusId, Connection, MonitorIdentity, Transport};
 use crate::iokit::IoObject;
+use crate::iokit::{online_displays, CoreDisplay_DisplayCreateInfoDictionary};
+use crate::quirks::{QuirkRegistry, Quirks, VCP_FIRMWARE_LEVEL};
+use crate::reply::{decode_reply, RetryPolicy};
+use crate::safe_mode::{SafeMode, SET_VCP_FEATURE_OPCODE};
+use crate::vendor::VendorRegistry;
 use crate::{arm, intel};
 use core_foundation::base::{CFType, TCFType};
 use core_foundation::data::CFData;
 use core_foundation::dictionary::CFDictionary;
 use core_foundation::string::{CFString, CFStringRef};
-use core_graphics::display::CGDisplay;
+use core_graphics::display::{CGDirectDisplayID, CGDisplay};
 use ddc::{
-    DdcCommand, DdcCommandMarker, DdcCommandRaw, DdcCommandRawMarker, DdcHost, Delay, ErrorCode, I2C_ADDRESS_DDC_CI,
-    SUB_ADDRESS_DDC_CI,
+    Ddc, DdcCommand, DdcCommandMarker, DdcCommandRaw, DdcCommandRawMarker, DdcHost, Delay, ErrorCode,
+    I2C_ADDRESS_DDC_CI, SUB_ADDRESS_DDC_CI,
 };
 use std::time::Duration;
 use std::{fmt, iter};
@@ -29,9 +36,27 @@ pub struct Monitor {
     monitor: CGDisplay,
     service: MonitorService,
     i2c_address: u16,
+    bus: BusId,
+    identity: MonitorIdentity,
     delay: Delay,
+    capabilities: Option<Result<Capabilities, String>>,
+    mccs_version: Option<Option<MccsVersion>>,
+    quirks: Quirks,
+    vendors: VendorRegistry,
+    retry_policy: RetryPolicy,
+    safe_mode: Option<SafeMode>,
+    last_opcode: Option<u8>,
+    pending_reset: Option<u64>,
 }
 
+// SAFETY: the only field that is not `Send` is the `IOAVService` of Apple Silicon monitors, a raw
+// CoreFoundation reference. It is created for this `Monitor` alone by `IOAVServiceCreateWithService`
+// and never handed out, so moving the `Monitor` moves its only user. CoreFoundation reference
+// counting is thread-safe, and `IOAVServiceReadI2C`/`IOAVServiceWriteI2C` are synchronous calls
+// without run loop or thread affinity. `Monitor` stays `!Sync`: every transfer takes `&mut self`, so
+// no two threads can use the service at the same time.
+unsafe impl Send for Monitor {}
+
 impl fmt::Display for Monitor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.description())
@@ -40,31 +65,75 @@ impl fmt::Display for Monitor {
 
 impl Monitor {
     /// Create a new monitor from the specified handle.
-    fn new(monitor: CGDisplay, service: MonitorService, i2c_address: u16) -> Self {
+    fn new(monitor: CGDisplay, service: MonitorService, i2c_address: u16, bus: Option<u64>) -> Self {
+        let identity = MonitorIdentity {
+            vendor: monitor.vendor_number(),
+            model: monitor.model_number(),
+            serial: monitor.serial_number(),
+            name: product_name(monitor),
+        };
         Monitor {
             monitor,
             service,
             i2c_address,
+            bus: bus.map_or(BusId::UNKNOWN, BusId),
+            identity,
             delay: Default::default(),
+            capabilities: None,
+            mccs_version: None,
+            quirks: Quirks::default(),
+            vendors: VendorRegistry::new(),
+            retry_policy: RetryPolicy::default(),
+            safe_mode: None,
+            last_opcode: None,
+            pending_reset: None,
         }
     }
 
-    /// Enumerate all connected physical monitors returning [Vec<Monitor>]
+    /// Enumerate all connected physical monitors returning [Vec<Monitor>], applying the
+    /// [builtin](QuirkRegistry::builtin) quirks
     pub fn enumerate() -> Result<Vec<Self>, Error> {
-        let monitors = CGDisplay::active_displays()
-            .map_err(Error::from)?
+        Self::enumerate_with_quirks(&QuirkRegistry::builtin())
+    }
+
+    /// Enumerate all connected physical monitors returning [Vec<Monitor>], applying the quirks
+    /// from `registry`
+    pub fn enumerate_with_quirks(registry: &QuirkRegistry) -> Result<Vec<Self>, Error> {
+        Self::from_displays(CGDisplay::active_displays()?, registry)
+    }
+
+    /// Enumerate all online physical monitors, including ones that are not in the active display
+    /// list because they are asleep, in standby or mirrored, applying the
+    /// [builtin](QuirkRegistry::builtin) quirks
+    pub fn enumerate_online() -> Result<Vec<Self>, Error> {
+        Self::from_displays(online_displays()?, &QuirkRegistry::builtin())
+    }
+
+    fn from_displays(display_ids: Vec<CGDirectDisplayID>, registry: &QuirkRegistry) -> Result<Vec<Self>, Error> {
+        let mut monitors: Vec<Self> = display_ids
             .into_iter()
             .filter_map(|display_id| {
                 let display = CGDisplay::new(display_id);
                 return if let Some(service) = intel::get_io_framebuffer_port(display) {
-                    Some(Self::new(display, MonitorService::Intel(service), I2C_ADDRESS_DDC_CI))
-                } else if let Ok((service, i2c_address)) = arm::get_display_av_service(display) {
-                    Some(Self::new(display, MonitorService::Arm(service), i2c_address))
+                    let bus = service.registry_entry_id().ok();
+                    Some(Self::new(
+                        display,
+                        MonitorService::Intel(service),
+                        I2C_ADDRESS_DDC_CI,
+                        bus,
+                    ))
+                } else if let Ok((service, i2c_address, bus)) = arm::get_display_av_service(display) {
+                    Some(Self::new(display, MonitorService::Arm(service), i2c_address, bus))
                 } else {
                     None
                 };
             })
             .collect();
+        let vendors = VendorRegistry::builtin();
+        for monitor in &mut monitors {
+            monitor.apply_quirks(registry);
+            monitor.set_vendor_registry(&vendors);
+        }
         Ok(monitors)
     }
 
@@ -89,15 +158,7 @@ impl Monitor {
 
     /// Product name for this [Monitor], if available
     pub fn product_name(&self) -> Option<String> {
-        let info: CFDictionary<CFString, CFType> =
-            unsafe { CFDictionary::wrap_under_create_rule(CoreDisplay_DisplayCreateInfoDictionary(self.monitor.id)) };
-
-        let display_product_name_key = CFString::from_static_string("DisplayProductName");
-        let display_product_names_dict = info.find(&display_product_name_key)?.downcast::<CFDictionary>()?;
-        let (_, localized_product_names) = display_product_names_dict.get_keys_and_values();
-        localized_product_names
-            .first()
-            .map(|name| unsafe { CFString::wrap_under_get_rule(*name as CFStringRef) }.to_string())
+        self.identity.name.clone()
     }
 
     /// Returns Extended display identification data (EDID) for this [Monitor] as raw bytes data
@@ -109,11 +170,203 @@ impl Monitor {
         Some(edid_data.bytes().into())
     }
 
+    /// Identity of this [Monitor]
+    pub fn identity(&self) -> MonitorIdentity {
+        self.identity.clone()
+    }
+
+    /// Takes over the configuration and cached state of `previous`, a stale handle to the same monitor
+    pub(crate) fn adopt_settings(&mut self, previous: &Monitor) {
+        self.capabilities = previous.capabilities.clone();
+        self.mccs_version = previous.mccs_version;
+        self.quirks = previous.quirks.clone();
+        self.vendors = previous.vendors.clone();
+        self.retry_policy = previous.retry_policy;
+        self.safe_mode = previous.safe_mode.clone();
+    }
+
+    /// Token of the reset last prepared with [Monitor::prepare_reset] and not run yet
+    pub(crate) fn pending_reset(&self) -> Option<u64> {
+        self.pending_reset
+    }
+
+    /// Records the token of a prepared reset, or forgets it with `None`
+    pub(crate) fn set_pending_reset(&mut self, token: Option<u64>) {
+        self.pending_reset = token;
+    }
+
+    /// Identity of this [Monitor], as read when the handle was created
+    pub(crate) fn cached_identity(&self) -> &MonitorIdentity {
+        &self.identity
+    }
+
+    /// Looks up the quirks of this [Monitor] in `registry` and applies them, replacing any
+    /// previously applied ones. The firmware level is only read if `registry` has firmware
+    /// specific entries for this model.
+    pub fn apply_quirks(&mut self, registry: &QuirkRegistry) {
+        let identity = self.identity();
+        let firmware = if registry.needs_firmware(&identity) {
+            self.sleep();
+            self.get_vcp_feature(VCP_FIRMWARE_LEVEL).ok().map(|value| value.value())
+        } else {
+            None
+        };
+        self.quirks = registry.lookup(&identity, firmware);
+    }
+
+    /// Quirks applied to this [Monitor]
+    pub fn quirks(&self) -> &Quirks {
+        &self.quirks
+    }
+
+    /// Replaces the quirks applied to this [Monitor]
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    /// Vendor extensions applying to this [Monitor], see [Monitor::vcp_feature]
+    pub fn vendor_registry(&self) -> &VendorRegistry {
+        &self.vendors
+    }
+
+    /// Replaces the vendor extensions of this [Monitor] with the ones in `registry` applying to it.
+    /// [Monitor::enumerate] starts with the [builtin](VendorRegistry::builtin) ones.
+    pub fn set_vendor_registry(&mut self, registry: &VendorRegistry) {
+        self.vendors = registry.for_monitor(&self.identity);
+    }
+
+    /// Retry policy used when the monitor is busy or a transfer fails. The number of retries after
+    /// failed transfers is raised to the one in the [quirks](Monitor::quirks) of this [Monitor], if
+    /// that is higher.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retry_policy.retries.max(self.quirks.retries.unwrap_or(0)),
+            ..self.retry_policy
+        }
+    }
+
+    /// Replaces the retry policy of this [Monitor]
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Safe mode restrictions of this [Monitor], if safe mode is on
+    pub fn safe_mode(&self) -> Option<&SafeMode> {
+        self.safe_mode.as_ref()
+    }
+
+    /// Mutable access to the safe mode restrictions, e.g. to [unlock](SafeMode::unlock) a destructive code
+    pub fn safe_mode_mut(&mut self) -> Option<&mut SafeMode> {
+        self.safe_mode.as_mut()
+    }
+
+    /// Turns safe mode on with the given restrictions, or off with `None`. Writes fail until the
+    /// [capabilities](Monitor::cached_capabilities) have been read, which
+    /// [Monitor::enable_safe_mode] does right away.
+    pub fn set_safe_mode(&mut self, safe_mode: Option<SafeMode>) {
+        self.safe_mode = safe_mode;
+    }
+
+    /// Bus this [Monitor] is driven through
+    pub fn bus(&self) -> BusId {
+        self.bus
+    }
+
     /// CoreGraphics display handle for this monitor
     pub fn handle(&self) -> CGDisplay {
         self.monitor
     }
 
+    /// How this [Monitor] is connected
+    pub fn connection(&self) -> Connection {
+        Connection {
+            display_id: self.monitor.id,
+            transport: match self.service {
+                MonitorService::Intel(_) => Transport::Intel,
+                MonitorService::Arm(_) => Transport::Arm,
+            },
+            i2c_address: self.i2c_address,
+            bus: self.bus,
+        }
+    }
+
+    /// Reads the raw MCCS capabilities string of this [Monitor], retrying failed fragments
+    pub fn capabilities_string(&mut self) -> Result<Vec<u8>, Error> {
+        capabilities::read_capabilities_string(self)
+    }
+
+    /// Reads and parses the MCCS capabilities string of this [Monitor]. The outcome, successful or
+    /// not, replaces the one kept for [Monitor::cached_capabilities].
+    pub fn capabilities(&mut self) -> Result<Capabilities, Error> {
+        let result = self
+            .capabilities_string()
+            .and_then(|capabilities| Capabilities::parse(&capabilities));
+        self.capabilities = Some(match &result {
+            Ok(capabilities) => Ok(capabilities.clone()),
+            Err(error) => Err(error.to_string()),
+        });
+        result
+    }
+
+    /// Returns the capabilities of this [Monitor], reading them from the monitor only on first use.
+    /// A failed read is kept as well, so that a monitor without a capabilities string is not asked
+    /// for it again on every call, until [Monitor::clear_cache].
+    pub fn cached_capabilities(&mut self) -> Result<&Capabilities, Error> {
+        if self.capabilities.is_none() {
+            self.capabilities()?;
+        }
+        match self.capabilities.as_ref().expect("capabilities were just read") {
+            Ok(capabilities) => Ok(capabilities),
+            Err(message) => Err(Error::CapabilitiesUnavailable(message.clone())),
+        }
+    }
+
+    /// Forgets the capabilities and MCCS version read so far, e.g. after a read failed only
+    /// temporarily. They are read again on next use.
+    pub fn clear_cache(&mut self) {
+        self.capabilities = None;
+        self.mccs_version = None;
+    }
+
+    /// MCCS version implemented by this [Monitor], read from VCP 0xDF or, if the monitor reports
+    /// zero or does not support that feature, from the capabilities string. The version is read only
+    /// on first use; a monitor that reports it nowhere is not asked again until
+    /// [Monitor::clear_cache]. Other errors reading VCP 0xDF are returned and nothing is cached.
+    pub fn mccs_version(&mut self) -> Result<MccsVersion, Error> {
+        if let Some(version) = self.mccs_version {
+            return version.ok_or(Error::UnsupportedFeature { code: VCP_MCCS_VERSION });
+        }
+        self.sleep();
+        let version = match self.get_vcp_feature(VCP_MCCS_VERSION) {
+            Ok(value) if value.value() != 0 => Some(MccsVersion::new(value.sh, value.sl)),
+            Ok(_) | Err(Error::UnsupportedFeature { .. }) => self
+                .cached_capabilities()
+                .ok()
+                .and_then(|capabilities| capabilities.mccs_version),
+            Err(error) => return Err(error),
+        };
+        self.mccs_version = Some(version);
+        version.ok_or(Error::UnsupportedFeature { code: VCP_MCCS_VERSION })
+    }
+
+    /// MCCS version of this [Monitor] if it was already read, see [Monitor::mccs_version]
+    pub fn cached_mccs_version(&self) -> Option<MccsVersion> {
+        self.mccs_version.flatten()
+    }
+
+    /// Checks an encoded DDC/CI command against the safe mode restrictions, if safe mode is on.
+    /// Only the cached capabilities are used: reading them here would run through this check again.
+    fn check_safe_write(&self, data: &[u8]) -> Result<(), Error> {
+        let Some(safe_mode) = &self.safe_mode else {
+            return Ok(());
+        };
+        let capabilities = self
+            .capabilities
+            .as_ref()
+            .and_then(|capabilities| capabilities.as_ref().ok());
+        safe_mode.check_command(capabilities, &self.quirks, data)
+    }
+
     fn encode_command<'a>(&self, data: &[u8], packet: &'a mut [u8]) -> &'a [u8] {
         packet[0] = SUB_ADDRESS_DDC_CI;
         packet[1] = 0x80 | data.len() as u8;
@@ -122,25 +375,19 @@ impl Monitor {
             Self::checksum(iter::once((self.i2c_address as u8) << 1).chain(packet[..2 + data.len()].iter().cloned()));
         &packet[..3 + data.len()]
     }
+}
 
-    fn decode_response<'a>(&self, response: &'a mut [u8]) -> Result<&'a mut [u8], crate::error::Error> {
-        if response.is_empty() {
-            return Ok(response);
-        };
-        let len = (response[1] & 0x7f) as usize;
-        if len + 2 >= response.len() {
-            return Err(Error::Ddc(ErrorCode::InvalidLength));
-        }
-        let checksum = Self::checksum(
-            iter::once(((self.i2c_address << 1) | 1) as u8)
-                .chain(iter::once(SUB_ADDRESS_DDC_CI))
-                .chain(response[1..2 + len].iter().cloned()),
-        );
-        if response[2 + len] != checksum {
-            return Err(Error::Ddc(ErrorCode::InvalidChecksum));
-        }
-        Ok(&mut response[2..2 + len])
-    }
+/// Product name of a display, if available
+fn product_name(display: CGDisplay) -> Option<String> {
+    let info: CFDictionary<CFString, CFType> =
+        unsafe { CFDictionary::wrap_under_create_rule(CoreDisplay_DisplayCreateInfoDictionary(display.id)) };
+
+    let display_product_name_key = CFString::from_static_string("DisplayProductName");
+    let display_product_names_dict = info.find(&display_product_name_key)?.downcast::<CFDictionary>()?;
+    let (_, localized_product_names) = display_product_names_dict.get_keys_and_values();
+    localized_product_names
+        .first()
+        .map(|name| unsafe { CFString::wrap_under_get_rule(*name as CFStringRef) }.to_string())
 }
 
 impl DdcHost for Monitor {
@@ -158,14 +405,26 @@ impl DdcCommandRaw for Monitor {
         out: &'a mut [u8],
         response_delay: Duration,
     ) -> Result<&'a mut [u8], Self::Error> {
-        assert!(data.len() <= 36);
+        if data.len() > 36 {
+            return Err(Error::Ddc(ErrorCode::InvalidLength));
+        }
+        self.check_safe_write(data)?;
+        self.last_opcode = data.first().copied();
+        let response_delay = response_delay.max(self.quirks.response_delay().unwrap_or_default());
         let mut packet = [0u8; 36 + 3];
         let packet = self.encode_command(data, &mut packet);
-        let response = match &self.service {
-            MonitorService::Intel(service) => intel::execute(service, self.i2c_address, packet, out, response_delay),
-            MonitorService::Arm(service) => arm::execute(service, self.i2c_address, packet, out, response_delay),
-        }?;
-        self.decode_response(response)
+        let length = self.retry_policy().run(|| {
+            let response = match &self.service {
+                MonitorService::Intel(service) => {
+                    intel::execute(service, self.i2c_address, packet, out, response_delay)
+                }
+                MonitorService::Arm(service) => arm::execute(service, self.i2c_address, packet, out, response_delay),
+            }?;
+            let length = response.len();
+            decode_reply(self.i2c_address, response)?;
+            Ok(length)
+        })?;
+        decode_reply(self.i2c_address, &mut out[..length])
     }
 }
 
@@ -173,6 +432,11 @@ impl DdcCommandMarker for Monitor {}
 
 impl DdcCommandRawMarker for Monitor {
     fn set_sleep_delay(&mut self, delay: Delay) {
-        self.delay = delay;
+        self.delay = match self.quirks.set_vcp_delay() {
+            Some(minimum) if self.last_opcode == Some(SET_VCP_FEATURE_OPCODE) => {
+                Delay::new(minimum.max(delay.remaining()))
+            }
+            _ => delay,
+        };
     }
 }
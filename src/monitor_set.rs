@@ -0,0 +1,107 @@
+#![deny(missing_docs)]
+
+use crate::backend::{partition_by_bus, DisplayDescription};
+use crate::error::Error;
+use crate::monitor::Monitor;
+use std::thread;
+
+/// A group of monitors that are operated on concurrently: one thread per distinct bus, while
+/// monitors sharing a bus are driven one after another.
+#[derive(Debug, Default)]
+pub struct MonitorSet {
+    monitors: Vec<Monitor>,
+}
+
+impl MonitorSet {
+    /// Create a set from already enumerated monitors
+    pub fn new(monitors: Vec<Monitor>) -> Self {
+        MonitorSet { monitors }
+    }
+
+    /// Create a set of all connected physical monitors, see [Monitor::enumerate]
+    pub fn enumerate() -> Result<Self, Error> {
+        Ok(Self::new(Monitor::enumerate()?))
+    }
+
+    /// Monitors in this set
+    pub fn monitors(&self) -> &[Monitor] {
+        &self.monitors
+    }
+
+    /// Mutable access to the monitors in this set
+    pub fn monitors_mut(&mut self) -> &mut [Monitor] {
+        &mut self.monitors
+    }
+
+    /// Returns the monitors of this set
+    pub fn into_monitors(self) -> Vec<Monitor> {
+        self.monitors
+    }
+
+    /// Number of monitors in this set
+    pub fn len(&self) -> usize {
+        self.monitors.len()
+    }
+
+    /// Returns `true` if this set has no monitors
+    pub fn is_empty(&self) -> bool {
+        self.monitors.is_empty()
+    }
+
+    /// Runs `command` on every monitor, concurrently across buses. The command receives the index
+    /// of the monitor, so different monitors can be sent different commands. Returns the result of
+    /// every monitor, in the order of [MonitorSet::monitors].
+    pub fn run<T, F>(&mut self, command: F) -> Vec<Result<T, Error>>
+    where
+        T: Send,
+        F: Fn(usize, &mut Monitor) -> Result<T, Error> + Sync,
+    {
+        let displays: Vec<DisplayDescription> = self.monitors.iter().map(Monitor::display_description).collect();
+        let groups = partition_by_bus(&displays);
+        let mut slots: Vec<Option<&mut Monitor>> = self.monitors.iter_mut().map(Some).collect();
+        let mut results: Vec<Option<Result<T, Error>>> = (0..slots.len()).map(|_| None).collect();
+        let command = &command;
+        thread::scope(|scope| {
+            let handles: Vec<_> = groups
+                .into_iter()
+                .map(|group| {
+                    let mut monitors: Vec<(usize, &mut Monitor)> = group
+                        .into_iter()
+                        .map(|index| (index, slots[index].take().expect("monitor is in a single group")))
+                        .collect();
+                    scope.spawn(move || {
+                        monitors
+                            .iter_mut()
+                            .map(|(index, monitor)| (*index, command(*index, monitor)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            for handle in handles {
+                for (index, result) in handle.join().expect("monitor command panicked") {
+                    results[index] = Some(result);
+                }
+            }
+        });
+        results
+            .into_iter()
+            .map(|result| result.expect("every monitor was run"))
+            .collect()
+    }
+
+    /// Runs the same `command` on every monitor, concurrently across buses. Returns the result of
+    /// every monitor, in the order of [MonitorSet::monitors].
+    pub fn for_each<T, F>(&mut self, command: F) -> Vec<Result<T, Error>>
+    where
+        T: Send,
+        F: Fn(&mut Monitor) -> Result<T, Error> + Sync,
+    {
+        self.run(|_, monitor| command(monitor))
+    }
+}
+
+impl From<Vec<Monitor>> for MonitorSet {
+    fn from(monitors: Vec<Monitor>) -> Self {
+        MonitorSet::new(monitors)
+    }
+}
@@ -0,0 +1,100 @@
+#![deny(missing_docs)]
+
+use crate::controls::PowerMode;
+#[cfg(target_os = "macos")]
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
+use crate::input::poll_until;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+#[cfg(target_os = "macos")]
+use std::time::Duration;
+
+impl PowerMode {
+    /// Returns `true` if the display is on
+    pub fn is_on(self) -> bool {
+        self == PowerMode::On
+    }
+
+    /// Returns `true` if the display is in a DPM power saving state it can be woken from over DDC/CI
+    pub fn is_asleep(self) -> bool {
+        matches!(self, PowerMode::Standby | PowerMode::Suspend | PowerMode::Off)
+    }
+}
+
+/// Display power management (DPMS) through VCP 0xD6
+///
+/// A monitor that leaves the on state drops out of the active display list, and its [Monitor]
+/// handle may stop answering, e.g. when macOS releases the display's service. [Monitor::wake]
+/// therefore falls back to a fresh handle found by identity. Monitors that went to sleep before
+/// the handle was created can be found with [Monitor::enumerate_online] or [Monitor::find_online].
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Returns `true` if the display is on
+    pub fn is_on(&mut self) -> Result<bool, Error> {
+        Ok(self.power_mode()?.is_on())
+    }
+
+    /// Puts the display in DPM standby
+    pub fn standby(&mut self) -> Result<(), Error> {
+        self.set_power_mode(PowerMode::Standby)
+    }
+
+    /// Puts the display in DPM suspend
+    pub fn suspend(&mut self) -> Result<(), Error> {
+        self.set_power_mode(PowerMode::Suspend)
+    }
+
+    /// Puts the display in DPM off
+    pub fn display_off(&mut self) -> Result<(), Error> {
+        self.set_power_mode(PowerMode::Off)
+    }
+
+    /// Turns the display off as with its power button. Most monitors stop answering DDC/CI
+    /// afterwards and can only be turned on again by hand.
+    pub fn power_off(&mut self) -> Result<(), Error> {
+        self.set_power_mode(PowerMode::PowerOff)
+    }
+
+    /// Wakes the display from a power saving state and waits up to `timeout` for it to report that
+    /// it is on. Returns whether it did in time. If this handle stopped answering while the display
+    /// was asleep, it is replaced with a fresh one found by identity, which keeps the settings of
+    /// this one as with [Monitor::reacquire].
+    pub fn wake(&mut self, timeout: Duration) -> Result<bool, Error> {
+        if let Err(error) = self.set_power_mode(PowerMode::On) {
+            let mut monitor = Monitor::find_online(self.cached_identity())?.ok_or(error)?;
+            monitor.set_power_mode(PowerMode::On)?;
+            monitor.adopt_settings(self);
+            *self = monitor;
+        }
+        // A waking monitor may not answer at all for a while
+        let woken = poll_until(timeout, || match self.is_on() {
+            Ok(on) => Ok(on.then_some(None)),
+            Err(_) => {
+                let mut monitor = Monitor::find_online(self.cached_identity())?;
+                let on = monitor.as_mut().is_some_and(|monitor| monitor.is_on().unwrap_or(false));
+                Ok(on.then_some(monitor))
+            }
+        })?;
+        match woken {
+            Some(Some(mut monitor)) => {
+                monitor.adopt_settings(self);
+                *self = monitor;
+                Ok(true)
+            }
+            woken => Ok(woken.is_some()),
+        }
+    }
+
+    /// Finds the online monitor with the given identity, including monitors that are asleep and
+    /// therefore not in the active display list, see [MonitorIdentity::position_in]
+    pub fn find_online(identity: &MonitorIdentity) -> Result<Option<Monitor>, Error> {
+        let mut monitors = Monitor::enumerate_online()?;
+        let identities: Vec<MonitorIdentity> = monitors.iter().map(Monitor::identity).collect();
+        Ok(identity
+            .position_in(&identities)?
+            .map(|index| monitors.swap_remove(index)))
+    }
+}
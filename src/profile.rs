@@ -0,0 +1,229 @@
+#![deny(missing_docs)]
+
+use crate::backend::{DisplayBackend, DisplayDescription, MonitorSelector};
+use crate::catalog::{parse_feature_code, vcp_feature};
+use crate::controls::{ValueArgument, ValueParam};
+use crate::error::Error;
+use crate::snapshot::{apply_settings, SettingChange};
+use ddc::{FeatureCode, VcpValue};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Named sets of monitor settings, such as `day`, `night` or `presentation`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Profiles {
+    /// Profiles by name
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings applied to all monitors, along with per-monitor overrides.
+///
+/// Settings map VCP features, by name (like `brightness`) or hex code (like `0x10`), to values
+/// given as numbers, value names (like `hdmi-1`) or relative amounts (like `"+10"`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Profile {
+    /// Settings of all monitors
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub settings: BTreeMap<String, ValueParam>,
+    /// Settings of specific monitors, taking precedence over [Profile::settings] in the order given
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, rename = "override", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub overrides: Vec<ProfileOverride>,
+}
+
+/// Settings of the monitors matching a selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProfileOverride {
+    /// Monitor selector, see [MonitorSelector]
+    pub monitor: String,
+    /// Settings replacing or adding to those of the profile
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub settings: BTreeMap<String, ValueParam>,
+}
+
+/// Which part of a profile [apply_profile] applies
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileOptions {
+    /// Only apply to the monitors matching this selector
+    pub display: Option<MonitorSelector>,
+    /// Only apply these VCP features
+    pub features: Option<Vec<FeatureCode>>,
+    /// Only read the current values and return the changes, without writing anything
+    pub dry_run: bool,
+}
+
+/// Changes a profile made to a monitor, or would make in a dry run
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProfileChanges {
+    /// Position of the monitor, starting at 1
+    pub index: usize,
+    /// Persistent ID of the monitor
+    pub id: String,
+    /// Human-readable description of the monitor
+    pub description: String,
+    /// Changed settings, in the order they were written
+    pub changes: Vec<SettingChange>,
+    /// VCP features of the profile that could not be read from the monitor, and were left alone
+    pub skipped: Vec<FeatureCode>,
+}
+
+impl Profiles {
+    /// Parses profiles from TOML, with one `[profiles.<name>]` table per profile and
+    /// `[[profiles.<name>.override]]` tables for settings of specific monitors:
+    ///
+    /// ```toml
+    /// [profiles.night]
+    /// brightness = 20
+    /// contrast = 50
+    /// select-color-preset = "5000k"
+    ///
+    /// [[profiles.night.override]]
+    /// monitor = "name=DELL U2720Q"
+    /// brightness = 10
+    /// input-source = "hdmi-1"
+    /// ```
+    ///
+    /// Feature names, values and monitor selectors are checked right away.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let profiles: Profiles =
+            toml::from_str(text).map_err(|error| Error::InvalidConfiguration(error.to_string()))?;
+        profiles.validate()?;
+        Ok(profiles)
+    }
+
+    /// Loads profiles from a TOML file, see [Profiles::from_toml]
+    #[cfg(feature = "toml")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks the feature names, values and monitor selectors of all profiles
+    pub fn validate(&self) -> Result<(), Error> {
+        self.profiles.iter().try_for_each(|(name, profile)| {
+            profile
+                .validate()
+                .map_err(|error| Error::InvalidConfiguration(format!("profile {}: {}", name, error)))
+        })
+    }
+
+    /// Profile called `name`
+    pub fn get(&self, name: &str) -> Result<&Profile, Error> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| Error::InvalidArgument(format!("no profile named {:?}", name)))
+    }
+}
+
+impl Profile {
+    /// Checks the feature names, values and monitor selectors of the profile
+    pub fn validate(&self) -> Result<(), Error> {
+        resolve_settings(&self.settings)?;
+        for monitor in &self.overrides {
+            monitor.monitor.parse::<MonitorSelector>()?;
+            resolve_settings(&monitor.settings)?;
+        }
+        Ok(())
+    }
+
+    /// Settings of the display at `index`: those of the profile with the matching overrides applied
+    pub fn settings_for(
+        &self,
+        index: usize,
+        display: &DisplayDescription,
+    ) -> Result<Vec<(FeatureCode, ValueArgument)>, Error> {
+        let mut settings = resolve_settings(&self.settings)?;
+        for monitor in &self.overrides {
+            if !monitor.monitor.parse::<MonitorSelector>()?.matches(index, display) {
+                continue;
+            }
+            for (code, value) in resolve_settings(&monitor.settings)? {
+                settings.retain(|(existing, _)| *existing != code);
+                settings.push((code, value));
+            }
+        }
+        Ok(settings)
+    }
+}
+
+fn resolve_settings(settings: &BTreeMap<String, ValueParam>) -> Result<Vec<(FeatureCode, ValueArgument)>, Error> {
+    settings
+        .iter()
+        .map(|(feature, value)| {
+            let code = parse_feature_code(feature)?;
+            Ok((code, value.argument(code)?))
+        })
+        .collect()
+}
+
+/// Applies a profile to the displays of `backend`.
+///
+/// The current values are read first and only settings that differ are written, mode selections
+/// such as the color preset before the settings they could overwrite, which are then read again.
+/// Relative values therefore apply to what the new mode left. Features a monitor fails to
+/// read are skipped rather than failing the whole profile. With [ProfileOptions::dry_run], nothing
+/// is written and the returned changes are a preview.
+pub fn apply_profile<B: DisplayBackend + ?Sized>(
+    backend: &mut B,
+    profile: &Profile,
+    options: &ProfileOptions,
+) -> Result<Vec<ProfileChanges>, Error> {
+    let mut applied = Vec::new();
+    for (index, display) in backend.displays()?.into_iter().enumerate() {
+        if options
+            .display
+            .as_ref()
+            .is_some_and(|selector| !selector.matches(index, &display))
+        {
+            continue;
+        }
+        let settings: Vec<(FeatureCode, ValueArgument)> = profile
+            .settings_for(index, &display)?
+            .into_iter()
+            .filter(|(code, _)| options.features.as_ref().is_none_or(|features| features.contains(code)))
+            .collect();
+        let codes: Vec<FeatureCode> = settings.iter().map(|(code, _)| *code).collect();
+        let target = |code: FeatureCode, current: Option<&VcpValue>| {
+            let (_, value) = settings.iter().find(|(setting, _)| *setting == code)?;
+            Some(value.resolve(current?))
+        };
+        let (changes, skipped) = apply_settings(backend, index, &codes, target, options.dry_run)?;
+        applied.push(ProfileChanges {
+            index: index + 1,
+            id: display.identity.to_string(),
+            description: display.description,
+            changes,
+            skipped,
+        });
+    }
+    match &options.display {
+        Some(selector) if applied.is_empty() => Err(Error::MonitorNotFound(selector.to_string())),
+        _ => Ok(applied),
+    }
+}
+
+impl fmt::Display for ProfileChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {} (id {})", self.index, self.description, self.id)?;
+        if self.changes.is_empty() {
+            writeln!(f, "  unchanged")?;
+        }
+        for change in &self.changes {
+            writeln!(f, "  {}", change)?;
+        }
+        for code in &self.skipped {
+            match vcp_feature(*code) {
+                Some(feature) => writeln!(f, "  {}: skipped, could not be read", feature.name)?,
+                None => writeln!(f, "  VCP 0x{:02x}: skipped, could not be read", code)?,
+            }
+        }
+        Ok(())
+    }
+}
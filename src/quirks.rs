@@ -0,0 +1,257 @@
+#![deny(missing_docs)]
+
+use crate::identity::MonitorIdentity;
+use ddc::FeatureCode;
+use std::time::Duration;
+
+/// VCP code of the firmware level, used to match firmware specific quirks
+pub const VCP_FIRMWARE_LEVEL: FeatureCode = 0xc9;
+
+/// EDID vendor number of Dell monitors
+const VENDOR_DELL: u32 = 0x10ac;
+/// EDID vendor number of LG monitors
+const VENDOR_LG: u32 = 0x1e6d;
+/// EDID vendor number of Samsung monitors
+const VENDOR_SAMSUNG: u32 = 0x4c2d;
+
+/// Deviations of a monitor from the MCCS specification and how to work around them.
+///
+/// All settings are optional: a quirk only changes what it sets, so several quirks matching the same
+/// monitor can be combined with [Quirks::merge].
+///
+/// Value encoding and maximum overrides apply to the typed controls, such as
+/// [Monitor::input_source](crate::Monitor::input_source) and
+/// [Monitor::continuous_value](crate::Monitor::continuous_value). Raw [ddc::Ddc] reads and writes
+/// send and return values as they are on the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Quirks {
+    /// Minimum delay in milliseconds after a Set VCP Feature command before the next command
+    pub set_vcp_delay_ms: Option<u64>,
+    /// Minimum delay in milliseconds between a request and reading its reply
+    pub response_delay_ms: Option<u64>,
+    /// Minimum number of times a command is retried if the transfer fails or the reply is invalid,
+    /// raising [RetryPolicy::retries](crate::RetryPolicy::retries) if it is lower
+    pub retries: Option<usize>,
+    /// Whether the monitor sends and expects input source (VCP 0x60) values in the high byte.
+    /// `Some(false)` turns off the encoding set by a less specific quirk.
+    pub input_source_high_byte: Option<bool>,
+    /// Maximum values to use instead of the wrong ones reported by the monitor
+    pub maximum_overrides: Vec<MaximumOverride>,
+}
+
+/// Replacement for the maximum a monitor reports for a continuous VCP feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaximumOverride {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Actual maximum value
+    pub maximum: u16,
+}
+
+impl Quirks {
+    /// Applies the settings of `other` on top of these, `other` taking precedence
+    pub fn merge(&mut self, other: &Quirks) {
+        self.set_vcp_delay_ms = other.set_vcp_delay_ms.or(self.set_vcp_delay_ms);
+        self.response_delay_ms = other.response_delay_ms.or(self.response_delay_ms);
+        self.retries = other.retries.or(self.retries);
+        self.input_source_high_byte = other.input_source_high_byte.or(self.input_source_high_byte);
+        for maximum in &other.maximum_overrides {
+            self.maximum_overrides.retain(|existing| existing.code != maximum.code);
+            self.maximum_overrides.push(*maximum);
+        }
+    }
+
+    /// Minimum delay after a Set VCP Feature command
+    pub fn set_vcp_delay(&self) -> Option<Duration> {
+        self.set_vcp_delay_ms.map(Duration::from_millis)
+    }
+
+    /// Minimum delay between a request and reading its reply
+    pub fn response_delay(&self) -> Option<Duration> {
+        self.response_delay_ms.map(Duration::from_millis)
+    }
+
+    /// Corrected maximum of the VCP feature `code`, if the monitor is known to report a wrong one
+    pub fn maximum(&self, code: FeatureCode) -> Option<u16> {
+        self.maximum_overrides
+            .iter()
+            .find(|maximum| maximum.code == code)
+            .map(|maximum| maximum.maximum)
+    }
+
+    /// Converts a raw value read from the monitor into its MCCS value
+    pub fn decode_value(&self, code: FeatureCode, value: u16) -> u16 {
+        if code == 0x60 && self.input_source_high_byte == Some(true) && value > 0xff {
+            value >> 8
+        } else {
+            value
+        }
+    }
+
+    /// Converts an MCCS value into the raw value the monitor expects
+    pub fn encode_value(&self, code: FeatureCode, value: u16) -> u16 {
+        if code == 0x60 && self.input_source_high_byte == Some(true) && value <= 0xff {
+            value << 8
+        } else {
+            value
+        }
+    }
+}
+
+/// Quirks applying to a vendor, model and firmware level
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuirkEntry {
+    /// EDID vendor number
+    pub vendor: u32,
+    /// EDID product number, or `None` for all models of the vendor
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub model: Option<u32>,
+    /// Firmware level (VCP 0xC9 value), or `None` for any firmware
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub firmware: Option<u16>,
+    /// Quirks to apply
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub quirks: Quirks,
+}
+
+impl QuirkEntry {
+    /// Returns `true` if this entry applies to the monitor `identity` running `firmware`
+    pub fn matches(&self, identity: &MonitorIdentity, firmware: Option<u16>) -> bool {
+        self.vendor == identity.vendor
+            && self.model.is_none_or(|model| model == identity.model)
+            && self.firmware.is_none_or(|level| Some(level) == firmware)
+    }
+
+    /// How specific this entry is: firmware entries override model entries, which override vendor entries
+    fn specificity(&self) -> u8 {
+        u8::from(self.model.is_some()) + 2 * u8::from(self.firmware.is_some())
+    }
+}
+
+/// A collection of [QuirkEntry] records, looked up by monitor identity
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuirkRegistry {
+    /// Registered entries. When several entries of the same specificity match, later ones take precedence.
+    #[cfg_attr(feature = "serde", serde(default, rename = "quirk"))]
+    pub entries: Vec<QuirkEntry>,
+}
+
+impl QuirkRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        QuirkRegistry::default()
+    }
+
+    /// The quirks known to this crate, used by [Monitor::enumerate](crate::Monitor::enumerate)
+    pub fn builtin() -> Self {
+        QuirkRegistry {
+            entries: vec![
+                // Dell firmware sends null messages or invalid replies while busy with the previous command
+                QuirkEntry {
+                    vendor: VENDOR_DELL,
+                    model: None,
+                    firmware: None,
+                    quirks: Quirks {
+                        retries: Some(3),
+                        ..Default::default()
+                    },
+                },
+                // LG UltraFine monitors send and expect input sources in the high byte
+                QuirkEntry {
+                    vendor: VENDOR_LG,
+                    model: Some(0x5b11),
+                    firmware: None,
+                    quirks: Quirks {
+                        input_source_high_byte: Some(true),
+                        ..Default::default()
+                    },
+                },
+                // Samsung monitors drop commands sent too soon after a Set VCP Feature
+                QuirkEntry {
+                    vendor: VENDOR_SAMSUNG,
+                    model: None,
+                    firmware: None,
+                    quirks: Quirks {
+                        set_vcp_delay_ms: Some(100),
+                        ..Default::default()
+                    },
+                },
+                // Samsung models reporting a brightness maximum of 255 while only accepting up to 100
+                QuirkEntry {
+                    vendor: VENDOR_SAMSUNG,
+                    model: Some(0x0e18),
+                    firmware: None,
+                    quirks: Quirks {
+                        maximum_overrides: vec![MaximumOverride {
+                            code: 0x10,
+                            maximum: 100,
+                        }],
+                        ..Default::default()
+                    },
+                },
+            ],
+        }
+    }
+
+    /// Adds an entry, taking precedence over existing entries of the same specificity
+    pub fn add(&mut self, entry: QuirkEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Adds all entries of `other`, taking precedence over existing entries of the same specificity
+    pub fn extend(&mut self, other: QuirkRegistry) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Parses a registry from TOML, with one `[[quirk]]` table per entry:
+    ///
+    /// ```toml
+    /// [[quirk]]
+    /// vendor = 0x1e6d
+    /// model = 0x5b11
+    /// input_source_high_byte = true
+    ///
+    /// [[quirk]]
+    /// vendor = 0x4c2d
+    /// firmware = 0x0102
+    /// set_vcp_delay_ms = 150
+    /// maximum_overrides = [{ code = 0x10, maximum = 100 }]
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, crate::Error> {
+        toml::from_str(text).map_err(|error| crate::Error::InvalidConfiguration(error.to_string()))
+    }
+
+    /// Loads a registry from a TOML file, see [QuirkRegistry::from_toml]
+    #[cfg(feature = "toml")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Returns `true` if the quirks of the monitor `identity` depend on its firmware level
+    pub fn needs_firmware(&self, identity: &MonitorIdentity) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.firmware.is_some() && entry.matches(identity, entry.firmware))
+    }
+
+    /// Combined quirks of all entries matching the monitor `identity` running `firmware`
+    pub fn lookup(&self, identity: &MonitorIdentity, firmware: Option<u16>) -> Quirks {
+        let mut matching: Vec<&QuirkEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.matches(identity, firmware))
+            .collect();
+        matching.sort_by_key(|entry| entry.specificity());
+        let mut quirks = Quirks::default();
+        for entry in matching {
+            quirks.merge(&entry.quirks);
+        }
+        quirks
+    }
+}
@@ -0,0 +1,195 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+use ddc::{ErrorCode, SUB_ADDRESS_DDC_CI};
+use std::iter;
+use std::thread;
+use std::time::Duration;
+
+/// How a command is retried when the monitor is busy or the transfer fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Number of times a command is retried after the monitor replies with a null message
+    pub busy_retries: usize,
+    /// Time to wait for a busy monitor before retrying
+    pub busy_delay: Duration,
+    /// Number of times a command is retried after a failed transfer or an invalid reply
+    pub retries: usize,
+    /// Time to wait before retrying after a failed transfer or an invalid reply
+    pub retry_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            busy_retries: 3,
+            busy_delay: Duration::from_millis(100),
+            retries: 0,
+            retry_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn never() -> Self {
+        RetryPolicy {
+            busy_retries: 0,
+            retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Runs `transfer` until it succeeds or the retries allowed for its error are used up,
+    /// returning the last error in that case. Errors other than null messages, I/O and DDC/CI
+    /// errors are returned immediately.
+    pub fn run<T>(&self, mut transfer: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+        let mut busy_attempts = 0;
+        let mut error_attempts = 0;
+        loop {
+            match transfer() {
+                Ok(result) => return Ok(result),
+                Err(Error::NullMessage) if busy_attempts < self.busy_retries => {
+                    busy_attempts += 1;
+                    thread::sleep(self.busy_delay);
+                }
+                Err(Error::Io(_) | Error::Ddc(_)) if error_attempts < self.retries => {
+                    error_attempts += 1;
+                    thread::sleep(self.retry_delay);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// Opcode of the VCP Feature reply
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const GET_VCP_FEATURE_REPLY_OPCODE: u8 = 0x02;
+
+/// Result code of a VCP Feature reply for a feature the monitor does not support
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const UNSUPPORTED_RESULT_CODE: u8 = 0x01;
+
+/// Computes the DDC/CI checksum: the XOR of all bytes
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn checksum<II: IntoIterator<Item = u8>>(iter: II) -> u8 {
+    iter.into_iter().fold(0u8, |sum, v| sum ^ v)
+}
+
+/// Validates a raw DDC/CI reply read from the monitor at `i2c_address` and returns its payload.
+///
+/// The reply consists of the source address, a length byte with the high bit set, the payload and
+/// a checksum. A reply without payload is a null message, which the monitor sends when it is not
+/// ready to answer and is reported as [Error::NullMessage]. A VCP Feature reply with the
+/// "unsupported" result code is reported as [Error::UnsupportedFeature]. An empty `response` (no
+/// reply was requested) is returned as is.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn decode_reply(i2c_address: u16, response: &mut [u8]) -> Result<&mut [u8], Error> {
+    if response.is_empty() {
+        return Ok(response);
+    }
+    if response.len() < 3 {
+        return Err(Error::Ddc(ErrorCode::InvalidLength));
+    }
+    if response[1] & 0x80 == 0 {
+        return Err(Error::Ddc(ErrorCode::InvalidData));
+    }
+    let len = (response[1] & 0x7f) as usize;
+    if len + 2 >= response.len() {
+        return Err(Error::Ddc(ErrorCode::InvalidLength));
+    }
+    let checksum = checksum(
+        iter::once(((i2c_address << 1) | 1) as u8)
+            .chain(iter::once(SUB_ADDRESS_DDC_CI))
+            .chain(response[1..2 + len].iter().cloned()),
+    );
+    if response[2 + len] != checksum {
+        return Err(Error::Ddc(ErrorCode::InvalidChecksum));
+    }
+    if len == 0 {
+        return Err(Error::NullMessage);
+    }
+    if let [GET_VCP_FEATURE_REPLY_OPCODE, UNSUPPORTED_RESULT_CODE, code, ..] = response[2..2 + len] {
+        return Err(Error::UnsupportedFeature { code });
+    }
+    Ok(&mut response[2..2 + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddc::ErrorCode;
+
+    const I2C_ADDRESS: u16 = 0x37;
+
+    /// Builds a reply as read from the monitor: source address, length, payload and checksum
+    fn reply(payload: &[u8]) -> Vec<u8> {
+        let mut reply = vec![0x6e, 0x80 | payload.len() as u8];
+        reply.extend_from_slice(payload);
+        let checksum = [0x6f, 0x51].iter().chain(&reply[1..]).fold(0u8, |sum, v| sum ^ v);
+        reply.push(checksum);
+        reply.resize(11, 0);
+        reply
+    }
+
+    #[test]
+    fn test_decode_valid() {
+        let payload = [0x02, 0x00, 0x10, 0x00, 0x00, 0x64, 0x00, 0x32];
+        let mut response = reply(&payload);
+        assert_eq!(decode_reply(I2C_ADDRESS, &mut response).unwrap(), &payload);
+        assert!(decode_reply(I2C_ADDRESS, &mut []).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_null_message() {
+        let mut response = reply(&[]);
+        assert_eq!(&response[..3], &[0x6e, 0x80, 0xbe]);
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response),
+            Err(Error::NullMessage)
+        ));
+    }
+
+    #[test]
+    fn test_decode_unsupported() {
+        let mut response = reply(&[0x02, 0x01, 0x62, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response),
+            Err(Error::UnsupportedFeature { code: 0x62 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let mut response = reply(&[0x02, 0x00, 0x10, 0x00, 0x00, 0x64, 0x00, 0x32]);
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response[..6]),
+            Err(Error::Ddc(ErrorCode::InvalidLength))
+        ));
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response[..2]),
+            Err(Error::Ddc(ErrorCode::InvalidLength))
+        ));
+    }
+
+    #[test]
+    fn test_decode_garbage() {
+        let mut response = [0xffu8; 11];
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response),
+            Err(Error::Ddc(ErrorCode::InvalidLength))
+        ));
+        let mut response = [0x6e, 0x08, 0x02, 0x00, 0x10, 0x00, 0x00, 0x64, 0x00, 0x32, 0x00];
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response),
+            Err(Error::Ddc(ErrorCode::InvalidData))
+        ));
+        let mut response = reply(&[0x02, 0x00, 0x10, 0x00, 0x00, 0x64, 0x00, 0x32]);
+        response[5] ^= 0x01;
+        assert!(matches!(
+            decode_reply(I2C_ADDRESS, &mut response),
+            Err(Error::Ddc(ErrorCode::InvalidChecksum))
+        ));
+    }
+}
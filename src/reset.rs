@@ -0,0 +1,232 @@
+#![deny(missing_docs)]
+
+#[cfg(target_os = "macos")]
+use crate::controls::VCP_BRIGHTNESS;
+#[cfg(target_os = "macos")]
+use crate::error::Error;
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
+use crate::input::poll_until;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use crate::snapshot::{MonitorSnapshot, SettingChange};
+use ddc::FeatureCode;
+#[cfg(target_os = "macos")]
+use ddc::{Ddc, DdcHost};
+#[cfg(target_os = "macos")]
+use std::collections::hash_map::RandomState;
+use std::fmt;
+#[cfg(target_os = "macos")]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(target_os = "macos")]
+use std::time::Duration;
+
+/// A destructive operation that overwrites monitor settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResetOperation {
+    /// Restore all factory defaults (VCP 0x04)
+    FactoryDefaults,
+    /// Restore factory luminance and contrast defaults (VCP 0x05)
+    LuminanceContrast,
+    /// Restore factory geometry defaults (VCP 0x06)
+    Geometry,
+    /// Restore factory color defaults (VCP 0x08)
+    Color,
+    /// Store the current settings in the monitor's non-volatile memory (Save Current Settings command)
+    SaveSettings,
+}
+
+impl ResetOperation {
+    /// VCP code that triggers the operation, or `None` for [ResetOperation::SaveSettings] which is
+    /// a DDC/CI command of its own
+    pub fn code(self) -> Option<FeatureCode> {
+        match self {
+            ResetOperation::FactoryDefaults => Some(0x04),
+            ResetOperation::LuminanceContrast => Some(0x05),
+            ResetOperation::Geometry => Some(0x06),
+            ResetOperation::Color => Some(0x08),
+            ResetOperation::SaveSettings => None,
+        }
+    }
+}
+
+impl fmt::Display for ResetOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResetOperation::FactoryDefaults => "restore factory defaults",
+            ResetOperation::LuminanceContrast => "restore factory luminance / contrast defaults",
+            ResetOperation::Geometry => "restore factory geometry defaults",
+            ResetOperation::Color => "restore factory color defaults",
+            ResetOperation::SaveSettings => "save current settings",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A [ResetOperation] prepared on one monitor handle by [Monitor::prepare_reset], along with the
+/// settings saved before it. Show it to the user and pass it to [Monitor::confirm_reset] once they
+/// agree.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResetPlan {
+    operation: ResetOperation,
+    before: MonitorSnapshot,
+    token: u64,
+}
+
+impl ResetPlan {
+    /// Prepared operation
+    pub fn operation(&self) -> ResetOperation {
+        self.operation
+    }
+
+    /// Monitor the operation was prepared on
+    pub fn identity(&self) -> &MonitorIdentity {
+        &self.before.identity
+    }
+
+    /// Settings saved when the operation was prepared
+    pub fn before(&self) -> &MonitorSnapshot {
+        &self.before
+    }
+}
+
+/// Confirmation to run a prepared [ResetPlan], given by [Monitor::confirm_reset]. It is consumed by
+/// [Monitor::reset], so every operation has to be prepared and confirmed separately.
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "a confirmation does nothing until passed to Monitor::reset"]
+pub struct Confirmation {
+    plan: ResetPlan,
+}
+
+impl Confirmation {
+    /// Confirmed operation
+    pub fn operation(&self) -> ResetOperation {
+        self.plan.operation
+    }
+
+    /// Returns `true` if this confirmation was given for the monitor `identity`
+    pub fn applies_to(&self, identity: &MonitorIdentity) -> bool {
+        self.plan.identity().same_monitor(identity)
+    }
+}
+
+/// Returns a token that tells a [ResetPlan] apart from the ones prepared before it
+#[cfg(target_os = "macos")]
+fn reset_token() -> u64 {
+    // Every RandomState gets new keys, so even hashing nothing gives a new value
+    RandomState::new().build_hasher().finish()
+}
+
+/// Outcome of a [ResetOperation]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetReport {
+    /// Operation that was run
+    pub operation: ResetOperation,
+    /// Settings before the operation
+    pub before: MonitorSnapshot,
+    /// Settings after the operation completed
+    pub after: MonitorSnapshot,
+}
+
+impl ResetReport {
+    /// Writes that would bring the monitor back to the settings it had before the operation
+    pub fn undo_changes(&self) -> Vec<SettingChange> {
+        self.before.diff(&self.after)
+    }
+}
+
+/// Guarded factory reset and save commands
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Prepares running `operation` on this [Monitor], see [Monitor::reset]. The settings are saved in
+    /// a snapshot first; if that fails, the operation cannot be run. Preparing another operation
+    /// invalidates the previous plan.
+    pub fn prepare_reset(&mut self, operation: ResetOperation) -> Result<ResetPlan, Error> {
+        let before = self.snapshot()?;
+        let token = reset_token();
+        self.set_pending_reset(Some(token));
+        Ok(ResetPlan {
+            operation,
+            before,
+            token,
+        })
+    }
+
+    /// Confirms running a prepared operation, see [Monitor::reset]. `identity` is the monitor the
+    /// user agreed to, e.g. as listed when asking. A confirmation is only given for the plan last
+    /// prepared on this handle, if it belongs to that monitor.
+    ///
+    /// This makes sure the settings were saved just before and the confirmation cannot be reused;
+    /// whether the user actually agreed is up to the caller, e.g. by showing them the plan.
+    pub fn confirm_reset(&self, plan: ResetPlan, identity: &MonitorIdentity) -> Result<Confirmation, Error> {
+        if self.pending_reset() != Some(plan.token) {
+            return Err(Error::ResetNotPrepared);
+        }
+        if !plan.identity().same_monitor(identity) {
+            return Err(Error::ConfirmationMismatch {
+                confirmed: identity.clone(),
+                monitor: plan.identity().clone(),
+            });
+        }
+        Ok(Confirmation { plan })
+    }
+
+    /// Runs a confirmed destructive operation.
+    ///
+    /// The operation is triggered, bypassing the [safe mode](Monitor::enable_safe_mode) lock of its
+    /// code, and the monitor is polled for up to `timeout` until it reports completion. The returned
+    /// report holds the settings saved by [Monitor::prepare_reset] and the ones after, so they can be
+    /// restored with [Monitor::restore_snapshot]. A confirmation is only accepted once, and only if
+    /// no other operation was prepared since.
+    pub fn reset(&mut self, confirmation: Confirmation, timeout: Duration) -> Result<ResetReport, Error> {
+        let ResetPlan {
+            operation,
+            before,
+            token,
+        } = confirmation.plan;
+        if self.pending_reset() != Some(token) {
+            return Err(Error::ResetNotPrepared);
+        }
+        self.set_pending_reset(None);
+        match operation.code() {
+            Some(code) => {
+                let safe_mode = self.safe_mode().cloned();
+                if let Some(unlocked) = self.safe_mode_mut() {
+                    unlocked.unlock(code);
+                }
+                self.sleep();
+                let result = self.set_vcp_feature(code, 1);
+                self.set_safe_mode(safe_mode);
+                result?;
+            }
+            None => {
+                self.sleep();
+                self.save_current_settings()?;
+            }
+        }
+        poll_until(timeout, || Ok(self.operation_completed(operation).then_some(())))?
+            .ok_or(Error::Timeout(timeout))?;
+        let codes: Vec<FeatureCode> = before.values.iter().map(|value| value.code).collect();
+        let after = self.snapshot_codes(&codes);
+        Ok(ResetReport {
+            operation,
+            before,
+            after,
+        })
+    }
+
+    /// Polls the monitor once for completion of `operation`. The monitor reports a restore as done by
+    /// reading back zero from its code; monitors that cannot read the code back, and the save
+    /// command, are considered done as soon as they answer DDC/CI requests again.
+    fn operation_completed(&mut self, operation: ResetOperation) -> bool {
+        self.sleep();
+        if let Some(code) = operation.code() {
+            match self.get_vcp_feature(code) {
+                Ok(value) => return value.value() == 0,
+                Err(Error::NullMessage) => return false,
+                Err(_) => self.sleep(),
+            }
+        }
+        self.get_vcp_feature(VCP_BRIGHTNESS).is_ok()
+    }
+}
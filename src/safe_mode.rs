@@ -0,0 +1,111 @@
+#![deny(missing_docs)]
+
+use crate::capabilities::Capabilities;
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use crate::quirks::Quirks;
+use ddc::FeatureCode;
+use std::collections::BTreeSet;
+
+/// VCP codes that reset monitor settings to factory defaults, which safe mode blocks unless unlocked
+pub const DESTRUCTIVE_CODES: &[FeatureCode] = &[0x04, 0x05, 0x06, 0x08, 0x0a];
+
+/// Opcode of the Set VCP Feature command
+pub(crate) const SET_VCP_FEATURE_OPCODE: u8 = 0x03;
+
+/// Opcode of the Table Write command
+const TABLE_WRITE_OPCODE: u8 = 0xe7;
+
+/// Opcode of the Save Current Settings command
+const SAVE_CURRENT_SETTINGS_OPCODE: u8 = 0x0c;
+
+/// Write restrictions of a [Monitor] in safe mode, see [Monitor::enable_safe_mode]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafeMode {
+    unlocked: BTreeSet<FeatureCode>,
+}
+
+impl SafeMode {
+    /// Create a safe mode with all [DESTRUCTIVE_CODES] locked
+    pub fn new() -> Self {
+        SafeMode::default()
+    }
+
+    /// Allows writes to the destructive VCP feature `code`
+    pub fn unlock(&mut self, code: FeatureCode) {
+        self.unlocked.insert(code);
+    }
+
+    /// Blocks writes to the destructive VCP feature `code` again
+    pub fn lock(&mut self, code: FeatureCode) {
+        self.unlocked.remove(&code);
+    }
+
+    /// Returns `true` if writes to the VCP feature `code` are blocked regardless of the capabilities
+    pub fn is_locked(&self, code: FeatureCode) -> bool {
+        DESTRUCTIVE_CODES.contains(&code) && !self.unlocked.contains(&code)
+    }
+
+    /// Checks that writing `value` to the VCP feature `code` is allowed: the code is not a locked
+    /// destructive code, the monitor advertises it in `capabilities`, and for features with a list of
+    /// allowed values, `value` is one of them. A `value` of `None` stands for a table write.
+    pub fn check_write(&self, capabilities: &Capabilities, code: FeatureCode, value: Option<u16>) -> Result<(), Error> {
+        if self.is_locked(code) {
+            return Err(Error::DestructiveFeature { code });
+        }
+        if !capabilities.supports_vcp(code) {
+            return Err(Error::UnsupportedFeature { code });
+        }
+        match value {
+            Some(value) => capabilities.check_value(code, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks an encoded DDC/CI command like [SafeMode::check_write] if it is a Set VCP Feature or
+    /// Table Write command. Save Current Settings is allowed once the capabilities are known, and
+    /// reads are always allowed. Writes fail if `capabilities` is `None`, i.e. they were not read.
+    /// The value written is decoded with `quirks` first, as the capabilities list MCCS values rather
+    /// than what the monitor expects.
+    pub fn check_command(
+        &self,
+        capabilities: Option<&Capabilities>,
+        quirks: &Quirks,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let (code, value) = match data {
+            [SET_VCP_FEATURE_OPCODE, code, high, low, ..] => (
+                Some(*code),
+                Some(quirks.decode_value(*code, u16::from_be_bytes([*high, *low]))),
+            ),
+            [TABLE_WRITE_OPCODE, code, ..] => (Some(*code), None),
+            [SAVE_CURRENT_SETTINGS_OPCODE, ..] => (None, None),
+            _ => return Ok(()),
+        };
+        let capabilities = capabilities
+            .ok_or_else(|| Error::CapabilitiesUnavailable("safe mode needs them to check writes".into()))?;
+        match code {
+            Some(code) => self.check_write(capabilities, code, value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Capability-guarded writes
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Turns on safe mode with all [DESTRUCTIVE_CODES] locked. The capabilities are read now and
+    /// cached; from then on, writes to VCP features or values the monitor does not advertise, and to
+    /// locked destructive codes, fail with a descriptive error without being sent to the monitor.
+    pub fn enable_safe_mode(&mut self) -> Result<(), Error> {
+        self.cached_capabilities()?;
+        self.set_safe_mode(Some(SafeMode::new()));
+        Ok(())
+    }
+
+    /// Turns off safe mode
+    pub fn disable_safe_mode(&mut self) {
+        self.set_safe_mode(None);
+    }
+}
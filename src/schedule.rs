@@ -0,0 +1,635 @@
+#![deny(missing_docs)]
+
+use crate::backend::{DisplayDescription, MonitorSelector};
+use crate::color::{ColorTemperature, VCP_COLOR_TEMPERATURE_INCREMENT, VCP_COLOR_TEMPERATURE_REQUEST};
+use crate::controls::VCP_BRIGHTNESS;
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+#[cfg(target_os = "macos")]
+use crate::monitor_set::MonitorSet;
+use crate::transition::{run_transition, TransitionControl, TransitionOptions, TransitionOutcome};
+#[cfg(target_os = "macos")]
+use core_foundation::date::CFDate;
+#[cfg(target_os = "macos")]
+use core_foundation::timezone::CFTimeZone;
+use ddc::{Ddc, DdcHost};
+use std::fmt;
+use std::str::FromStr;
+#[cfg(target_os = "macos")]
+use std::thread;
+use std::time::Duration;
+#[cfg(target_os = "macos")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of seconds in a day
+const SECONDS_PER_DAY: u32 = 86_400;
+#[cfg(target_os = "macos")]
+/// Longest sleep of [run_schedule] between two checks for cancellation
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How soon [run_schedule] tries again to update monitors that failed
+pub const SCHEDULE_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A time of day, to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct TimeOfDay(u32);
+
+impl TimeOfDay {
+    /// Midnight, the start of the day
+    pub const MIDNIGHT: TimeOfDay = TimeOfDay(0);
+
+    /// Creates a time of day from hours and minutes
+    pub fn new(hour: u32, minute: u32) -> Result<Self, Error> {
+        if hour >= 24 || minute >= 60 {
+            return Err(Error::InvalidArgument(format!(
+                "invalid time of day {}:{:02}",
+                hour, minute
+            )));
+        }
+        Ok(TimeOfDay(hour * 3600 + minute * 60))
+    }
+
+    /// Creates a time of day from the seconds since midnight, wrapping around at the end of the day
+    pub fn from_seconds(seconds: u32) -> Self {
+        TimeOfDay(seconds % SECONDS_PER_DAY)
+    }
+
+    /// Seconds since midnight
+    pub fn seconds(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 3600, self.0 / 60 % 60)
+    }
+}
+
+/// Parses a time given as `HH:MM`
+impl FromStr for TimeOfDay {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidArgument(format!("invalid time of day {:?}", s));
+        let (hour, minute) = s.split_once(':').ok_or_else(invalid)?;
+        TimeOfDay::new(
+            hour.parse().map_err(|_| invalid())?,
+            minute.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+/// A moment in local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalTime {
+    /// Local date, as the number of days since 1970-01-01
+    pub day: i64,
+    /// Local time of day
+    pub time: TimeOfDay,
+    /// Offset of the local time zone from UTC in seconds, positive east of Greenwich
+    pub utc_offset: i32,
+}
+
+impl LocalTime {
+    /// Creates a local time from a calendar date
+    pub fn new(year: i32, month: u32, day: u32, time: TimeOfDay, utc_offset: i32) -> Self {
+        // Days from civil algorithm, see http://howardhinnant.github.io/date_algorithms.html
+        let year = i64::from(year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from(month);
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        LocalTime {
+            day: era * 146_097 + day_of_era - 719_468,
+            time,
+            utc_offset,
+        }
+    }
+
+    /// Local time of a UNIX timestamp in the time zone `utc_offset` seconds east of UTC
+    pub fn from_unix(seconds: i64, utc_offset: i32) -> Self {
+        let local = seconds + i64::from(utc_offset);
+        LocalTime {
+            day: local.div_euclid(i64::from(SECONDS_PER_DAY)),
+            time: TimeOfDay(local.rem_euclid(i64::from(SECONDS_PER_DAY)) as u32),
+            utc_offset,
+        }
+    }
+
+    /// The current time in the system time zone
+    #[cfg(target_os = "macos")]
+    pub fn now() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64);
+        let utc_offset = CFTimeZone::system().seconds_from_gmt(CFDate::now()) as i32;
+        LocalTime::from_unix(seconds, utc_offset)
+    }
+
+    /// UNIX timestamp of this time
+    pub fn to_unix(self) -> i64 {
+        self.local_seconds() - i64::from(self.utc_offset)
+    }
+
+    /// The same time zone `duration` later
+    pub fn after(self, duration: Duration) -> Self {
+        LocalTime::from_unix(self.to_unix() + duration.as_secs() as i64, self.utc_offset)
+    }
+
+    /// Time from this moment until `later`, zero if it is not later
+    pub fn until(self, later: LocalTime) -> Duration {
+        Duration::from_secs((later.to_unix() - self.to_unix()).max(0) as u64)
+    }
+
+    fn local_seconds(self) -> i64 {
+        self.day * i64::from(SECONDS_PER_DAY) + i64::from(self.time.0)
+    }
+}
+
+/// A place on earth, to compute the times of sunrise and sunset
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// Latitude in degrees, positive north of the equator
+    pub latitude: f64,
+    /// Longitude in degrees, positive east of Greenwich
+    pub longitude: f64,
+}
+
+/// Local times of sunrise and sunset of a day. Both are `None` during polar day and polar night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SunTimes {
+    /// Time the upper edge of the sun rises above the horizon
+    pub sunrise: Option<TimeOfDay>,
+    /// Time the upper edge of the sun sets below the horizon
+    pub sunset: Option<TimeOfDay>,
+}
+
+/// Computes the times of sunrise and sunset on the local `day` (days since 1970-01-01) at
+/// `location`, in the time zone `utc_offset` seconds east of UTC. Uses the sunrise equation, which
+/// is accurate to a few minutes away from the polar circles.
+pub fn sun_times(day: i64, location: &Location, utc_offset: i32) -> SunTimes {
+    const J2000: f64 = 2_451_545.0;
+    const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+    let (sin, cos) = (
+        |degrees: f64| degrees.to_radians().sin(),
+        |degrees: f64| degrees.to_radians().cos(),
+    );
+
+    // Julian days since J2000 of the local noon, as solar noon at the given longitude
+    let mean_solar_noon =
+        (day as f64 + UNIX_EPOCH_JULIAN_DAY + 0.5 - J2000).round() + 0.0008 - location.longitude / 360.0;
+    let mean_anomaly = (357.5291 + 0.985_600_28 * mean_solar_noon).rem_euclid(360.0);
+    let center = 1.9148 * sin(mean_anomaly) + 0.02 * sin(2.0 * mean_anomaly) + 0.0003 * sin(3.0 * mean_anomaly);
+    let ecliptic_longitude = (mean_anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + mean_solar_noon + 0.0053 * sin(mean_anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+    let declination = (sin(ecliptic_longitude) * sin(23.4397)).asin().to_degrees();
+    let hour_angle =
+        (sin(-0.833) - sin(location.latitude) * sin(declination)) / (cos(location.latitude) * cos(declination));
+    if !(-1.0..=1.0).contains(&hour_angle) {
+        return SunTimes {
+            sunrise: None,
+            sunset: None,
+        };
+    }
+    let hour_angle = hour_angle.acos().to_degrees();
+    let local_time = |julian_day: f64| {
+        let unix = ((julian_day - UNIX_EPOCH_JULIAN_DAY) * f64::from(SECONDS_PER_DAY)).round() as i64;
+        Some(LocalTime::from_unix(unix, utc_offset).time)
+    };
+    SunTimes {
+        sunrise: local_time(transit - hour_angle / 360.0),
+        sunset: local_time(transit + hour_angle / 360.0),
+    }
+}
+
+/// When a point of a [Schedule] takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub enum ScheduleTime {
+    /// At a fixed time of day
+    At(TimeOfDay),
+    /// The given number of minutes after sunrise, or before it if negative
+    Sunrise(i32),
+    /// The given number of minutes after sunset, or before it if negative
+    Sunset(i32),
+}
+
+impl ScheduleTime {
+    /// Time of day on a day with the given sun times; `None` for times relative to the sun on days
+    /// it does not rise or set. Offsets reaching past midnight wrap around, e.g. `sunset+240` after
+    /// a sunset at 22:00 is at 02:00.
+    pub fn resolve(self, sun: &SunTimes) -> Option<TimeOfDay> {
+        let seconds = self.seconds_from_midnight(sun)?;
+        Some(TimeOfDay(seconds.rem_euclid(i64::from(SECONDS_PER_DAY)) as u32))
+    }
+
+    /// Seconds from the start of a day with the given sun times, negative or past the end of the
+    /// day if an offset reaches into the day before or after
+    fn seconds_from_midnight(self, sun: &SunTimes) -> Option<i64> {
+        let (time, minutes) = match self {
+            ScheduleTime::At(time) => return Some(i64::from(time.0)),
+            ScheduleTime::Sunrise(minutes) => (sun.sunrise?, minutes),
+            ScheduleTime::Sunset(minutes) => (sun.sunset?, minutes),
+        };
+        Some(i64::from(time.0) + i64::from(minutes) * 60)
+    }
+
+    fn is_relative_to_sun(self) -> bool {
+        !matches!(self, ScheduleTime::At(_))
+    }
+}
+
+impl fmt::Display for ScheduleTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (event, minutes) = match self {
+            ScheduleTime::At(time) => return write!(f, "{}", time),
+            ScheduleTime::Sunrise(minutes) => ("sunrise", *minutes),
+            ScheduleTime::Sunset(minutes) => ("sunset", *minutes),
+        };
+        match minutes {
+            0 => write!(f, "{}", event),
+            minutes => write!(f, "{}{:+}", event, minutes),
+        }
+    }
+}
+
+/// Parses a time given as `HH:MM`, `sunrise` or `sunset`, optionally followed by an offset in
+/// minutes like `sunset-30`
+impl FromStr for ScheduleTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let relative = |rest: &str| match rest {
+            "" => Ok(0),
+            offset if offset.starts_with(['+', '-']) => offset.parse::<i32>().map_err(|_| ()),
+            _ => Err(()),
+        };
+        let invalid = |_| Error::InvalidArgument(format!("invalid schedule time {:?}", s));
+        if let Some(rest) = s.strip_prefix("sunrise") {
+            relative(rest).map(ScheduleTime::Sunrise).map_err(invalid)
+        } else if let Some(rest) = s.strip_prefix("sunset") {
+            relative(rest).map(ScheduleTime::Sunset).map_err(invalid)
+        } else {
+            s.parse().map(ScheduleTime::At)
+        }
+    }
+}
+
+impl TryFrom<String> for ScheduleTime {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ScheduleTime> for String {
+    fn from(time: ScheduleTime) -> Self {
+        time.to_string()
+    }
+}
+
+/// Settings a [Schedule] reaches at a given time. Settings left `None` are not changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleTarget {
+    /// Brightness (VCP 0x10)
+    pub brightness: Option<u16>,
+    /// Color temperature in Kelvin, see [ColorTemperature]
+    pub color_temperature: Option<u32>,
+}
+
+/// A point of the curve of a [Schedule]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchedulePoint {
+    /// When the point takes effect
+    pub at: ScheduleTime,
+    /// Settings at that time
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub target: ScheduleTarget,
+}
+
+/// How a [Schedule] moves between its points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Interpolation {
+    /// Settings change at the points and are held until the next one
+    Step,
+    /// Settings change gradually from one point to the next
+    #[default]
+    Linear,
+}
+
+/// Brightness and color temperature over the day, as a curve through points at fixed times or
+/// relative to sunrise and sunset.
+///
+/// Each setting follows the points that set it, wrapping around midnight, so a point can change
+/// the brightness only. With [Interpolation::Linear], a setting changes over the whole time between
+/// two points: points with equal settings hold them, e.g. from an hour after sunrise to an hour
+/// before sunset.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Schedule {
+    /// Location for the times of sunrise and sunset, required by points relative to them
+    pub location: Option<Location>,
+    /// How settings move between points
+    pub interpolation: Interpolation,
+    /// Interval between updates while settings change gradually, in milliseconds
+    pub update_interval_ms: u64,
+    /// Duration of the transition of each update, in milliseconds
+    pub transition_ms: u64,
+    /// Selectors of the monitors to control, see [MonitorSelector]; all monitors if empty
+    pub monitors: Vec<String>,
+    /// Points of the curve, in any order
+    #[cfg_attr(feature = "serde", serde(rename = "point"))]
+    pub points: Vec<SchedulePoint>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            location: None,
+            interpolation: Interpolation::default(),
+            update_interval_ms: 60_000,
+            transition_ms: 2_000,
+            monitors: Vec::new(),
+            points: Vec::new(),
+        }
+    }
+}
+
+impl Schedule {
+    /// Parses a schedule from TOML, with one `[[point]]` table per point:
+    ///
+    /// ```toml
+    /// location = { latitude = 52.37, longitude = 4.90 }
+    /// monitors = ["name=DELL U2720Q"]
+    ///
+    /// [[point]]
+    /// at = "sunrise"
+    /// brightness = 40
+    /// color_temperature = 5000
+    ///
+    /// [[point]]
+    /// at = "sunrise+60"
+    /// brightness = 80
+    /// color_temperature = 6500
+    ///
+    /// [[point]]
+    /// at = "sunset-60"
+    /// brightness = 80
+    ///
+    /// [[point]]
+    /// at = "22:30"
+    /// brightness = 20
+    /// color_temperature = 3400
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let schedule: Schedule =
+            toml::from_str(text).map_err(|error| Error::InvalidConfiguration(error.to_string()))?;
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Loads a schedule from a TOML file, see [Schedule::from_toml]
+    #[cfg(feature = "toml")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks the monitor selectors, and that points relative to the sun come with a location
+    pub fn validate(&self) -> Result<(), Error> {
+        for selector in &self.monitors {
+            selector
+                .parse::<MonitorSelector>()
+                .map_err(|error| Error::InvalidConfiguration(error.to_string()))?;
+        }
+        if self.location.is_none() && self.points.iter().any(|point| point.at.is_relative_to_sun()) {
+            return Err(Error::InvalidConfiguration(
+                "points relative to sunrise or sunset need a location".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the schedule controls the display at `index`
+    pub fn selects(&self, index: usize, display: &DisplayDescription) -> Result<bool, Error> {
+        if self.monitors.is_empty() {
+            return Ok(true);
+        }
+        for selector in &self.monitors {
+            if selector.parse::<MonitorSelector>()?.matches(index, display) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Sun times of the local `day` at the location of the schedule
+    pub fn sun_times(&self, day: i64, utc_offset: i32) -> SunTimes {
+        match &self.location {
+            Some(location) => sun_times(day, location, utc_offset),
+            None => SunTimes {
+                sunrise: None,
+                sunset: None,
+            },
+        }
+    }
+
+    /// Points around `now`, from the day before to the day after, as local seconds and sorted.
+    /// Points relative to the sun are left out on days it does not rise or set.
+    fn points_around(&self, now: LocalTime) -> Vec<(i64, &SchedulePoint)> {
+        let mut points: Vec<(i64, &SchedulePoint)> = (now.day - 1..=now.day + 1)
+            .flat_map(|day| {
+                let sun = self.sun_times(day, now.utc_offset);
+                self.points.iter().filter_map(move |point| {
+                    let seconds = point.at.seconds_from_midnight(&sun)?;
+                    Some((day * i64::from(SECONDS_PER_DAY) + seconds, point))
+                })
+            })
+            .collect();
+        points.sort_by_key(|(seconds, _)| *seconds);
+        points
+    }
+
+    /// Settings the schedule reaches at `now`
+    pub fn target_at(&self, now: LocalTime) -> ScheduleTarget {
+        let points = self.points_around(now);
+        let now = now.local_seconds();
+        ScheduleTarget {
+            brightness: self
+                .setting_at(&points, now, |target| target.brightness.map(u32::from))
+                .map(|value| value.min(u32::from(u16::MAX)) as u16),
+            color_temperature: self.setting_at(&points, now, |target| target.color_temperature),
+        }
+    }
+
+    fn setting_at<F>(&self, points: &[(i64, &SchedulePoint)], now: i64, setting: F) -> Option<u32>
+    where
+        F: Fn(&ScheduleTarget) -> Option<u32>,
+    {
+        let mut setting_points = points
+            .iter()
+            .filter_map(|(seconds, point)| Some((*seconds, setting(&point.target)?)));
+        let mut previous = None;
+        let next = loop {
+            match setting_points.next() {
+                Some((seconds, value)) if seconds <= now => previous = Some((seconds, value)),
+                next => break next,
+            }
+        };
+        let (since, from) = previous?;
+        match (self.interpolation, next) {
+            (Interpolation::Linear, Some((until, to))) => {
+                let progress = (now - since) as f64 / (until - since) as f64;
+                Some((f64::from(from) + (f64::from(to) - f64::from(from)) * progress).round() as u32)
+            }
+            _ => Some(from),
+        }
+    }
+
+    /// When the settings next change after `now`: the next point, or while settings change
+    /// gradually, [Schedule::update_interval_ms] from now. `None` for a schedule without points.
+    pub fn next_change(&self, now: LocalTime) -> Option<LocalTime> {
+        let next_point = self
+            .points_around(now)
+            .into_iter()
+            .map(|(seconds, _)| seconds)
+            .find(|seconds| *seconds > now.local_seconds())?;
+        let next_point = now.after(Duration::from_secs((next_point - now.local_seconds()) as u64));
+        if self.interpolation == Interpolation::Linear && self.target_at(now) != self.target_at(next_point) {
+            let update = now.after(Duration::from_millis(self.update_interval_ms.max(1000)));
+            if update.to_unix() < next_point.to_unix() {
+                return Some(update);
+            }
+        }
+        Some(next_point)
+    }
+
+    /// Timing of the transitions of each update
+    pub fn transition_options(&self) -> TransitionOptions {
+        TransitionOptions {
+            duration: Duration::from_millis(self.transition_ms),
+            ..TransitionOptions::default()
+        }
+    }
+}
+
+/// Moves brightness and color temperature to `target`. Color temperature is left alone if the
+/// monitor does not report its increment. Returns `false` if `control` cancelled a transition.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn apply_target<D>(
+    ddc: &mut D,
+    target: &ScheduleTarget,
+    options: &TransitionOptions,
+    control: &TransitionControl,
+) -> Result<bool, Error>
+where
+    D: Ddc<Error = Error> + DdcHost,
+{
+    if let Some(brightness) = target.brightness {
+        if let TransitionOutcome::Cancelled(_) = run_transition(ddc, VCP_BRIGHTNESS, brightness, options, control)? {
+            return Ok(false);
+        }
+    }
+    if let Some(kelvin) = target.color_temperature {
+        ddc.sleep();
+        if let Ok(increment) = ddc.get_vcp_feature(VCP_COLOR_TEMPERATURE_INCREMENT) {
+            let request = ColorTemperature::for_kelvin(kelvin, increment.value()).request;
+            let outcome = run_transition(ddc, VCP_COLOR_TEMPERATURE_REQUEST, request, options, control)?;
+            return Ok(matches!(outcome, TransitionOutcome::Completed(_)));
+        }
+    }
+    Ok(true)
+}
+
+/// Time-of-day schedules
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Fades brightness and color temperature to a target of a [Schedule]. Color temperature is
+    /// left alone if the monitor does not report its increment (VCP 0x0B). Returns `false` if
+    /// `control` cancelled a transition, in which case the following ones are not started.
+    pub fn apply_schedule_target(
+        &mut self,
+        target: &ScheduleTarget,
+        options: &TransitionOptions,
+        control: &TransitionControl,
+    ) -> Result<bool, Error> {
+        apply_target(self, target, options, control)
+    }
+}
+
+/// Runs `schedule` on the monitors it selects until `control` is cancelled.
+///
+/// The monitors are moved to the current target right away and then whenever
+/// [Schedule::next_change] says it changes, concurrently across buses. A monitor that fails, e.g.
+/// because it is asleep, is tried again on its own after [SCHEDULE_RETRY_INTERVAL], or at the
+/// next change if that comes first.
+#[cfg(target_os = "macos")]
+pub fn run_schedule(monitors: &mut MonitorSet, schedule: &Schedule, control: &TransitionControl) -> Result<(), Error> {
+    schedule.validate()?;
+    let selected = monitors
+        .monitors()
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| schedule.selects(index, &monitor.display_description()))
+        .collect::<Result<Vec<bool>, Error>>()?;
+    let options = schedule.transition_options();
+    let mut pending = selected.clone();
+    while !control.is_cancelled() {
+        let now = LocalTime::now();
+        let target = schedule.target_at(now);
+        let results = monitors.run(|index, monitor| {
+            if pending[index] {
+                monitor.apply_schedule_target(&target, &options, control)?;
+            }
+            Ok(())
+        });
+        for (pending, result) in pending.iter_mut().zip(&results) {
+            *pending = *pending && result.is_err();
+        }
+        let mut wait = match schedule.next_change(now) {
+            Some(next) => LocalTime::now().until(next),
+            None => Duration::from_millis(schedule.update_interval_ms),
+        };
+        if pending.contains(&true) && wait > SCHEDULE_RETRY_INTERVAL {
+            wait = SCHEDULE_RETRY_INTERVAL;
+        } else {
+            pending.clone_from(&selected);
+        }
+        let wake = SystemTime::now() + wait;
+        while !control.is_cancelled() {
+            match wake.duration_since(SystemTime::now()) {
+                Ok(left) if !left.is_zero() => thread::sleep(left.min(CANCEL_CHECK_INTERVAL)),
+                _ => break,
+            }
+        }
+    }
+    Ok(())
+}
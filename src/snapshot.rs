@@ -0,0 +1,347 @@
+#![deny(missing_docs)]
+
+use crate::backend::DisplayBackend;
+#[cfg(target_os = "macos")]
+use crate::backend::SingleMonitor;
+use crate::capabilities::Capabilities;
+use crate::catalog::{vcp_feature, VcpFeatureType};
+use crate::error::Error;
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::{FeatureCode, VcpValue};
+use std::fmt;
+use std::str::FromStr;
+
+/// VCP codes that are never written back when restoring a snapshot: they switch inputs, change the
+/// power state, lock the OSD or trigger one-shot actions rather than hold a setting.
+pub const RESTORE_EXCLUDED_CODES: &[FeatureCode] = &[0x02, 0x03, 0x1e, 0x1f, 0x52, 0x60, 0xa2, 0xa5, 0xb0, 0xca, 0xd6];
+
+/// VCP codes selecting modes that reset other settings, which therefore have to be restored first
+const RESTORE_FIRST_CODES: &[FeatureCode] = &[0x14, 0xdc];
+
+/// Saved value of a single VCP feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotValue {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Value at the time of the snapshot
+    pub value: u16,
+    /// Maximum value reported by the monitor
+    pub maximum: u16,
+}
+
+/// Saved state of a monitor's VCP features
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorSnapshot {
+    /// Monitor the snapshot was taken from
+    pub identity: MonitorIdentity,
+    /// Saved VCP feature values
+    pub values: Vec<SnapshotValue>,
+    /// VCP features that could not be read when the snapshot was taken. They are written as comments
+    /// in the text format, so they are not read back from it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub skipped: Vec<FeatureCode>,
+}
+
+/// A single VCP feature write needed to restore a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SettingChange {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Current value, if it could be read
+    pub from: Option<u16>,
+    /// Value to be written
+    pub to: u16,
+}
+
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match vcp_feature(self.code) {
+            Some(feature) => write!(f, "{}", feature.name)?,
+            None => write!(f, "VCP 0x{:02x}", self.code)?,
+        }
+        match self.from {
+            Some(from) => write!(f, ": {} -> {}", from, self.to),
+            None => write!(f, ": ? -> {}", self.to),
+        }
+    }
+}
+
+/// Returns `true` if a VCP feature holds a setting that can be safely written back
+pub fn is_restorable(code: FeatureCode) -> bool {
+    vcp_feature(code).is_some_and(|feature| {
+        feature.is_readable()
+            && feature.is_writable()
+            && feature.ty != VcpFeatureType::Table
+            && !RESTORE_EXCLUDED_CODES.contains(&code)
+    })
+}
+
+/// Order in which settings are restored: mode selections first, then other enumerated settings,
+/// then continuous values which the former could have overwritten.
+pub(crate) fn restore_order(code: FeatureCode) -> u8 {
+    if RESTORE_FIRST_CODES.contains(&code) {
+        0
+    } else if vcp_feature(code).is_some_and(|feature| feature.ty == VcpFeatureType::NonContinuous) {
+        1
+    } else {
+        2
+    }
+}
+
+/// VCP features [Monitor::snapshot] saves from a monitor with the given capabilities: all readable
+/// continuous and non-continuous features it advertises
+pub fn snapshot_features(capabilities: &Capabilities) -> Vec<FeatureCode> {
+    capabilities
+        .vcp_features
+        .keys()
+        .copied()
+        .filter(|code| {
+            vcp_feature(*code).is_some_and(|feature| feature.is_readable() && feature.ty != VcpFeatureType::Table)
+        })
+        .collect()
+}
+
+impl MonitorSnapshot {
+    /// Saved value of the VCP feature `code`
+    pub fn value(&self, code: FeatureCode) -> Option<u16> {
+        self.values
+            .iter()
+            .find(|value| value.code == code)
+            .map(|value| value.value)
+    }
+
+    /// Returns the writes needed to bring a monitor from its `current` state back to this snapshot,
+    /// in the order they should be applied. Settings that are unchanged or not restorable are left out.
+    pub fn diff(&self, current: &MonitorSnapshot) -> Vec<SettingChange> {
+        let mut changes: Vec<SettingChange> = self
+            .values
+            .iter()
+            .filter(|saved| is_restorable(saved.code))
+            .map(|saved| SettingChange {
+                code: saved.code,
+                from: current.value(saved.code),
+                to: saved.value,
+            })
+            .filter(|change| change.from != Some(change.to))
+            .collect();
+        changes.sort_by_key(|change| (restore_order(change.code), change.code));
+        changes
+    }
+}
+
+/// Restores a snapshot to the display at `display` of `backend`, which has to be the monitor the
+/// snapshot was taken from or another one of the same model.
+///
+/// Only settings that differ from the current state are written, and read-only or disruptive
+/// features (see [RESTORE_EXCLUDED_CODES]) are skipped. Mode selections such as the color preset are
+/// written first; as they can reset other settings, those are read and compared again afterwards.
+/// With `dry_run`, nothing is written and the returned list shows what would change, as far as it
+/// can be known before any mode changed.
+pub fn restore_snapshot<B: DisplayBackend + ?Sized>(
+    backend: &mut B,
+    display: usize,
+    snapshot: &MonitorSnapshot,
+    dry_run: bool,
+) -> Result<Vec<SettingChange>, Error> {
+    let identity = backend
+        .displays()?
+        .into_iter()
+        .nth(display)
+        .ok_or_else(|| Error::MonitorNotFound(format!("display {}", display + 1)))?
+        .identity;
+    if !identity.same_model(&snapshot.identity) {
+        return Err(Error::SnapshotMismatch {
+            snapshot: snapshot.identity.clone(),
+            monitor: identity,
+        });
+    }
+    let codes: Vec<FeatureCode> = snapshot
+        .values
+        .iter()
+        .map(|value| value.code)
+        .filter(|code| is_restorable(*code))
+        .collect();
+    let (changes, _) = apply_settings(backend, display, &codes, |code, _| snapshot.value(code), dry_run)?;
+    Ok(changes)
+}
+
+/// Brings the VCP features `codes` of the display at `display` to their targets, computed by
+/// `target` from the current value (`None` if it could not be read), or left alone if `target`
+/// returns `None`. Mode selections are written first, see [restore_order]; once one changed, the
+/// other features are read and compared again, as the mode may have reset them. Returns the changes
+/// in the order they were written, or would be in a dry run, along with the features that could not
+/// be read.
+pub(crate) fn apply_settings<B, F>(
+    backend: &mut B,
+    display: usize,
+    codes: &[FeatureCode],
+    target: F,
+    dry_run: bool,
+) -> Result<(Vec<SettingChange>, Vec<FeatureCode>), Error>
+where
+    B: DisplayBackend + ?Sized,
+    F: Fn(FeatureCode, Option<&VcpValue>) -> Option<u16>,
+{
+    let (changes, unread) = plan_changes(backend, display, codes, &target);
+    if dry_run {
+        return Ok((changes, unread));
+    }
+    let (modes, mut others): (Vec<SettingChange>, Vec<SettingChange>) =
+        changes.into_iter().partition(|change| restore_order(change.code) == 0);
+    for change in &modes {
+        backend.set_vcp_feature(display, change.code, change.to)?;
+    }
+    if !modes.is_empty() {
+        let remaining: Vec<FeatureCode> = codes.iter().copied().filter(|code| restore_order(*code) != 0).collect();
+        others = plan_changes(backend, display, &remaining, &target).0;
+    }
+    for change in &others {
+        backend.set_vcp_feature(display, change.code, change.to)?;
+    }
+    Ok((modes.into_iter().chain(others).collect(), unread))
+}
+
+/// Reads the current values of `codes` and returns the changes needed to reach their targets, in
+/// [restore_order], along with the features that could not be read
+fn plan_changes<B, F>(
+    backend: &mut B,
+    display: usize,
+    codes: &[FeatureCode],
+    target: &F,
+) -> (Vec<SettingChange>, Vec<FeatureCode>)
+where
+    B: DisplayBackend + ?Sized,
+    F: Fn(FeatureCode, Option<&VcpValue>) -> Option<u16>,
+{
+    let mut changes = Vec::new();
+    let mut unread = Vec::new();
+    for &code in codes {
+        let current = backend.get_vcp_feature(display, code).ok();
+        if current.is_none() {
+            unread.push(code);
+        }
+        let from = current.as_ref().map(VcpValue::value);
+        match target(code, current.as_ref()) {
+            Some(to) if from != Some(to) => changes.push(SettingChange { code, from, to }),
+            _ => {}
+        }
+    }
+    changes.sort_by_key(|change| (restore_order(change.code), change.code));
+    (changes, unread)
+}
+
+/// Plain text form of a snapshot: a `monitor <ID> <name>` line followed by one `<code> <value>
+/// <maximum>` line per feature, with the code in hex. Empty lines and lines starting with `#` are
+/// ignored when parsing.
+impl fmt::Display for MonitorSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "monitor {}", self.identity)?;
+        if let Some(name) = &self.identity.name {
+            write!(f, " {}", name)?;
+        }
+        writeln!(f)?;
+        for value in &self.values {
+            writeln!(f, "0x{:02x} {} {}", value.code, value.value, value.maximum)?;
+        }
+        for code in &self.skipped {
+            writeln!(f, "# 0x{:02x} could not be read", code)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MonitorSnapshot {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = lines
+            .next()
+            .and_then(|line| line.strip_prefix("monitor "))
+            .ok_or_else(|| Error::InvalidSnapshot("missing monitor line".into()))?;
+        let (id, name) = header.trim().split_once(' ').unwrap_or((header.trim(), ""));
+        let mut identity: MonitorIdentity = id.parse()?;
+        identity.name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+        let values = lines
+            .map(|line| {
+                let invalid = || Error::InvalidSnapshot(format!("invalid line {:?}", line));
+                let mut fields = line.split_whitespace();
+                let (Some(code), Some(value), Some(maximum), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(invalid());
+                };
+                let code = code.strip_prefix("0x").unwrap_or(code);
+                Ok(SnapshotValue {
+                    code: FeatureCode::from_str_radix(code, 16).map_err(|_| invalid())?,
+                    value: value.parse().map_err(|_| invalid())?,
+                    maximum: maximum.parse().map_err(|_| invalid())?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(MonitorSnapshot {
+            identity,
+            values,
+            skipped: Vec::new(),
+        })
+    }
+}
+
+/// Reads the given VCP features of a display of `backend` into a snapshot, listing the ones that
+/// fail to read in [MonitorSnapshot::skipped]
+#[cfg_attr(not(any(target_os = "macos", feature = "cli")), allow(dead_code))]
+pub(crate) fn read_snapshot(
+    backend: &mut impl DisplayBackend,
+    index: usize,
+    identity: MonitorIdentity,
+    codes: &[FeatureCode],
+) -> MonitorSnapshot {
+    let mut snapshot = MonitorSnapshot {
+        identity,
+        values: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for &code in codes {
+        match backend.get_vcp_feature(index, code) {
+            Ok(value) => snapshot.values.push(SnapshotValue {
+                code,
+                value: value.value(),
+                maximum: value.maximum(),
+            }),
+            Err(_) => snapshot.skipped.push(code),
+        }
+    }
+    snapshot
+}
+
+/// Snapshots of monitor settings
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Saves the values of all readable continuous and non-continuous VCP features this [Monitor]
+    /// advertises in its capabilities. Features that fail to read are listed in [MonitorSnapshot::skipped].
+    pub fn snapshot(&mut self) -> Result<MonitorSnapshot, Error> {
+        let codes = snapshot_features(self.cached_capabilities()?);
+        Ok(self.snapshot_codes(&codes))
+    }
+
+    /// Saves the values of the given VCP features. Features that fail to read are listed in
+    /// [MonitorSnapshot::skipped].
+    pub fn snapshot_codes(&mut self, codes: &[FeatureCode]) -> MonitorSnapshot {
+        let identity = self.identity();
+        read_snapshot(&mut SingleMonitor(self), 0, identity, codes)
+    }
+
+    /// Restores a snapshot taken from this monitor or another one of the same model, see
+    /// [restore_snapshot]
+    pub fn restore_snapshot(&mut self, snapshot: &MonitorSnapshot, dry_run: bool) -> Result<Vec<SettingChange>, Error> {
+        restore_snapshot(&mut SingleMonitor(self), 0, snapshot, dry_run)
+    }
+}
@@ -0,0 +1,326 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::commands::{Command, CommandResult, TableWrite};
+use ddc::{DdcCommand, ErrorCode, FeatureCode};
+
+/// Maximum number of data bytes carried by a single table read or write fragment
+pub const TABLE_FRAGMENT_SIZE: usize = 32;
+
+/// Number of attempts made to transfer a single table fragment before giving up
+pub const TABLE_FRAGMENT_RETRIES: usize = 3;
+
+/// Largest table that will be read before assuming the monitor never terminates the transfer
+pub const TABLE_MAX_SIZE: usize = 0x8000;
+
+/// Table Read request (opcode 0xE2) for the fragment at `offset` of a table VCP feature.
+///
+/// Unlike [ddc::commands::TableRead], its reply accepts the empty fragment that marks the end of a table.
+#[derive(Copy, Clone, Debug)]
+pub struct TableReadFragment {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Offset of the requested fragment
+    pub offset: u16,
+}
+
+impl Command for TableReadFragment {
+    type Ok = TableFragment;
+    const MIN_LEN: usize = 4;
+    const MAX_LEN: usize = 4;
+    const DELAY_RESPONSE_MS: u64 = 40;
+    const DELAY_COMMAND_MS: u64 = 50;
+
+    fn len(&self) -> usize {
+        4
+    }
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, ErrorCode> {
+        if data.len() < 4 {
+            return Err(ErrorCode::InvalidLength);
+        }
+        data[0] = 0xe2;
+        data[1] = self.code;
+        data[2] = (self.offset >> 8) as u8;
+        data[3] = self.offset as u8;
+        Ok(4)
+    }
+}
+
+/// A fragment of table data from a Table Read Reply (opcode 0xE4)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableFragment {
+    /// Offset of this fragment within the table
+    pub offset: u16,
+    /// Fragment data, empty at the end of the table
+    pub data: Vec<u8>,
+}
+
+impl CommandResult for TableFragment {
+    const MAX_LEN: usize = 3 + TABLE_FRAGMENT_SIZE;
+
+    fn decode(data: &[u8]) -> Result<Self, ErrorCode> {
+        if data.len() < 3 || data.len() > Self::MAX_LEN {
+            return Err(ErrorCode::InvalidLength);
+        }
+        if data[0] != 0xe4 {
+            return Err(ErrorCode::InvalidOpcode);
+        }
+        Ok(TableFragment {
+            offset: u16::from_be_bytes([data[1], data[2]]),
+            data: data[3..].to_vec(),
+        })
+    }
+}
+
+/// Splits `data` into fragments of at most [TABLE_FRAGMENT_SIZE] bytes, along with the table
+/// offset each fragment has to be written at.
+pub fn table_fragments(offset: u16, data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    data.chunks(TABLE_FRAGMENT_SIZE)
+        .enumerate()
+        .map(move |(index, chunk)| (offset.wrapping_add((index * TABLE_FRAGMENT_SIZE) as u16), chunk))
+}
+
+/// Runs `transfer` up to [TABLE_FRAGMENT_RETRIES] times, reporting the last error along with the fragment offset
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn retry_fragment<D, T>(
+    ddc: &mut D,
+    code: FeatureCode,
+    offset: u16,
+    mut transfer: impl FnMut(&mut D) -> Result<T, Error>,
+) -> Result<T, Error>
+where
+    D: DdcCommand<Error = Error>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        ddc.sleep();
+        match transfer(ddc) {
+            Ok(result) => return Ok(result),
+            Err(error) if attempt >= TABLE_FRAGMENT_RETRIES => {
+                return Err(Error::TableFragment {
+                    code,
+                    offset,
+                    source: Box::new(error),
+                })
+            }
+            Err(_) => continue,
+        }
+    }
+}
+
+/// Reads a whole table, fragment by fragment, until the monitor returns a fragment shorter than
+/// [TABLE_FRAGMENT_SIZE], which is the last one, or an empty fragment
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn read_table<D>(ddc: &mut D, code: FeatureCode) -> Result<Vec<u8>, Error>
+where
+    D: DdcCommand<Error = Error>,
+{
+    let mut table = Vec::new();
+    loop {
+        let offset = table.len() as u16;
+        let fragment = retry_fragment(ddc, code, offset, |ddc| {
+            let fragment = ddc.execute(TableReadFragment { code, offset })?;
+            if fragment.offset != offset {
+                return Err(Error::Ddc(ErrorCode::InvalidOffset));
+            }
+            Ok(fragment.data)
+        })?;
+        let last = fragment.len() < TABLE_FRAGMENT_SIZE;
+        table.extend_from_slice(&fragment);
+        if last {
+            return Ok(table);
+        }
+        if table.len() > TABLE_MAX_SIZE {
+            return Err(Error::TableFragment {
+                code,
+                offset,
+                source: Box::new(Error::Ddc(ErrorCode::InvalidLength)),
+            });
+        }
+    }
+}
+
+/// Writes `data` to a table starting at `offset`, fragment by fragment
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn write_table<D>(ddc: &mut D, code: FeatureCode, offset: u16, data: &[u8]) -> Result<(), Error>
+where
+    D: DdcCommand<Error = Error>,
+{
+    if usize::from(offset) + data.len() > usize::from(u16::MAX) + 1 {
+        return Err(Error::Ddc(ErrorCode::InvalidLength));
+    }
+    for (offset, fragment) in table_fragments(offset, data) {
+        retry_fragment(ddc, code, offset, |ddc| {
+            ddc.execute(TableWrite::new(code, offset, fragment))
+        })?;
+    }
+    Ok(())
+}
+
+/// Table VCP feature access
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Reads the complete value of a table VCP feature, reassembling it from as many Table Read
+    /// fragments as needed until a short or empty one ends the table. Failed fragments are retried; if a fragment keeps failing, the error
+    /// reports its offset.
+    pub fn read_table(&mut self, code: FeatureCode) -> Result<Vec<u8>, Error> {
+        read_table(self, code)
+    }
+
+    /// Writes `data` to a table VCP feature starting at `offset`, split into Table Write fragments
+    /// of at most [TABLE_FRAGMENT_SIZE] bytes. Failed fragments are retried; if a fragment keeps
+    /// failing, the error reports its offset.
+    pub fn write_table(&mut self, code: FeatureCode, offset: u16, data: &[u8]) -> Result<(), Error> {
+        write_table(self, code, offset, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddc::{DdcCommandMarker, DdcCommandRaw, DdcCommandRawMarker, DdcHost, Delay};
+    use std::time::Duration;
+
+    const CODE: FeatureCode = 0x73;
+
+    /// Monitor holding a single table VCP feature
+    #[derive(Default)]
+    struct TableDdc {
+        table: Vec<u8>,
+        /// Number of upcoming Table Read replies that report the wrong offset
+        wrong_offsets: usize,
+        /// Number of upcoming transfers that fail
+        failures: usize,
+        /// Offsets of the Table Read commands received
+        reads: Vec<u16>,
+        /// Table Write commands received
+        writes: Vec<(u16, Vec<u8>)>,
+    }
+
+    impl DdcHost for TableDdc {
+        type Error = Error;
+    }
+
+    impl DdcCommandRaw for TableDdc {
+        fn execute_raw<'a>(
+            &mut self,
+            data: &[u8],
+            out: &'a mut [u8],
+            _response_delay: Duration,
+        ) -> Result<&'a mut [u8], Error> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(Error::Ddc(ErrorCode::InvalidChecksum));
+            }
+            let reply = match data {
+                [0xe2, CODE, high, low] => {
+                    let offset = u16::from_be_bytes([*high, *low]);
+                    self.reads.push(offset);
+                    let start = usize::from(offset).min(self.table.len());
+                    let end = (start + TABLE_FRAGMENT_SIZE).min(self.table.len());
+                    let reported = if self.wrong_offsets > 0 {
+                        self.wrong_offsets -= 1;
+                        offset + 1
+                    } else {
+                        offset
+                    };
+                    [&[0xe4][..], &reported.to_be_bytes(), &self.table[start..end]].concat()
+                }
+                [0xe7, CODE, high, low, fragment @ ..] => {
+                    self.writes.push((u16::from_be_bytes([*high, *low]), fragment.to_vec()));
+                    Vec::new()
+                }
+                _ => return Err(Error::Ddc(ErrorCode::InvalidOpcode)),
+            };
+            out[..reply.len()].copy_from_slice(&reply);
+            Ok(&mut out[..reply.len()])
+        }
+    }
+
+    impl DdcCommandRawMarker for TableDdc {
+        fn set_sleep_delay(&mut self, _delay: Delay) {}
+    }
+
+    impl DdcCommandMarker for TableDdc {}
+
+    fn table_ddc(len: u8) -> TableDdc {
+        TableDdc {
+            table: (0..len).collect(),
+            ..TableDdc::default()
+        }
+    }
+
+    #[test]
+    fn test_read_table_reassembles_fragments() {
+        // The short third fragment ends the table without another request
+        let mut ddc = table_ddc(70);
+        assert_eq!(read_table(&mut ddc, CODE).unwrap(), (0..70).collect::<Vec<u8>>());
+        assert_eq!(ddc.reads, vec![0, 32, 64]);
+
+        // A table filling whole fragments ends with an empty one
+        let mut ddc = table_ddc(64);
+        assert_eq!(read_table(&mut ddc, CODE).unwrap(), (0..64).collect::<Vec<u8>>());
+        assert_eq!(ddc.reads, vec![0, 32, 64]);
+    }
+
+    #[test]
+    fn test_read_table_retries_wrong_offset() {
+        let mut ddc = TableDdc {
+            wrong_offsets: 1,
+            failures: 1,
+            ..table_ddc(40)
+        };
+        assert_eq!(read_table(&mut ddc, CODE).unwrap(), (0..40).collect::<Vec<u8>>());
+        assert_eq!(ddc.reads, vec![0, 0, 32]);
+
+        let mut ddc = TableDdc {
+            wrong_offsets: TABLE_FRAGMENT_RETRIES,
+            ..table_ddc(40)
+        };
+        assert!(matches!(
+            read_table(&mut ddc, CODE),
+            Err(Error::TableFragment { code: CODE, offset: 0, source })
+                if matches!(*source, Error::Ddc(ErrorCode::InvalidOffset))
+        ));
+    }
+
+    #[test]
+    fn test_write_table_fragments() {
+        let data: Vec<u8> = (0..70).collect();
+        let mut ddc = TableDdc {
+            failures: 1,
+            ..TableDdc::default()
+        };
+        write_table(&mut ddc, CODE, 0x10, &data).unwrap();
+        assert_eq!(
+            ddc.writes,
+            vec![
+                (0x10, data[0..32].to_vec()),
+                (0x30, data[32..64].to_vec()),
+                (0x50, data[64..70].to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_table_reports_failed_fragment() {
+        let mut ddc = TableDdc {
+            failures: usize::MAX,
+            ..TableDdc::default()
+        };
+        assert!(matches!(
+            write_table(&mut ddc, CODE, 0x20, &[0; 40]),
+            Err(Error::TableFragment { code: CODE, offset: 0x20, source })
+                if matches!(*source, Error::Ddc(ErrorCode::InvalidChecksum))
+        ));
+        assert!(ddc.writes.is_empty());
+        assert!(matches!(
+            write_table(&mut ddc, CODE, 0xfff0, &[0; 40]),
+            Err(Error::Ddc(ErrorCode::InvalidLength))
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::{Ddc, DdcHost, FeatureCode};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Shortest interval between two writes of a transition: the delay the DDC/CI specification
+/// requires after a Set VCP Feature command
+pub const MIN_STEP_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Easing curve of a transition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Starts slowly and accelerates
+    EaseIn,
+    /// Starts quickly and decelerates
+    EaseOut,
+    /// Starts and ends slowly
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Progress of the value at time `t`, both as fractions between 0 and 1
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Timing of a transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransitionOptions {
+    /// Total duration of the transition
+    pub duration: Duration,
+    /// Easing curve
+    pub easing: Easing,
+    /// Interval between writes, raised to [MIN_STEP_INTERVAL] if shorter
+    pub step_interval: Duration,
+}
+
+impl Default for TransitionOptions {
+    fn default() -> Self {
+        TransitionOptions {
+            duration: Duration::from_millis(500),
+            easing: Easing::default(),
+            step_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// A single write of a transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransitionStep {
+    /// Time since the start of the transition at which the value is written
+    pub at: Duration,
+    /// Value to write
+    pub value: u16,
+}
+
+/// Plans the writes needed to move a continuous VCP feature from `from` to `to`.
+///
+/// Steps are spaced at least [TransitionOptions::step_interval] apart, follow the easing curve, and
+/// steps that would not change the value are left out. The last step always writes `to`, at the
+/// end of the duration. No steps are planned if `from` already equals `to`.
+pub fn plan_transition(from: u16, to: u16, options: &TransitionOptions) -> Vec<TransitionStep> {
+    if from == to {
+        return Vec::new();
+    }
+    let interval = options.step_interval.max(MIN_STEP_INTERVAL);
+    let count = options.duration.as_nanos().div_ceil(interval.as_nanos()).max(1) as u32;
+    let mut steps: Vec<TransitionStep> = Vec::new();
+    let mut previous = from;
+    for index in 1..=count {
+        let t = f64::from(index) / f64::from(count);
+        let value = if index == count {
+            to
+        } else {
+            let progress = options.easing.apply(t);
+            (f64::from(from) + (f64::from(to) - f64::from(from)) * progress).round() as u16
+        };
+        if value != previous {
+            steps.push(TransitionStep {
+                at: options.duration * index / count,
+                value,
+            });
+            previous = value;
+        }
+    }
+    steps
+}
+
+/// How a transition ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionOutcome {
+    /// The final target was reached
+    Completed(u16),
+    /// The transition was cancelled after writing the given value
+    Cancelled(u16),
+}
+
+impl TransitionOutcome {
+    /// Last value written, or the starting value if nothing was written
+    pub fn value(&self) -> u16 {
+        match self {
+            TransitionOutcome::Completed(value) | TransitionOutcome::Cancelled(value) => *value,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    cancelled: bool,
+    target: Option<(u16, Duration)>,
+}
+
+/// Handle to steer a running transition from another thread. Clones share the same transition.
+#[derive(Debug, Clone, Default)]
+pub struct TransitionControl {
+    state: Arc<Mutex<ControlState>>,
+}
+
+impl TransitionControl {
+    /// Create a new control for a transition that has not started yet
+    pub fn new() -> Self {
+        TransitionControl::default()
+    }
+
+    /// Stops the transition before its next write
+    pub fn cancel(&self) {
+        self.state.lock().unwrap().cancelled = true;
+    }
+
+    /// Returns `true` if the transition was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Redirects the transition to a new `target`, reached `duration` from now starting at the last
+    /// written value. If several targets arrive between two writes, only the latest one is used.
+    pub fn retarget(&self, target: u16, duration: Duration) {
+        self.state.lock().unwrap().target = Some((target, duration));
+    }
+
+    /// Takes the pending new target, if any
+    pub fn take_target(&self) -> Option<(u16, Duration)> {
+        self.state.lock().unwrap().target.take()
+    }
+}
+
+/// Runs a transition of the continuous VCP feature `code` towards `target`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn run_transition<D>(
+    ddc: &mut D,
+    code: FeatureCode,
+    target: u16,
+    options: &TransitionOptions,
+    control: &TransitionControl,
+) -> Result<TransitionOutcome, Error>
+where
+    D: Ddc<Error = Error> + DdcHost,
+{
+    ddc.sleep();
+    let current = ddc.get_vcp_feature(code)?;
+    let check = |value: u16| {
+        if value > current.maximum() {
+            Err(Error::ValueOutOfRange {
+                code,
+                value,
+                maximum: current.maximum(),
+            })
+        } else {
+            Ok(value)
+        }
+    };
+    let mut last = current.value();
+    let mut steps = plan_transition(last, check(target)?, options).into_iter().peekable();
+    let mut started = Instant::now();
+    loop {
+        if control.is_cancelled() {
+            return Ok(TransitionOutcome::Cancelled(last));
+        }
+        if let Some((target, duration)) = control.take_target() {
+            let options = TransitionOptions { duration, ..*options };
+            steps = plan_transition(last, check(target)?, &options).into_iter().peekable();
+            started = Instant::now();
+            continue;
+        }
+        let Some(&step) = steps.peek() else {
+            return Ok(TransitionOutcome::Completed(last));
+        };
+        // Wait in short slices, so that a cancellation or new target takes effect before the next write
+        if let Some(wait) = step.at.checked_sub(started.elapsed()).filter(|wait| !wait.is_zero()) {
+            thread::sleep(wait.min(MIN_STEP_INTERVAL));
+            continue;
+        }
+        steps.next();
+        ddc.sleep();
+        ddc.set_vcp_feature(code, step.value)?;
+        last = step.value;
+    }
+}
+
+/// Smooth transitions of continuous settings
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Fades the continuous VCP feature `code` from its current value to `target`, writing the
+    /// intermediate values planned by [plan_transition]. The call blocks until the transition
+    /// completes or `control` cancels it; `control` can also redirect it to a new target.
+    pub fn transition(
+        &mut self,
+        code: FeatureCode,
+        target: u16,
+        options: &TransitionOptions,
+        control: &TransitionControl,
+    ) -> Result<TransitionOutcome, Error> {
+        run_transition(self, code, target, options, control)
+    }
+}
@@ -0,0 +1,259 @@
+#![deny(missing_docs)]
+
+#[cfg(target_os = "macos")]
+use crate::catalog::vcp_feature_for_version;
+use crate::catalog::{
+    describe_feature_value, names_match, parse_feature_code, vcp_feature, vcp_feature_by_name, Access::ReadWrite,
+    VcpFeature,
+};
+use crate::error::Error;
+use crate::identity::MonitorIdentity;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::{FeatureCode, VcpValue};
+use std::ops::RangeInclusive;
+
+/// VCP codes reserved by the MCCS specification for manufacturer specific features
+pub const VENDOR_CODES: RangeInclusive<FeatureCode> = 0xe0..=0xff;
+
+/// Manufacturer specific VCP features of a vendor's monitors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VendorExtension {
+    /// Vendor name
+    pub name: &'static str,
+    /// EDID vendor number
+    pub vendor: u32,
+    /// EDID product numbers the features apply to, or empty for all models of the vendor
+    pub models: &'static [u32],
+    /// Features in the [VENDOR_CODES] range
+    pub features: &'static [VcpFeature],
+}
+
+impl VendorExtension {
+    /// Returns `true` if this extension applies to the monitor `identity`
+    pub fn matches(&self, identity: &MonitorIdentity) -> bool {
+        self.vendor == identity.vendor && (self.models.is_empty() || self.models.contains(&identity.model))
+    }
+
+    /// Looks up a feature of this extension by its code
+    pub fn feature(&self, code: FeatureCode) -> Option<&'static VcpFeature> {
+        self.features.iter().find(|feature| feature.code == code)
+    }
+}
+
+/// Vendor extensions, consulted before the standard catalog for codes in the [VENDOR_CODES] range
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VendorRegistry {
+    extensions: Vec<VendorExtension>,
+}
+
+impl VendorRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        VendorRegistry::default()
+    }
+
+    /// The vendor extensions known to this crate
+    pub fn builtin() -> Self {
+        VendorRegistry {
+            extensions: BUILTIN_EXTENSIONS.iter().map(|extension| **extension).collect(),
+        }
+    }
+
+    /// Adds an extension, taking precedence over previously registered ones
+    pub fn register(&mut self, extension: VendorExtension) {
+        self.extensions.insert(0, extension);
+    }
+
+    /// Registered extensions, in order of precedence
+    pub fn extensions(&self) -> &[VendorExtension] {
+        &self.extensions
+    }
+
+    /// A registry with only the extensions applying to the monitor `identity`
+    pub fn for_monitor(&self, identity: &MonitorIdentity) -> VendorRegistry {
+        VendorRegistry {
+            extensions: self
+                .extensions
+                .iter()
+                .filter(|extension| extension.matches(identity))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Looks up the VCP feature `code` of the monitor `identity`: codes in the [VENDOR_CODES] range
+    /// are looked up in the matching extensions, all others in the standard catalog
+    pub fn feature(&self, identity: &MonitorIdentity, code: FeatureCode) -> Option<&'static VcpFeature> {
+        if !VENDOR_CODES.contains(&code) {
+            return vcp_feature(code);
+        }
+        self.matching(identity).find_map(|extension| extension.feature(code))
+    }
+
+    /// Looks up a VCP feature of the monitor `identity` by its name, see [crate::names_match]. Vendor features
+    /// take precedence over standard ones of the same name.
+    pub fn feature_by_name(&self, identity: &MonitorIdentity, name: &str) -> Option<&'static VcpFeature> {
+        self.vendor_feature_by_name(identity, name)
+            .or_else(|| vcp_feature_by_name(name))
+    }
+
+    /// Parses a VCP feature code of the monitor `identity` given as the name of a vendor feature, or
+    /// as anything [crate::parse_feature_code] accepts
+    pub fn parse_feature_code(&self, identity: &MonitorIdentity, s: &str) -> Result<FeatureCode, Error> {
+        match self.vendor_feature_by_name(identity, s) {
+            Some(feature) => Ok(feature.code),
+            None => parse_feature_code(s),
+        }
+    }
+
+    /// Formats a VCP value of the monitor `identity` as `Name = value`, see [crate::describe_vcp_value]
+    pub fn describe(&self, identity: &MonitorIdentity, code: FeatureCode, value: &VcpValue) -> String {
+        describe_feature_value(code, self.feature(identity, code), value)
+    }
+
+    fn vendor_feature_by_name(&self, identity: &MonitorIdentity, name: &str) -> Option<&'static VcpFeature> {
+        self.matching(identity)
+            .flat_map(|extension| extension.features)
+            .find(|feature| names_match(feature.name, name))
+    }
+
+    fn matching<'a>(&'a self, identity: &'a MonitorIdentity) -> impl Iterator<Item = &'a VendorExtension> {
+        self.extensions
+            .iter()
+            .filter(move |extension| extension.matches(identity))
+    }
+}
+
+/// Vendor aware VCP feature descriptions
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Looks up the VCP feature `code` of this [Monitor], including the extensions of its
+    /// [vendor registry](Monitor::set_vendor_registry). Standard features are described as in the
+    /// MCCS version of this monitor, if it was already read.
+    pub fn vcp_feature(&self, code: FeatureCode) -> Option<&'static VcpFeature> {
+        if VENDOR_CODES.contains(&code) {
+            self.vendor_registry().feature(self.cached_identity(), code)
+        } else {
+            vcp_feature_for_version(code, self.cached_mccs_version())
+        }
+    }
+
+    /// Looks up a VCP feature of this [Monitor] by its name, vendor features taking precedence over
+    /// standard ones of the same name
+    pub fn vcp_feature_by_name(&self, name: &str) -> Option<&'static VcpFeature> {
+        self.vendor_registry().feature_by_name(self.cached_identity(), name)
+    }
+
+    /// Parses a VCP feature code of this [Monitor] given as hex or as the name of a standard or
+    /// vendor feature, failing for names this monitor does not know
+    pub fn parse_feature_code(&self, s: &str) -> Result<FeatureCode, Error> {
+        self.vendor_registry().parse_feature_code(self.cached_identity(), s)
+    }
+
+    /// Formats a VCP value of this [Monitor] as `Name = value`, see [Monitor::vcp_feature]
+    pub fn describe_vcp_value(&self, code: FeatureCode, value: &VcpValue) -> String {
+        describe_feature_value(code, self.vcp_feature(code), value)
+    }
+}
+
+// Vendor features below are collected from vendor documentation and community reverse engineering.
+// Not every model of a vendor supports every feature.
+
+static BUILTIN_EXTENSIONS: &[&VendorExtension] = &[&DELL, &LG, &BENQ];
+
+/// Dell monitors
+pub static DELL: VendorExtension = VendorExtension {
+    name: "Dell",
+    vendor: 0x10ac,
+    models: &[],
+    features: &[
+        VcpFeature::non_continuous(0xe0, "Dell Preset Mode", ReadWrite, &[]),
+        VcpFeature::non_continuous(
+            0xe7,
+            "Dell USB Upstream (KVM)",
+            ReadWrite,
+            &[(0x00, "USB-C"), (0x01, "USB-B 1"), (0x02, "USB-B 2")],
+        ),
+        VcpFeature::non_continuous(
+            0xe8,
+            "Dell PxP Sub Input",
+            ReadWrite,
+            &[
+                (0x0f, "DisplayPort-1"),
+                (0x10, "DisplayPort-2"),
+                (0x11, "HDMI-1"),
+                (0x12, "HDMI-2"),
+                (0x1b, "USB-C"),
+            ],
+        ),
+        VcpFeature::non_continuous(
+            0xe9,
+            "Dell PxP Mode",
+            ReadWrite,
+            &[
+                (0x00, "Off"),
+                (0x01, "PiP small"),
+                (0x02, "PiP large"),
+                (0x24, "PbP 2 windows"),
+                (0x2e, "PbP 4 windows"),
+            ],
+        ),
+        VcpFeature::non_continuous(
+            0xf0,
+            "Dell Auto Select Input",
+            ReadWrite,
+            &[(0x00, "Off"), (0x01, "On")],
+        ),
+    ],
+};
+
+/// LG monitors
+pub static LG: VendorExtension = VendorExtension {
+    name: "LG",
+    vendor: 0x1e6d,
+    models: &[],
+    features: &[
+        VcpFeature::non_continuous(
+            0xf4,
+            "LG Input Source",
+            ReadWrite,
+            &[
+                (0x90, "HDMI-1"),
+                (0x91, "HDMI-2"),
+                (0xd0, "DisplayPort-1"),
+                (0xd1, "USB-C"),
+            ],
+        ),
+        VcpFeature::non_continuous(
+            0xf7,
+            "LG PBP Mode",
+            ReadWrite,
+            &[(0x00, "Off"), (0x01, "PBP"), (0x02, "PIP")],
+        ),
+    ],
+};
+
+/// BenQ monitors
+pub static BENQ: VendorExtension = VendorExtension {
+    name: "BenQ",
+    vendor: 0x09d1,
+    models: &[],
+    features: &[
+        VcpFeature::non_continuous(
+            0xe3,
+            "BenQ Color Mode",
+            ReadWrite,
+            &[
+                (0x00, "Standard"),
+                (0x01, "sRGB"),
+                (0x02, "Movie"),
+                (0x03, "Game"),
+                (0x04, "Eco"),
+                (0x05, "M-book"),
+                (0x06, "Low Blue Light"),
+            ],
+        ),
+        VcpFeature::non_continuous(0xe6, "BenQ Low Blue Light", ReadWrite, &[]),
+    ],
+};
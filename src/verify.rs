@@ -0,0 +1,108 @@
+#![deny(missing_docs)]
+
+use crate::error::Error;
+#[cfg(target_os = "macos")]
+use crate::monitor::Monitor;
+use ddc::{Ddc, DdcHost, FeatureCode};
+use std::thread;
+use std::time::Duration;
+
+/// How a write is confirmed by reading the value back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VerifyOptions {
+    /// Time the monitor is given to apply a value before it is read back, on top of the DDC/CI command delay
+    pub settle_time: Duration,
+    /// Largest accepted difference between the requested value and the value read back, for
+    /// monitors that round values to coarser steps
+    pub tolerance: u16,
+    /// Number of times the value is written before giving up
+    pub attempts: usize,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            settle_time: Duration::from_millis(100),
+            tolerance: 0,
+            attempts: 3,
+        }
+    }
+}
+
+impl VerifyOptions {
+    /// Returns `true` if the value read back is close enough to the requested one
+    pub fn accepts(&self, requested: u16, actual: u16) -> bool {
+        requested.abs_diff(actual) <= self.tolerance
+    }
+}
+
+/// Outcome of a verified write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VerifiedWrite {
+    /// VCP feature code
+    pub code: FeatureCode,
+    /// Value that was written
+    pub requested: u16,
+    /// Value the monitor reported afterwards
+    pub actual: u16,
+    /// Number of writes needed
+    pub attempts: usize,
+}
+
+impl VerifiedWrite {
+    /// Returns `true` if the monitor applied exactly the requested value
+    pub fn is_exact(&self) -> bool {
+        self.requested == self.actual
+    }
+}
+
+/// Writes a VCP feature and reads it back until the monitor reports an accepted value, see
+/// [Monitor::set_and_verify]
+pub fn set_and_verify<D>(
+    ddc: &mut D,
+    code: FeatureCode,
+    value: u16,
+    options: &VerifyOptions,
+) -> Result<VerifiedWrite, Error>
+where
+    D: Ddc<Error = Error> + DdcHost,
+{
+    let mut actual = None;
+    for attempt in 1..=options.attempts.max(1) {
+        ddc.sleep();
+        ddc.set_vcp_feature(code, value)?;
+        ddc.sleep();
+        thread::sleep(options.settle_time);
+        let current = ddc.get_vcp_feature(code)?.value();
+        if options.accepts(value, current) {
+            return Ok(VerifiedWrite {
+                code,
+                requested: value,
+                actual: current,
+                attempts: attempt,
+            });
+        }
+        actual = Some(current);
+    }
+    Err(Error::VerificationFailed {
+        code,
+        requested: value,
+        actual: actual.unwrap_or_default(),
+    })
+}
+
+/// Verified writes
+#[cfg(target_os = "macos")]
+impl Monitor {
+    /// Writes a VCP feature, waits for the monitor to settle and reads the value back, rewriting it
+    /// up to [VerifyOptions::attempts] times if the monitor ignored the write. Returns the value the
+    /// monitor ended up at, or [Error::VerificationFailed] if it never accepted the value.
+    pub fn set_and_verify(
+        &mut self,
+        code: FeatureCode,
+        value: u16,
+        options: &VerifyOptions,
+    ) -> Result<VerifiedWrite, Error> {
+        set_and_verify(self, code, value, options)
+    }
+}
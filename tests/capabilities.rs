@@ -0,0 +1,105 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::MockDdc;
+use ddc_macos::{
+    read_capabilities_string, Capabilities, DisplayType, Error, MccsVersion, Protocol, CAPABILITIES_FRAGMENT_RETRIES,
+};
+
+fn fixture(name: &str) -> Capabilities {
+    let data = std::fs::read(format!("tests/capabilities/{}.txt", name)).unwrap();
+    Capabilities::parse(&data).unwrap()
+}
+
+#[test]
+fn test_parse_well_formed() {
+    let caps = fixture("dell_u2415");
+    assert_eq!(caps.protocol, Some(Protocol::Monitor));
+    assert_eq!(caps.display_type, Some(DisplayType::Lcd));
+    assert_eq!(caps.model.as_deref(), Some("U2415"));
+    assert_eq!(caps.mccs_version, Some(MccsVersion::new(2, 1)));
+    assert_eq!(caps.commands, vec![0x01, 0x02, 0x03, 0x07, 0x0C, 0xE3, 0xF3]);
+    assert_eq!(caps.vcp_features.len(), 30);
+    assert_eq!(caps.vcp_values(0x60), Some(&[0x0F, 0x11, 0x12][..]));
+    assert_eq!(caps.vcp_values(0x10), None);
+    assert!(caps.supports_vcp(0x10));
+    assert!(!caps.supports_vcp(0x62));
+    assert_eq!(caps.extensions.get("mswhql").map(String::as_str), Some("1"));
+    assert_eq!(caps.extensions.get("asset_eep").map(String::as_str), Some("40"));
+}
+
+#[test]
+fn test_parse_model_with_spaces() {
+    let caps = fixture("lg_ultrafine");
+    assert_eq!(caps.model.as_deref(), Some("LG UltraFine"));
+    assert_eq!(caps.vcp_values(0x15).map(<[u8]>::len), Some(9));
+    assert!(caps.supports_vcp(0xFF));
+}
+
+#[test]
+fn test_parse_missing_closing_parenthesis() {
+    let caps = fixture("samsung_s27e590");
+    assert_eq!(caps.mccs_version, Some(MccsVersion::new(2, 0)));
+    assert_eq!(caps.extensions.get("mswhql").map(String::as_str), Some("1"));
+    assert_eq!(caps.vcp_values(0xD6), Some(&[0x01, 0x04, 0x05][..]));
+    assert!(caps.supports_vcp(0xE2));
+}
+
+#[test]
+fn test_parse_hex_without_spaces() {
+    let caps = fixture("hp_z27");
+    assert_eq!(caps.display_type, Some(DisplayType::Lcd));
+    assert_eq!(caps.commands, vec![0x01, 0x02, 0x03, 0x07, 0x0C, 0xE3, 0xF3]);
+    assert_eq!(caps.vcp_features.len(), 29);
+    assert_eq!(caps.vcp_values(0xD6), Some(&[0x01, 0x04][..]));
+    assert_eq!(caps.mccs_version, Some(MccsVersion::new(2, 2)));
+}
+
+#[test]
+fn test_parse_spaced_values_and_trailing_nuls() {
+    let caps = fixture("benq_pd2700u");
+    assert_eq!(caps.vcp_values(0x14), Some(&[0x04, 0x05, 0x06, 0x08, 0x0B][..]));
+    assert_eq!(caps.vcp_values(0x8D), Some(&[0x01, 0x02][..]));
+    assert_eq!(caps.mccs_version, Some(MccsVersion::new(2, 2)));
+    assert_eq!(
+        caps.extensions.get("vcpname").map(String::as_str),
+        Some("10(Brightness) 12(Contrast)")
+    );
+}
+
+#[test]
+fn test_parse_garbage() {
+    assert!(Capabilities::parse(b"").is_err());
+    assert!(Capabilities::parse(b"\0\0\0").is_err());
+    assert!(Capabilities::parse(b"not a capabilities string").is_err());
+}
+
+#[test]
+fn test_read_in_fragments() {
+    let string = std::fs::read("tests/capabilities/dell_u2415.txt").unwrap();
+    let mut ddc = MockDdc {
+        capabilities: [&string[..], b"\0"].concat(),
+        failures: CAPABILITIES_FRAGMENT_RETRIES - 1,
+        ..MockDdc::default()
+    };
+    assert_eq!(read_capabilities_string(&mut ddc).unwrap(), string);
+
+    let mut ddc = MockDdc {
+        capabilities: string,
+        failures: CAPABILITIES_FRAGMENT_RETRIES,
+        ..MockDdc::default()
+    };
+    assert!(read_capabilities_string(&mut ddc).is_err());
+}
+
+#[test]
+fn test_read_endless() {
+    let mut ddc = MockDdc {
+        endless_capabilities: true,
+        ..MockDdc::default()
+    };
+    let error = read_capabilities_string(&mut ddc).unwrap_err();
+    assert!(matches!(error, Error::Ddc(_)));
+    assert!(error.to_string().contains("exceeds"));
+}
@@ -0,0 +1,91 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::vcp_value;
+use ddc_macos::{
+    describe_vcp_value, mccs_value, names_match, vcp_feature, vcp_feature_by_name, vcp_feature_for_version, Access,
+    MccsVersion, VcpFeatureType, VCP_FEATURES,
+};
+
+#[test]
+fn test_catalog_is_sorted() {
+    assert!(VCP_FEATURES.windows(2).all(|pair| pair[0].code < pair[1].code));
+}
+
+#[test]
+fn test_lookup() {
+    let brightness = vcp_feature(0x10).unwrap();
+    assert_eq!(brightness.name, "Brightness");
+    assert_eq!(brightness.ty, VcpFeatureType::Continuous);
+    assert_eq!(brightness.access, Access::ReadWrite);
+
+    let factory_reset = vcp_feature(0x04).unwrap();
+    assert!(!factory_reset.is_readable());
+    assert!(factory_reset.is_writable());
+
+    assert_eq!(vcp_feature(0x73).unwrap().ty, VcpFeatureType::Table);
+    assert!(vcp_feature(0xe0).is_none());
+
+    assert_eq!(vcp_feature_by_name("input-source").unwrap().code, 0x60);
+    assert_eq!(vcp_feature_by_name("INPUT_SOURCE").unwrap().code, 0x60);
+    assert!(names_match("Display Firmware Level", "display-firmware-level"));
+    assert!(!names_match("Brightness", "Bright"));
+}
+
+#[test]
+fn test_value_names() {
+    assert_eq!(vcp_feature(0x60).unwrap().value_name(0x0f), Some("DisplayPort-1"));
+    assert_eq!(vcp_feature(0xd6).unwrap().value_name(0x04), Some("Off"));
+    assert_eq!(vcp_feature(0x14).unwrap().value_name(0x05), Some("6500 K"));
+    assert_eq!(vcp_feature(0x60).unwrap().value_name(0x42), None);
+    assert_eq!(vcp_feature(0x60).unwrap().value_by_name("hdmi1"), Some(0x11));
+}
+
+#[test]
+fn test_describe_value() {
+    assert_eq!(
+        describe_vcp_value(0x60, &vcp_value(0x0f, 0x12)),
+        "Input Source = DisplayPort-1"
+    );
+    assert_eq!(describe_vcp_value(0x10, &vcp_value(30, 100)), "Brightness = 30 / 100");
+    assert_eq!(describe_vcp_value(0x60, &vcp_value(0x42, 0x12)), "Input Source = 0x42");
+    assert_eq!(describe_vcp_value(0xe0, &vcp_value(1, 2)), "VCP 0xe0 = 0x0001");
+}
+
+#[test]
+fn test_version_specific_features() {
+    // DisplayPort and HDMI inputs are defined since MCCS 2.0
+    for version in [MccsVersion::V2_0, MccsVersion::V2_1, MccsVersion::V2_2] {
+        let input = vcp_feature_for_version(0x60, Some(version)).unwrap();
+        assert_eq!(input.value_name(0x0f), Some("DisplayPort-1"));
+        assert_eq!(input.value_name(0x12), Some("HDMI-2"));
+    }
+    assert_eq!(vcp_feature_for_version(0x60, None), vcp_feature(0x60));
+
+    assert_eq!(
+        vcp_feature_for_version(0xdc, Some(MccsVersion::V2_0)).unwrap().name,
+        "Display Mode"
+    );
+    assert_eq!(
+        vcp_feature_for_version(0xdc, Some(MccsVersion::V3_0)).unwrap().name,
+        "Display Application"
+    );
+    assert_eq!(
+        vcp_feature_for_version(0x10, Some(MccsVersion::V2_0)).unwrap().name,
+        "Brightness"
+    );
+}
+
+#[test]
+fn test_version_specific_values() {
+    assert_eq!(mccs_value(0x14, 0x0505, Some(MccsVersion::V3_0)), 0x05);
+    assert_eq!(mccs_value(0x14, 0x0505, Some(MccsVersion::V2_2)), 0x0505);
+    assert_eq!(mccs_value(0x14, 0x0505, None), 0x0505);
+    assert_eq!(mccs_value(0x60, 0x010f, Some(MccsVersion::V3_0)), 0x0f);
+    assert_eq!(mccs_value(0xd6, 0x0104, Some(MccsVersion::V3_0)), 0x04);
+    assert_eq!(mccs_value(0x60, 0x010f, Some(MccsVersion::V2_1)), 0x010f);
+    assert_eq!(mccs_value(0x10, 0x0505, Some(MccsVersion::V3_0)), 0x0505);
+    assert!(MccsVersion::V2_0 < MccsVersion::V2_1 && MccsVersion::V2_2 < MccsVersion::V3_0);
+    assert_eq!("2.2a".parse::<MccsVersion>().unwrap(), MccsVersion::V2_2);
+}
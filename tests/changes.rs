@@ -0,0 +1,116 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::MockDdc;
+use ddc_macos::{ChangeDetection, ChangePoller, ControlChange, Error, NewControlValue};
+
+#[test]
+fn test_new_control_value() {
+    assert_eq!(NewControlValue::from_value(0x01), Some(NewControlValue::None));
+    assert_eq!(NewControlValue::from_value(0x02), Some(NewControlValue::Present));
+    assert_eq!(NewControlValue::from_value(0xff), Some(NewControlValue::NoUserControls));
+    assert_eq!(NewControlValue::from_value(0x00), None);
+}
+
+#[test]
+fn test_record() {
+    let mut poller = ChangePoller::new([0x12, 0x10, 0x10]);
+    assert_eq!(poller.codes(), &[0x10, 0x12]);
+    assert_eq!(poller.detection(), None);
+
+    assert_eq!(poller.record(0x10, 50), None);
+    assert_eq!(poller.value(0x10), Some(50));
+    assert_eq!(poller.record(0x10, 50), None);
+    assert_eq!(
+        poller.record(0x10, 70),
+        Some(ControlChange {
+            code: 0x10,
+            previous: Some(50),
+            value: 70
+        })
+    );
+    assert_eq!(poller.value(0x10), Some(70));
+    assert_eq!(poller.value(0x12), None);
+
+    let poller = ChangePoller::new([0x10]).with_detection(ChangeDetection::ReadAndDiff);
+    assert_eq!(poller.detection(), Some(ChangeDetection::ReadAndDiff));
+}
+
+#[test]
+fn test_poll_read_and_diff() {
+    let mut ddc = MockDdc::default().with_value(0x10, 50, 100).with_value(0x12, 75, 100);
+    let mut poller = ChangePoller::new([0x10, 0x12, 0x62]);
+    assert!(poller.poll(&mut ddc, false).unwrap().is_empty());
+    assert_eq!(poller.detection(), Some(ChangeDetection::ReadAndDiff));
+    // The unsupported volume is dropped rather than failing every poll
+    assert_eq!(poller.codes(), &[0x10, 0x12]);
+    assert_eq!(poller.unsupported(), &[0x62]);
+    assert_eq!(poller.value(0x12), Some(75));
+
+    assert!(poller.poll(&mut ddc, false).unwrap().is_empty());
+    ddc.change(0x10, 70);
+    assert_eq!(
+        poller.poll(&mut ddc, false).unwrap(),
+        vec![ControlChange {
+            code: 0x10,
+            previous: Some(50),
+            value: 70
+        }]
+    );
+    assert!(ddc.writes.is_empty());
+}
+
+#[test]
+fn test_poll_new_control_value() {
+    let mut ddc = MockDdc::default()
+        .with_value(0x02, 0x01, 0xff)
+        .with_value(0x52, 0x00, 0xff)
+        .with_value(0x10, 50, 100)
+        .with_value(0x12, 75, 100);
+    let mut poller = ChangePoller::new([0x10, 0x12]);
+    assert!(poller.poll(&mut ddc, true).unwrap().is_empty());
+    assert_eq!(poller.detection(), Some(ChangeDetection::NewControlValue));
+    assert_eq!(ddc.writes, vec![(0x02, 0x01)]);
+
+    // Nothing is read but the flag while nothing changed
+    assert!(poller.poll(&mut ddc, true).unwrap().is_empty());
+    ddc.change(0x12, 30);
+    ddc.change(0x12, 40);
+    ddc.change(0x16, 10);
+    assert_eq!(
+        poller.poll(&mut ddc, true).unwrap(),
+        vec![ControlChange {
+            code: 0x12,
+            previous: Some(75),
+            value: 40
+        }]
+    );
+    assert_eq!(ddc.value(0x02), Some(0x01));
+    assert!(ddc.active_controls.is_empty());
+    assert!(poller.poll(&mut ddc, true).unwrap().is_empty());
+}
+
+#[test]
+fn test_poll_without_advertised_new_control_value() {
+    let mut ddc = MockDdc::default()
+        .with_value(0x02, 0x01, 0xff)
+        .with_value(0x10, 50, 100);
+    let mut poller = ChangePoller::new([0x10]);
+    poller.poll(&mut ddc, false).unwrap();
+    assert_eq!(poller.detection(), Some(ChangeDetection::ReadAndDiff));
+    assert!(ddc.writes.is_empty());
+}
+
+#[test]
+fn test_poll_errors() {
+    let mut ddc = MockDdc::default().with_value(0x10, 50, 100);
+    let mut poller = ChangePoller::new([0x10]).with_detection(ChangeDetection::ReadAndDiff);
+    poller.poll(&mut ddc, false).unwrap();
+    // Failed transfers are reported, and do not drop the watched control
+    ddc.failures = 1;
+    assert!(matches!(poller.poll(&mut ddc, false), Err(Error::Ddc(_))));
+    assert_eq!(poller.codes(), &[0x10]);
+    ddc.change(0x10, 60);
+    assert_eq!(poller.poll(&mut ddc, false).unwrap().len(), 1);
+}
@@ -0,0 +1,556 @@
+#![cfg(feature = "cli")]
+extern crate ddc;
+extern crate ddc_macos;
+
+use ddc::{FeatureCode, VcpValue};
+use ddc_macos::{
+    select_display, Cli, CliCommand, DisplayBackend, DisplayDescription, Error, FeatureValue, JsonError,
+    MonitorIdentity, MonitorSelector, MonitorSnapshot, ValueArgument, JSON_SCHEMA_VERSION,
+};
+use std::collections::BTreeMap;
+
+struct MockDisplay {
+    description: DisplayDescription,
+    values: BTreeMap<FeatureCode, (u16, u16)>,
+    capabilities: &'static str,
+    edid: Option<Vec<u8>>,
+}
+
+/// Backend simulating monitors, recording every write
+struct MockBackend {
+    displays: Vec<MockDisplay>,
+    writes: Vec<(usize, FeatureCode, u16)>,
+}
+
+impl DisplayBackend for MockBackend {
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error> {
+        Ok(self
+            .displays
+            .iter()
+            .map(|display| display.description.clone())
+            .collect())
+    }
+
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error> {
+        let (value, maximum) = *self.displays[display]
+            .values
+            .get(&code)
+            .ok_or(Error::UnsupportedFeature { code })?;
+        Ok(VcpValue {
+            ty: 0,
+            mh: (maximum >> 8) as u8,
+            ml: maximum as u8,
+            sh: (value >> 8) as u8,
+            sl: value as u8,
+        })
+    }
+
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error> {
+        self.writes.push((display, code, value));
+        if let Some(current) = self.displays[display].values.get_mut(&code) {
+            current.0 = value;
+        }
+        Ok(())
+    }
+
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error> {
+        Ok(self.displays[display].capabilities.as_bytes().to_vec())
+    }
+
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.displays[display].edid.clone())
+    }
+}
+
+fn description(model: u32, serial: u32, name: &str, serial_number: Option<&str>) -> DisplayDescription {
+    DisplayDescription {
+        identity: MonitorIdentity {
+            vendor: 0x10ac,
+            model,
+            serial,
+            name: Some(name.into()),
+        },
+        description: name.into(),
+        serial_number: serial_number.map(Into::into),
+        connection: None,
+    }
+}
+
+fn backend() -> MockBackend {
+    MockBackend {
+        displays: vec![
+            MockDisplay {
+                description: description(0xa0c4, 12345, "DELL U2415", Some("CN0XYZ")),
+                values: BTreeMap::from([
+                    (0x10, (50, 100)),
+                    (0x12, (75, 100)),
+                    (0x60, (0x0f, 0x12)),
+                    (0xc9, (0x0102, 0)),
+                ]),
+                capabilities:
+                    "(prot(monitor)type(lcd)model(U2415)cmds(01 02 03 0C)vcp(10 12 60(0F 11) C9 E2)mccs_ver(2.1))",
+                edid: Some((0..20).collect()),
+            },
+            MockDisplay {
+                description: description(0xd0ba, 0, "DELL P2720D", None),
+                values: BTreeMap::from([(0x10, (20, 100))]),
+                capabilities: "(vcp(10))",
+                edid: None,
+            },
+        ],
+        writes: Vec::new(),
+    }
+}
+
+fn run(backend: &mut MockBackend, args: &[&str]) -> Result<String, Error> {
+    let mut out = Vec::new();
+    Cli::parse(args)?.run(backend, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        Cli::parse(["--display", "2", "get", "brightness"]).unwrap(),
+        Cli {
+            display: Some(MonitorSelector::Index(2)),
+            command: CliCommand::Get { code: 0x10 },
+            json: false,
+        }
+    );
+    assert_eq!(
+        Cli::parse(["set", "0x60", "hdmi-1", "-d=x"])
+            .map(|cli| cli.command)
+            .ok(),
+        None,
+        "unknown options are rejected"
+    );
+    assert_eq!(
+        Cli::parse(["set", "input-source", "hdmi-1", "--display=serial=CN0XYZ"]).unwrap(),
+        Cli {
+            display: Some(MonitorSelector::Serial("CN0XYZ".into())),
+            command: CliCommand::Set {
+                code: 0x60,
+                value: ValueArgument::Absolute(0x11),
+            },
+            json: false,
+        }
+    );
+    assert_eq!(
+        Cli::parse(["set", "12", "-10"]).unwrap().command,
+        CliCommand::Set {
+            code: 0x12,
+            value: ValueArgument::Relative(-10),
+        }
+    );
+    assert_eq!(
+        Cli::parse(["restore", "--dry-run", "-"]).unwrap().command,
+        CliCommand::Restore {
+            path: "-".into(),
+            dry_run: true,
+        }
+    );
+    assert!(Cli::parse(["list", "--json"]).unwrap().json);
+    assert_eq!(Cli::parse(Vec::<String>::new()).unwrap().command, CliCommand::Help);
+    assert!(matches!(Cli::parse(["get"]), Err(Error::InvalidArgument(_))));
+    assert!(matches!(
+        Cli::parse(["get", "no-such-feature"]),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Cli::parse(["set", "10", "lots"]),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Cli::parse(["--dry-run", "dump"]),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert_eq!(
+        Cli::parse(["profile", "--only", "brightness,12", "profiles.toml", "night"])
+            .unwrap()
+            .command,
+        CliCommand::Profile {
+            path: "profiles.toml".into(),
+            name: "night".into(),
+            features: Some(vec![0x10, 0x12]),
+            dry_run: false,
+        }
+    );
+    assert!(matches!(
+        Cli::parse(["--only", "10", "get", "10"]),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_selectors() {
+    assert_eq!(
+        "10ac:a0c4:12345".parse::<MonitorSelector>().unwrap(),
+        MonitorSelector::Id(MonitorIdentity {
+            vendor: 0x10ac,
+            model: 0xa0c4,
+            serial: 12345,
+            name: None,
+        })
+    );
+    assert_eq!(
+        "u2415".parse::<MonitorSelector>().unwrap(),
+        MonitorSelector::Name("u2415".into())
+    );
+
+    let displays = backend().displays().unwrap();
+    let select = |selector: &str| select_display(&displays, Some(&selector.parse().unwrap()));
+    assert_eq!(select_display(&displays, None).unwrap(), 0);
+    assert_eq!(select("2").unwrap(), 1);
+    assert_eq!(select("10ac:d0ba:0").unwrap(), 1);
+    assert_eq!(select("serial=CN0XYZ").unwrap(), 0);
+    assert_eq!(select("p2720").unwrap(), 1);
+    assert!(matches!(select("3"), Err(Error::MonitorNotFound(_))));
+    assert!(matches!(select("dell"), Err(Error::AmbiguousMonitor(_))));
+    assert!(matches!(select_display(&[], None), Err(Error::MonitorNotFound(_))));
+}
+
+#[test]
+fn test_relative_values() {
+    let value = VcpValue {
+        ty: 0,
+        mh: 0,
+        ml: 100,
+        sh: 0,
+        sl: 95,
+    };
+    assert_eq!(ValueArgument::Relative(10).resolve(&value), 100);
+    assert_eq!(ValueArgument::Relative(-10).resolve(&value), 85);
+    assert_eq!(ValueArgument::Relative(-200).resolve(&value), 0);
+    assert_eq!(ValueArgument::Absolute(7).resolve(&value), 7);
+}
+
+#[test]
+fn test_list_get_set() {
+    let mut backend = backend();
+    assert_eq!(
+        run(&mut backend, &["list"]).unwrap(),
+        "1: DELL U2415 (id 10ac:a0c4:12345, serial CN0XYZ)\n2: DELL P2720D (id 10ac:d0ba:0)\n"
+    );
+    assert_eq!(
+        run(&mut backend, &["get", "input-source"]).unwrap(),
+        "Input Source = DisplayPort-1\n"
+    );
+    assert_eq!(
+        run(&mut backend, &["-d", "2", "get", "10"]).unwrap(),
+        "Brightness = 20 / 100\n"
+    );
+
+    run(&mut backend, &["set", "brightness", "+60"]).unwrap();
+    run(&mut backend, &["set", "input-source", "hdmi-1"]).unwrap();
+    assert_eq!(backend.writes, vec![(0, 0x10, 100), (0, 0x60, 0x11)]);
+    assert!(matches!(
+        run(&mut backend, &["-d", "2", "get", "contrast"]),
+        Err(Error::UnsupportedFeature { code: 0x12 })
+    ));
+}
+
+#[test]
+fn test_info_caps_edid() {
+    let mut backend = backend();
+    let info = run(&mut backend, &["info"]).unwrap();
+    assert!(info.contains("Firmware level: 1.02\n"), "{}", info);
+    assert!(info.contains("Display usage time: not supported\n"), "{}", info);
+    assert!(info.contains("MCCS version: 2.1\n"), "{}", info);
+
+    assert_eq!(
+        run(&mut backend, &["caps"]).unwrap(),
+        "Model: U2415\n\
+         MCCS version: 2.1\n\
+         Commands: 01 02 03 0c\n\
+         VCP features:\n  \
+         0x10 Brightness\n  \
+         0x12 Contrast\n  \
+         0x60 Input Source: DisplayPort-1, HDMI-1\n  \
+         0xc9 Display Firmware Level\n  \
+         0xe2\n"
+    );
+
+    assert_eq!(
+        run(&mut backend, &["edid"]).unwrap(),
+        "00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n10 11 12 13\n"
+    );
+    assert_eq!(run(&mut backend, &["-d", "2", "edid"]).unwrap(), "No EDID available\n");
+}
+
+#[test]
+fn test_dump_and_restore() {
+    let mut backend = backend();
+    let dump = run(&mut backend, &["dump"]).unwrap();
+    assert_eq!(
+        dump,
+        "monitor 10ac:a0c4:12345 DELL U2415\n0x10 50 100\n0x12 75 100\n0x60 15 18\n0xc9 258 0\n"
+    );
+
+    let path = std::env::temp_dir().join(format!("ddc-macos-cli-{}.txt", std::process::id()));
+    std::fs::write(&path, dump).unwrap();
+    let path = path.to_str().unwrap();
+    run(&mut backend, &["set", "10", "30"]).unwrap();
+    run(&mut backend, &["set", "12", "30"]).unwrap();
+    backend.writes.clear();
+
+    assert_eq!(
+        run(&mut backend, &["restore", "--dry-run", path]).unwrap(),
+        "Brightness: 30 -> 50\nContrast: 30 -> 75\n"
+    );
+    assert!(backend.writes.is_empty());
+    run(&mut backend, &["restore", path]).unwrap();
+    assert_eq!(backend.writes, vec![(0, 0x10, 50), (0, 0x12, 75)]);
+    assert!(matches!(
+        run(&mut backend, &["-d", "2", "restore", path]),
+        Err(Error::SnapshotMismatch { .. })
+    ));
+    std::fs::remove_file(path).unwrap();
+    // Features that fail to read are listed rather than silently left out
+    backend.displays[0].values.remove(&0x12);
+    let dump = run(&mut backend, &["dump"]).unwrap();
+    assert_eq!(
+        dump,
+        "monitor 10ac:a0c4:12345 DELL U2415\n0x10 50 100\n0x60 15 18\n0xc9 258 0\n# 0x12 could not be read\n"
+    );
+    assert_eq!(dump.parse::<MonitorSnapshot>().unwrap().values.len(), 3);
+}
+
+#[test]
+fn test_profile() {
+    let mut backend = backend();
+    let path = std::env::temp_dir().join(format!("ddc-macos-profiles-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "[profiles.night]\nbrightness = 20\ncontrast = 40\n\n\
+         [[profiles.night.override]]\nmonitor = \"name=P2720D\"\nbrightness = 10\n",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_eq!(
+        run(&mut backend, &["profile", "--dry-run", path, "night"]).unwrap(),
+        "1: DELL U2415 (id 10ac:a0c4:12345)\n  Brightness: 50 -> 20\n  Contrast: 75 -> 40\n\
+         2: DELL P2720D (id 10ac:d0ba:0)\n  Brightness: 20 -> 10\n  Contrast: skipped, could not be read\n"
+    );
+    assert!(backend.writes.is_empty());
+    run(
+        &mut backend,
+        &["-d", "1", "profile", "--only", "contrast", path, "night"],
+    )
+    .unwrap();
+    assert_eq!(backend.writes, vec![(0, 0x12, 40)]);
+    run(&mut backend, &["profile", path, "night"]).unwrap();
+    assert_eq!(backend.writes[1..], [(0, 0x10, 20), (1, 0x10, 10)]);
+    assert!(matches!(
+        run(&mut backend, &["profile", path, "day"]),
+        Err(Error::InvalidArgument(_))
+    ));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_json_output() {
+    let mut backend = backend();
+    let json: serde_json::Value = serde_json::from_str(&run(&mut backend, &["--json", "get", "10"]).unwrap()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "schema": JSON_SCHEMA_VERSION,
+            "command": "get",
+            "result": {"code": 16, "ty": 0, "value": 50, "maximum": 100},
+        })
+    );
+
+    assert_eq!(
+        run_json(&mut backend, &["set", "10", "+5"]),
+        serde_json::json!({
+            "schema": JSON_SCHEMA_VERSION,
+            "command": "set",
+            "result": {"code": 16, "from": 50, "to": 55},
+        })
+    );
+
+    let list: serde_json::Value = serde_json::from_str(&run(&mut backend, &["--json", "list"]).unwrap()).unwrap();
+    assert_eq!(list["result"][0]["id"], "10ac:a0c4:12345");
+    assert_eq!(list["result"][1]["index"], 2);
+    assert_eq!(
+        serde_json::to_value(JsonError::from(&Error::MonitorNotFound("index 3".into()))).unwrap(),
+        serde_json::json!({"schema": JSON_SCHEMA_VERSION, "error": "No monitor matches index 3"})
+    );
+
+    // Invalid arguments are reported as JSON too, if it was asked for
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_ddc-macos"))
+        .args(["--json", "get", "no-such-feature"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let error: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(error["schema"], JSON_SCHEMA_VERSION);
+    assert!(
+        error["error"].as_str().unwrap().contains("no-such-feature"),
+        "{}",
+        error
+    );
+    assert_eq!(
+        FeatureValue::new(0x10, &backend.get_vcp_feature(0, 0x10).unwrap()).vcp_value(),
+        backend.get_vcp_feature(0, 0x10).unwrap()
+    );
+}
+
+/// Runs a command with `--json` and parses its output
+fn run_json(backend: &mut MockBackend, args: &[&str]) -> serde_json::Value {
+    let args: Vec<&str> = ["--json"].iter().chain(args).copied().collect();
+    serde_json::from_str(&run(backend, &args).unwrap()).unwrap()
+}
+
+/// The JSON output is pinned here rather than derived from the library types, so any change to it
+/// shows up as a failure and has to come with a new [JSON_SCHEMA_VERSION] if it is not an addition
+#[test]
+fn test_json_schema() {
+    let mut backend = backend();
+    let identity = serde_json::json!({"vendor": 4268, "model": 41156, "serial": 12345, "name": "DELL U2415"});
+    assert_eq!(
+        run_json(&mut backend, &["list"]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "list",
+            "result": [
+                {
+                    "index": 1,
+                    "id": "10ac:a0c4:12345",
+                    "identity": identity,
+                    "description": "DELL U2415",
+                    "serial_number": "CN0XYZ",
+                    "connection": null,
+                },
+                {
+                    "index": 2,
+                    "id": "10ac:d0ba:0",
+                    "identity": {"vendor": 4268, "model": 53434, "serial": 0, "name": "DELL P2720D"},
+                    "description": "DELL P2720D",
+                    "serial_number": null,
+                    "connection": null,
+                },
+            ],
+        })
+    );
+    assert_eq!(
+        run_json(&mut backend, &["info"]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "info",
+            "result": {
+                "firmware": {"version": 1, "revision": 2},
+                "mccs_version": {"major": 2, "minor": 1},
+                "controller": null,
+                "technology": null,
+                "usage_hours": null,
+                "application_enable_key": null,
+            },
+        })
+    );
+    assert_eq!(
+        run_json(&mut backend, &["caps"]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "capabilities",
+            "result": {
+                "raw": "(prot(monitor)type(lcd)model(U2415)cmds(01 02 03 0C)vcp(10 12 60(0F 11) C9 E2)mccs_ver(2.1))",
+                "parsed": {
+                    "protocol": "Monitor",
+                    "display_type": "Lcd",
+                    "model": "U2415",
+                    "commands": [1, 2, 3, 12],
+                    "mccs_version": {"major": 2, "minor": 1},
+                    "vcp_features": {
+                        "16": {"values": []},
+                        "18": {"values": []},
+                        "96": {"values": [15, 17]},
+                        "201": {"values": []},
+                        "226": {"values": []},
+                    },
+                    "extensions": {},
+                },
+            },
+        })
+    );
+    assert_eq!(
+        run_json(&mut backend, &["edid"]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "edid",
+            "result": {"data": "000102030405060708090a0b0c0d0e0f10111213", "parsed": null},
+        })
+    );
+    assert_eq!(
+        run_json(&mut backend, &["-d", "2", "edid"]),
+        serde_json::json!({"schema": 1, "command": "edid", "result": {"data": null, "parsed": null}})
+    );
+    assert_eq!(
+        run_json(&mut backend, &["dump"]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "dump",
+            "result": {
+                "identity": identity,
+                "values": [
+                    {"code": 16, "value": 50, "maximum": 100},
+                    {"code": 18, "value": 75, "maximum": 100},
+                    {"code": 96, "value": 15, "maximum": 18},
+                    {"code": 201, "value": 258, "maximum": 0},
+                ],
+            },
+        })
+    );
+
+    let dump = std::env::temp_dir().join(format!("ddc-macos-json-{}.txt", std::process::id()));
+    std::fs::write(&dump, run(&mut backend, &["dump"]).unwrap()).unwrap();
+    let profiles = std::env::temp_dir().join(format!("ddc-macos-json-{}.toml", std::process::id()));
+    std::fs::write(
+        &profiles,
+        "[profiles.night]\nbrightness = 20\ncontrast = 40\n\n\
+         [[profiles.night.override]]\nmonitor = \"name=P2720D\"\nbrightness = 10\n",
+    )
+    .unwrap();
+    run(&mut backend, &["set", "10", "30"]).unwrap();
+    assert_eq!(
+        run_json(&mut backend, &["restore", "--dry-run", dump.to_str().unwrap()]),
+        serde_json::json!({
+            "schema": 1,
+            "command": "restore",
+            "result": {"dry_run": true, "changes": [{"code": 16, "from": 30, "to": 50}]},
+        })
+    );
+    assert_eq!(
+        run_json(
+            &mut backend,
+            &["profile", "--dry-run", profiles.to_str().unwrap(), "night"]
+        ),
+        serde_json::json!({
+            "schema": 1,
+            "command": "profile",
+            "result": {
+                "dry_run": true,
+                "monitors": [
+                    {
+                        "index": 1,
+                        "id": "10ac:a0c4:12345",
+                        "description": "DELL U2415",
+                        "changes": [{"code": 16, "from": 30, "to": 20}, {"code": 18, "from": 75, "to": 40}],
+                        "skipped": [],
+                    },
+                    {
+                        "index": 2,
+                        "id": "10ac:d0ba:0",
+                        "description": "DELL P2720D",
+                        "changes": [{"code": 16, "from": 20, "to": 10}],
+                        "skipped": [18],
+                    },
+                ],
+            },
+        })
+    );
+    std::fs::remove_file(dump).unwrap();
+    std::fs::remove_file(profiles).unwrap();
+}
@@ -0,0 +1,61 @@
+extern crate ddc_macos;
+
+use ddc_macos::{ColorPreset, ColorSettings, ColorTemperature, RgbValues};
+
+#[test]
+fn test_color_temperature() {
+    let temperature = ColorTemperature {
+        request: 70,
+        increment: Some(50),
+    };
+    assert_eq!(temperature.kelvin(), Some(6500));
+    assert_eq!(ColorTemperature::for_kelvin(6500, 50), temperature);
+    assert_eq!(ColorTemperature::for_kelvin(6520, 50).request, 70);
+    assert_eq!(ColorTemperature::for_kelvin(6530, 50).request, 71);
+    assert_eq!(ColorTemperature::for_kelvin(2000, 50).request, 0);
+    assert_eq!(
+        ColorTemperature {
+            request: 70,
+            increment: None
+        }
+        .kelvin(),
+        None
+    );
+}
+
+#[test]
+fn test_writes_order() {
+    let settings = ColorSettings {
+        preset: Some(ColorPreset::User1),
+        color_temperature: None,
+        gain: Some(RgbValues {
+            red: 100,
+            green: 98,
+            blue: 95,
+        }),
+        black_level: Some(RgbValues {
+            red: 50,
+            green: 50,
+            blue: 52,
+        }),
+    };
+    assert_eq!(
+        settings.writes(),
+        [
+            (0x14, 0x0b),
+            (0x16, 100),
+            (0x18, 98),
+            (0x1a, 95),
+            (0x6c, 50),
+            (0x6e, 50),
+            (0x70, 52)
+        ]
+    );
+    assert!(ColorSettings::default().writes().is_empty());
+
+    let temperature_only = ColorSettings {
+        color_temperature: Some(ColorTemperature::for_kelvin(5000, 100)),
+        ..Default::default()
+    };
+    assert_eq!(temperature_only.writes(), [(0x0c, 20)]);
+}
@@ -0,0 +1,52 @@
+extern crate ddc_macos;
+
+use ddc::commands::{Command, CommandResult};
+use ddc_macos::{IdentificationReply, IdentificationRequest, TimingReport, TimingReportRequest};
+
+#[test]
+fn test_encode_requests() {
+    let mut data = [0u8; 4];
+    assert_eq!(TimingReportRequest.encode(&mut data), Ok(1));
+    assert_eq!(data[0], 0x07);
+    assert_eq!(IdentificationRequest.encode(&mut data), Ok(1));
+    assert_eq!(data[0], 0xf1);
+}
+
+#[test]
+fn test_decode_timing_report() {
+    // 67.50 kHz / 60.00 Hz, positive sync polarities
+    let report = TimingReport::decode(&[0x4e, 0x03, 0x1a, 0x5e, 0x17, 0x70]).unwrap();
+    assert_eq!(report.horizontal_frequency, 6750);
+    assert_eq!(report.vertical_frequency, 6000);
+    assert!(!report.status.out_of_range());
+    assert!(!report.status.unstable());
+    assert!(report.status.positive_horizontal_sync());
+    assert!(report.status.positive_vertical_sync());
+    assert_eq!(
+        report.to_string(),
+        "67.50 kHz / 60.00 Hz (in range, H sync +, V sync +)"
+    );
+}
+
+#[test]
+fn test_decode_timing_report_out_of_range() {
+    let report = TimingReport::decode(&[0x4e, 0xc0, 0x00, 0x00, 0x00, 0x00]).unwrap();
+    assert!(report.status.out_of_range());
+    assert!(report.status.unstable());
+    assert!(!report.status.positive_vertical_sync());
+}
+
+#[test]
+fn test_decode_invalid_timing_report() {
+    assert!(TimingReport::decode(&[0x4e, 0x03, 0x1a, 0x5e, 0x17]).is_err());
+    assert!(TimingReport::decode(&[0x02, 0x03, 0x1a, 0x5e, 0x17, 0x70]).is_err());
+    assert!(TimingReport::decode(&[]).is_err());
+}
+
+#[test]
+fn test_decode_identification() {
+    let reply = IdentificationReply::decode(&[0xe1, 0x10, 0xac, 0xc4, 0xa0]).unwrap();
+    assert_eq!(reply.data, vec![0x10, 0xac, 0xc4, 0xa0]);
+    assert!(IdentificationReply::decode(&[0xe3, 0x00]).is_err());
+    assert!(IdentificationReply::decode(&[]).is_err());
+}
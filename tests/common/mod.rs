@@ -0,0 +1,201 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use ddc::{DdcCommandMarker, DdcCommandRaw, DdcCommandRawMarker, DdcHost, Delay, ErrorCode, FeatureCode, VcpValue};
+use ddc_macos::{
+    Capabilities, DisplayBackend, DisplayDescription, Error, MonitorIdentity, MonitorSnapshot, Quirks, SafeMode,
+    SimulatedBackend, SnapshotValue,
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+/// Identity of a Dell U2415 with the given serial number and product name
+pub fn dell(serial: u32, name: Option<&str>) -> MonitorIdentity {
+    MonitorIdentity {
+        vendor: 0x10ac,
+        model: 0xa0c4,
+        serial,
+        name: name.map(Into::into),
+    }
+}
+
+/// Identity of a monitor model that reports neither a serial number nor a product name
+pub fn model(vendor: u32, model: u32) -> MonitorIdentity {
+    MonitorIdentity {
+        vendor,
+        model,
+        serial: 0,
+        name: None,
+    }
+}
+
+/// Snapshot of the Dell U2415 with serial number 12345, holding the given values with a maximum of 100
+pub fn snapshot(values: &[(u8, u16)]) -> MonitorSnapshot {
+    MonitorSnapshot {
+        identity: dell(12345, Some("DELL U2415")),
+        values: values
+            .iter()
+            .map(|(code, value)| SnapshotValue {
+                code: *code,
+                value: *value,
+                maximum: 100,
+            })
+            .collect(),
+        skipped: Vec::new(),
+    }
+}
+
+/// VCP value as read from a monitor
+pub fn vcp_value(value: u16, maximum: u16) -> VcpValue {
+    VcpValue {
+        ty: 0,
+        mh: (maximum >> 8) as u8,
+        ml: maximum as u8,
+        sh: (value >> 8) as u8,
+        sl: value as u8,
+    }
+}
+
+/// Backend whose monitors reset their brightness to `brightness` whenever the color preset (0x14)
+/// is written, as many monitors do when switching presets
+pub struct PresetResetBackend {
+    pub inner: SimulatedBackend,
+    pub brightness: u16,
+}
+
+impl DisplayBackend for PresetResetBackend {
+    fn displays(&mut self) -> Result<Vec<DisplayDescription>, Error> {
+        self.inner.displays()
+    }
+
+    fn get_vcp_feature(&mut self, display: usize, code: FeatureCode) -> Result<VcpValue, Error> {
+        self.inner.get_vcp_feature(display, code)
+    }
+
+    fn set_vcp_feature(&mut self, display: usize, code: FeatureCode, value: u16) -> Result<(), Error> {
+        self.inner.set_vcp_feature(display, code, value)?;
+        if code == 0x14 {
+            self.inner.set_value(display, 0x10, self.brightness);
+        }
+        Ok(())
+    }
+
+    fn capabilities_string(&mut self, display: usize) -> Result<Vec<u8>, Error> {
+        self.inner.capabilities_string(display)
+    }
+
+    fn edid(&mut self, display: usize) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.edid(display)
+    }
+}
+
+/// Simulated DDC/CI connection to a single monitor, answering VCP feature and capabilities
+/// requests like a real one would
+#[derive(Debug, Default)]
+pub struct MockDdc {
+    /// Supported VCP features, with their current and maximum values
+    pub values: BTreeMap<FeatureCode, (u16, u16)>,
+    /// Capabilities string, sent in fragments of 32 bytes
+    pub capabilities: Vec<u8>,
+    /// Keep sending capabilities fragments, however far the offset gets
+    pub endless_capabilities: bool,
+    /// Number of upcoming transfers that fail
+    pub failures: usize,
+    /// Number of upcoming writes the monitor accepts without changing the value
+    pub ignored_writes: usize,
+    /// Codes queued in the Active Control FIFO (VCP 0x52), if the monitor supports it
+    pub active_controls: VecDeque<FeatureCode>,
+    /// Set VCP Feature commands received
+    pub writes: Vec<(FeatureCode, u16)>,
+    /// Safe mode, checking commands like a Monitor does against the capabilities it has cached
+    pub safe_mode: Option<(SafeMode, Option<Capabilities>)>,
+}
+
+impl MockDdc {
+    pub fn with_value(mut self, code: FeatureCode, value: u16, maximum: u16) -> Self {
+        self.values.insert(code, (value, maximum));
+        self
+    }
+
+    pub fn value(&self, code: FeatureCode) -> Option<u16> {
+        self.values.get(&code).map(|(value, _)| *value)
+    }
+
+    /// Changes a value as the monitor's own controls would, reporting it through the New Control
+    /// Value flag (VCP 0x02) and the Active Control FIFO if the monitor supports them
+    pub fn change(&mut self, code: FeatureCode, value: u16) {
+        if let Some((current, _)) = self.values.get_mut(&code) {
+            *current = value;
+        }
+        if let Some((flag, _)) = self.values.get_mut(&0x02) {
+            *flag = 0x02;
+            self.active_controls.push_back(code);
+        }
+    }
+}
+
+impl DdcHost for MockDdc {
+    type Error = Error;
+}
+
+impl DdcCommandRaw for MockDdc {
+    fn execute_raw<'a>(
+        &mut self,
+        data: &[u8],
+        out: &'a mut [u8],
+        _response_delay: Duration,
+    ) -> Result<&'a mut [u8], Error> {
+        if let Some((safe_mode, capabilities)) = &self.safe_mode {
+            safe_mode.check_command(capabilities.as_ref(), &Quirks::default(), data)?;
+        }
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(Error::Ddc(ErrorCode::InvalidChecksum));
+        }
+        let reply = match *data {
+            [0x01, 0x52] if self.values.contains_key(&0x52) => {
+                let code = self.active_controls.pop_front().unwrap_or_default();
+                vec![0x02, 0x00, 0x52, 0x00, 0x00, 0xff, 0x00, code]
+            }
+            [0x01, code] => match self.values.get(&code) {
+                Some((value, maximum)) => {
+                    let [mh, ml] = maximum.to_be_bytes();
+                    let [sh, sl] = value.to_be_bytes();
+                    vec![0x02, 0x00, code, 0x00, mh, ml, sh, sl]
+                }
+                // Monitor decodes the unsupported result code of the reply into this error
+                None => return Err(Error::UnsupportedFeature { code }),
+            },
+            [0x03, code, high, low] => {
+                let value = u16::from_be_bytes([high, low]);
+                self.writes.push((code, value));
+                if self.ignored_writes > 0 {
+                    self.ignored_writes -= 1;
+                } else if let Some((current, _)) = self.values.get_mut(&code) {
+                    *current = value;
+                }
+                Vec::new()
+            }
+            [0x0c] => Vec::new(),
+            [0xf3, high, low] => {
+                let offset = usize::from(u16::from_be_bytes([high, low]));
+                let fragment = if self.endless_capabilities {
+                    b"vcp(10 12 14 60)".as_slice()
+                } else {
+                    let start = offset.min(self.capabilities.len());
+                    &self.capabilities[start..(start + 32).min(self.capabilities.len())]
+                };
+                [&[0xe3, high, low][..], fragment].concat()
+            }
+            _ => return Err(Error::Ddc(ErrorCode::InvalidOpcode)),
+        };
+        out[..reply.len()].copy_from_slice(&reply);
+        Ok(&mut out[..reply.len()])
+    }
+}
+
+impl DdcCommandRawMarker for MockDdc {
+    fn set_sleep_delay(&mut self, _delay: Delay) {}
+}
+
+impl DdcCommandMarker for MockDdc {}
@@ -0,0 +1,52 @@
+extern crate ddc_macos;
+
+use ddc_macos::{ColorPreset, ContinuousValue, InputSource, PowerMode};
+
+#[test]
+fn test_percent_scaling() {
+    let value = ContinuousValue {
+        value: 50,
+        maximum: 100,
+    };
+    assert_eq!(value.percent(), 50);
+    assert_eq!(value.value_for_percent(30), Some(30));
+    assert_eq!(value.value_for_percent(101), None);
+
+    let value = ContinuousValue { value: 32, maximum: 64 };
+    assert_eq!(value.percent(), 50);
+    assert_eq!(value.value_for_percent(25), Some(16));
+    assert_eq!(value.value_for_percent(100), Some(64));
+    assert_eq!(value.value_for_percent(1), Some(1));
+
+    assert_eq!(ContinuousValue { value: 0, maximum: 0 }.percent(), 0);
+}
+
+#[test]
+fn test_value_conversions() {
+    assert_eq!(InputSource::from(0x0f), InputSource::DisplayPort1);
+    assert_eq!(InputSource::Hdmi2.value(), 0x12);
+    assert_eq!(InputSource::from(0x1234), InputSource::Other(0x1234));
+    assert_eq!(u16::from(PowerMode::Standby), 0x02);
+    assert_eq!(PowerMode::from(0x05), PowerMode::PowerOff);
+    assert_eq!(ColorPreset::from(0x05), ColorPreset::Kelvin6500);
+}
+
+#[test]
+fn test_value_display() {
+    assert_eq!(InputSource::DisplayPort1.to_string(), "DisplayPort-1");
+    assert_eq!(InputSource::Other(0x42).to_string(), "0x42");
+    assert_eq!(PowerMode::Off.to_string(), "Off");
+    assert_eq!(ColorPreset::Srgb.to_string(), "sRGB");
+}
+
+#[test]
+fn test_power_mode_states() {
+    assert!(PowerMode::On.is_on());
+    assert!(!PowerMode::On.is_asleep());
+    for mode in [PowerMode::Standby, PowerMode::Suspend, PowerMode::Off] {
+        assert!(mode.is_asleep());
+        assert!(!mode.is_on());
+    }
+    assert!(!PowerMode::PowerOff.is_asleep());
+    assert!(!PowerMode::from(0x42).is_asleep());
+}
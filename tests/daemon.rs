@@ -0,0 +1,258 @@
+#![cfg(feature = "daemon")]
+extern crate ddc_macos;
+extern crate serde_json;
+
+use ddc_macos::{
+    Daemon, DaemonClient, DaemonOptions, DaemonServer, DisplayDescription, Error, FeatureParam, MonitorIdentity,
+    RpcRequest, SimulatedBackend, SimulatedDisplay, ValueParam, CHANGED_NOTIFICATION, INVALID_PARAMS, INVALID_REQUEST,
+    METHOD_NOT_FOUND, MONITOR_ERROR, PARSE_ERROR,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+fn display(name: &str, serial: u32) -> SimulatedDisplay {
+    SimulatedDisplay::new(DisplayDescription {
+        identity: MonitorIdentity {
+            vendor: 0x10ac,
+            model: 0xa0c4,
+            serial,
+            name: Some(name.into()),
+        },
+        description: name.into(),
+        serial_number: None,
+        connection: None,
+    })
+    .with_value(0x10, 50, 100)
+    .with_value(0x12, 75, 100)
+    .with_capabilities("(prot(monitor)type(lcd)model(U2720Q)vcp(10 12)mccs_ver(2.1))")
+}
+
+/// Two simulated buses, with one monitor each
+fn daemon(options: DaemonOptions) -> (Daemon, SimulatedBackend, SimulatedBackend) {
+    let first = SimulatedBackend::new(vec![display("Left", 1)]);
+    let second = SimulatedBackend::new(vec![display("Right", 2)]);
+    let daemon = Daemon::new(vec![first.clone(), second.clone()], options).unwrap();
+    (daemon, first, second)
+}
+
+fn call(daemon: &Daemon, method: &str, params: Value) -> Value {
+    let (session, _) = daemon.open_session();
+    let response = daemon.handle(&session, RpcRequest::new(7, method, params)).unwrap();
+    assert_eq!(response.id, json!(7));
+    match response.error {
+        Some(error) => json!({ "error": error.code }),
+        None => response.result.unwrap(),
+    }
+}
+
+#[test]
+fn test_list_and_get() {
+    let (daemon, _, _) = daemon(DaemonOptions::default());
+    let list = call(&daemon, "list", Value::Null);
+    assert_eq!(list[0]["index"], 1);
+    assert_eq!(list[1]["description"], "Right");
+
+    let value = call(&daemon, "get", json!({ "code": "brightness" }));
+    assert_eq!(value["value"], 50);
+    assert_eq!(value["maximum"], 100);
+    let value = call(&daemon, "get", json!({ "display": "2", "code": 0x12 }));
+    assert_eq!(value["value"], 75);
+    let value = call(&daemon, "get", json!({ "display": "name=right", "code": "0x10" }));
+    assert_eq!(value["value"], 50);
+}
+
+#[test]
+fn test_set() {
+    let (daemon, first, second) = daemon(DaemonOptions::default());
+    let value = call(
+        &daemon,
+        "set",
+        json!({ "display": "2", "code": "contrast", "value": 30 }),
+    );
+    assert_eq!(value["value"], 30);
+    let value = call(
+        &daemon,
+        "set",
+        json!({ "display": "2", "code": "contrast", "value": "+5" }),
+    );
+    assert_eq!(value["value"], 35);
+    assert_eq!(second.writes(), vec![(0, 0x12, 30), (0, 0x12, 35)]);
+    assert!(first.writes().is_empty());
+}
+
+#[test]
+fn test_concurrent_relative_sets() {
+    let (daemon, first, _) = daemon(DaemonOptions::default());
+    let daemon = Arc::new(daemon);
+    let threads: Vec<_> = (0..10)
+        .map(|_| {
+            let daemon = daemon.clone();
+            thread::spawn(move || daemon.set(0, 0x10, ddc_macos::ValueArgument::Relative(1)).unwrap())
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    // No increment is lost to another one reading the same value
+    assert_eq!(first.value(0, 0x10), Some(60));
+    assert_eq!(first.writes().len(), 10);
+}
+
+#[test]
+fn test_capabilities() {
+    let (daemon, _, _) = daemon(DaemonOptions::default());
+    let capabilities = call(&daemon, "capabilities", json!({}));
+    assert_eq!(capabilities["model"], "U2720Q");
+    assert!(capabilities["vcp_features"].get("18").is_some());
+}
+
+#[test]
+fn test_errors() {
+    let (daemon, _, _) = daemon(DaemonOptions::default());
+    assert_eq!(
+        call(&daemon, "frobnicate", Value::Null),
+        json!({ "error": METHOD_NOT_FOUND })
+    );
+    assert_eq!(call(&daemon, "get", json!({})), json!({ "error": INVALID_PARAMS }));
+    assert_eq!(
+        call(&daemon, "get", json!({ "code": "no such feature" })),
+        json!({ "error": INVALID_PARAMS })
+    );
+    assert_eq!(
+        call(&daemon, "get", json!({ "display": "3", "code": 0x10 })),
+        json!({ "error": INVALID_PARAMS })
+    );
+    assert_eq!(
+        call(&daemon, "get", json!({ "code": 0x60 })),
+        json!({ "error": MONITOR_ERROR })
+    );
+
+    let (session, _) = daemon.open_session();
+    let error = |line: &str| {
+        let response: Value = serde_json::from_str(&daemon.handle_line(&session, line).unwrap()).unwrap();
+        (response["id"].clone(), response["error"]["code"].clone())
+    };
+    assert_eq!(error("{"), (Value::Null, json!(PARSE_ERROR)));
+    assert_eq!(error(r#"{"jsonrpc":"2.0","id":3}"#), (json!(3), json!(INVALID_REQUEST)));
+    assert_eq!(
+        error(r#"{"jsonrpc":"1.0","id":4,"method":"list"}"#),
+        (json!(4), json!(INVALID_REQUEST))
+    );
+    // Notifications are not answered
+    assert_eq!(
+        daemon.handle_line(&session, r#"{"jsonrpc":"2.0","method":"list"}"#),
+        None
+    );
+}
+
+#[test]
+fn test_value_cache() {
+    let (daemon, first, _) = daemon(DaemonOptions {
+        value_ttl: Duration::from_secs(60),
+        ..DaemonOptions::default()
+    });
+    assert_eq!(daemon.get(0, 0x10, false).unwrap().value, 50);
+    first.set_value(0, 0x10, 80);
+    assert_eq!(daemon.get(0, 0x10, false).unwrap().value, 50);
+    assert_eq!(daemon.get(0, 0x10, true).unwrap().value, 80);
+
+    let (daemon, first, _) = daemon_without_cache();
+    assert_eq!(daemon.get(0, 0x10, false).unwrap().value, 50);
+    first.set_value(0, 0x10, 80);
+    assert_eq!(daemon.get(0, 0x10, false).unwrap().value, 80);
+}
+
+fn daemon_without_cache() -> (Daemon, SimulatedBackend, SimulatedBackend) {
+    daemon(DaemonOptions {
+        value_ttl: Duration::ZERO,
+        ..DaemonOptions::default()
+    })
+}
+
+#[test]
+fn test_subscriptions() {
+    let (daemon, first, second) = daemon_without_cache();
+    let (session, notifications) = daemon.open_session();
+    let (other, other_notifications) = daemon.open_session();
+    let subscription = daemon.subscribe(&session, 0, vec![0x10]).unwrap();
+
+    // Changes made on the monitor are found by polling
+    first.set_value(0, 0x10, 60);
+    second.set_value(0, 0x10, 60);
+    daemon.poll_subscriptions();
+    let notification = notifications.try_recv().unwrap();
+    assert_eq!(notification.method, CHANGED_NOTIFICATION);
+    assert_eq!(notification.params["subscription"], subscription);
+    assert_eq!(notification.params["display"], 1);
+    assert_eq!(notification.params["previous"], 50);
+    assert_eq!(notification.params["value"]["value"], 60);
+    assert!(notifications.try_recv().is_err());
+
+    // Changes made by other clients are sent right away
+    daemon.set(0, 0x10, ddc_macos::ValueArgument::Absolute(70)).unwrap();
+    assert_eq!(notifications.try_recv().unwrap().params["value"]["value"], 70);
+    daemon.set(0, 0x12, ddc_macos::ValueArgument::Absolute(70)).unwrap();
+    assert!(notifications.try_recv().is_err());
+    assert!(other_notifications.try_recv().is_err());
+
+    // Unchanged values are not notified
+    daemon.poll_subscriptions();
+    assert!(notifications.try_recv().is_err());
+
+    assert!(!daemon.unsubscribe(&other, subscription));
+    assert!(daemon.unsubscribe(&session, subscription));
+    first.set_value(0, 0x10, 20);
+    daemon.poll_subscriptions();
+    assert!(notifications.try_recv().is_err());
+}
+
+#[test]
+fn test_closed_session() {
+    let (daemon, first, _) = daemon_without_cache();
+    let (session, notifications) = daemon.open_session();
+    daemon.subscribe(&session, 0, vec![0x10]).unwrap();
+    daemon.close_session(session);
+    first.set_value(0, 0x10, 60);
+    daemon.poll_subscriptions();
+    assert!(notifications.try_recv().is_err());
+}
+
+#[test]
+fn test_socket() {
+    let (daemon, first, _) = daemon(DaemonOptions {
+        value_ttl: Duration::ZERO,
+        poll_interval: Duration::from_millis(20),
+    });
+    let path = std::env::temp_dir().join(format!("ddc-macos-test-{}.sock", std::process::id()));
+    let server = DaemonServer::bind(Arc::new(daemon), &path).unwrap();
+    let server = thread::spawn(move || server.serve());
+
+    let mut client = DaemonClient::connect(&path).unwrap();
+    let mut other = DaemonClient::connect(&path).unwrap();
+    assert_eq!(client.list().unwrap().len(), 2);
+    assert_eq!(client.capabilities(Some("1")).unwrap().model.as_deref(), Some("U2720Q"));
+    let code = || FeatureParam::Name("brightness".into());
+    assert_eq!(client.get(None, code(), false).unwrap().value, 50);
+
+    let subscription = client.subscribe(None, vec![code()]).unwrap();
+    assert_eq!(other.set(None, code(), ValueParam::Value(40)).unwrap().value, 40);
+    let notification = client.next_notification(Duration::from_secs(5)).unwrap().unwrap();
+    assert_eq!(notification.subscription, subscription);
+    assert_eq!((notification.previous, notification.value.value), (Some(50), 40));
+
+    first.set_value(0, 0x10, 45);
+    let notification = client.next_notification(Duration::from_secs(5)).unwrap().unwrap();
+    assert_eq!(notification.value.value, 45);
+
+    match other.get(None, FeatureParam::Code(0x60), false) {
+        Err(Error::Rpc { code, .. }) => assert_eq!(code, MONITOR_ERROR),
+        result => panic!("unexpected result {:?}", result),
+    }
+    assert!(client.unsubscribe(subscription).unwrap());
+    assert_eq!(client.next_notification(Duration::from_millis(50)).unwrap(), None);
+
+    drop(server);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,53 @@
+extern crate ddc_macos;
+
+use ddc_macos::{Edid, Error};
+
+fn edid() -> Vec<u8> {
+    let mut data = vec![0u8; 128];
+    data[..8].copy_from_slice(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+    // "DEL"
+    data[8..10].copy_from_slice(&[0x10, 0xac]);
+    data[10..12].copy_from_slice(&0xa0c4u16.to_le_bytes());
+    data[12..16].copy_from_slice(&12345u32.to_le_bytes());
+    data[16] = 12;
+    data[17] = 27;
+    data[18] = 1;
+    data[19] = 4;
+    data[72..77].copy_from_slice(&[0, 0, 0, 0xfc, 0]);
+    data[77..90].copy_from_slice(b"DELL U2415\n  ");
+    data[90..95].copy_from_slice(&[0, 0, 0, 0xff, 0]);
+    data[95..108].copy_from_slice(b"CN0XYZ123456A");
+    data
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        Edid::parse(&edid()).unwrap(),
+        Edid {
+            manufacturer: "DEL".into(),
+            product_code: 0xa0c4,
+            serial: 12345,
+            week: 12,
+            year: 2017,
+            version: 1,
+            revision: 4,
+            name: Some("DELL U2415".into()),
+            serial_number: Some("CN0XYZ123456A".into()),
+        }
+    );
+}
+
+#[test]
+fn test_parse_invalid() {
+    assert!(matches!(Edid::parse(&edid()[..100]), Err(Error::InvalidEdid(_))));
+    let mut data = edid();
+    data[0] = 0xff;
+    assert!(matches!(Edid::parse(&data), Err(Error::InvalidEdid(_))));
+
+    let mut data = edid();
+    data[72..].fill(0);
+    let parsed = Edid::parse(&data).unwrap();
+    assert_eq!(parsed.name, None);
+    assert_eq!(parsed.serial_number, None);
+}
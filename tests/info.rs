@@ -0,0 +1,69 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::vcp_value;
+use ddc_macos::{display_technology_name, usage_hours, DisplayController, FirmwareLevel, MccsVersion, MonitorInfo};
+
+#[test]
+fn test_decode_items() {
+    assert_eq!(usage_hours(&vcp_value(0x1234, 0)), 0x1234);
+    assert_eq!(usage_hours(&vcp_value(0x0010, 0xff01)), 0x10010);
+
+    let firmware = FirmwareLevel::from(vcp_value(0x0102, 0));
+    assert_eq!(
+        firmware,
+        FirmwareLevel {
+            version: 1,
+            revision: 2
+        }
+    );
+    assert_eq!(firmware.to_string(), "1.02");
+
+    let controller = DisplayController::from(vcp_value(5, 9));
+    assert_eq!(controller.manufacturer_name(), Some("Mstar"));
+    assert_eq!(controller.to_string(), "Mstar (type 0x0009)");
+    assert_eq!(
+        DisplayController::from(vcp_value(0xee, 0)).to_string(),
+        "manufacturer 0xee (type 0x0000)"
+    );
+
+    assert_eq!(display_technology_name(0x03), Some("LCD (active matrix)"));
+}
+
+#[test]
+fn test_display_unsupported() {
+    let info = MonitorInfo {
+        usage_hours: Some(1234),
+        firmware: Some(FirmwareLevel {
+            version: 1,
+            revision: 2,
+        }),
+        technology: Some(0x03),
+        mccs_version: Some(MccsVersion::V2_1),
+        ..Default::default()
+    };
+    assert_eq!(
+        info.to_string(),
+        "Display usage time: 1234 h\n\
+         Firmware level: 1.02\n\
+         Display controller: not supported\n\
+         Display technology: LCD (active matrix)\n\
+         Application enable key: not supported\n\
+         MCCS version: 2.1\n"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_roundtrip() {
+    let info = MonitorInfo {
+        usage_hours: Some(1234),
+        controller: Some(DisplayController::from(vcp_value(5, 9))),
+        mccs_version: Some(MccsVersion::V2_2),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&info).unwrap();
+    assert!(json.contains("\"usage_hours\":1234"));
+    assert_eq!(serde_json::from_str::<MonitorInfo>(&json).unwrap(), info);
+}
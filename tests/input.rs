@@ -0,0 +1,43 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::dell;
+use ddc_macos::{Error, MonitorIdentity};
+
+#[test]
+fn test_same_monitor() {
+    let monitor = dell(12345, Some("DELL U2415"));
+    assert!(monitor.same_monitor(&dell(12345, None)));
+    assert!(!monitor.same_monitor(&dell(54321, Some("DELL U2415"))));
+    assert!(!monitor.same_monitor(&MonitorIdentity {
+        model: 0xa0c5,
+        ..monitor.clone()
+    }));
+    assert!(monitor.same_model(&dell(54321, None)));
+}
+
+#[test]
+fn test_same_monitor_without_serial() {
+    let monitor = dell(0, Some("DELL U2415"));
+    assert!(monitor.same_monitor(&dell(0, Some("DELL U2415"))));
+    assert!(!monitor.same_monitor(&dell(0, Some("DELL U2415 (2)"))));
+    assert!(!monitor.same_monitor(&dell(12345, Some("DELL U2415"))));
+}
+
+#[test]
+fn test_position_in() {
+    let connected = [
+        dell(1, None),
+        dell(2, None),
+        dell(0, Some("DELL U2415")),
+        dell(0, Some("DELL U2415")),
+    ];
+    assert_eq!(dell(2, None).position_in(&connected).unwrap(), Some(1));
+    assert_eq!(dell(3, None).position_in(&connected).unwrap(), None);
+    // Identical models without serial numbers cannot be told apart
+    assert!(matches!(
+        dell(0, Some("DELL U2415")).position_in(&connected),
+        Err(Error::AmbiguousMonitor(_))
+    ));
+}
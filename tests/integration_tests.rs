@@ -1,3 +1,5 @@
+#![cfg(target_os = "macos")]
+
 extern crate ddc_macos;
 use ddc::Ddc;
 
@@ -0,0 +1,226 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::PresetResetBackend;
+use ddc_macos::{
+    apply_profile, DisplayDescription, Error, MonitorIdentity, Profile, ProfileOptions, ProfileOverride, SettingChange,
+    SimulatedBackend, SimulatedDisplay, ValueArgument, ValueParam,
+};
+use std::collections::BTreeMap;
+
+fn display(name: &str, serial: u32) -> SimulatedDisplay {
+    SimulatedDisplay::new(DisplayDescription {
+        identity: MonitorIdentity {
+            vendor: 0x10ac,
+            model: 0xa0c4,
+            serial,
+            name: Some(name.into()),
+        },
+        description: name.into(),
+        serial_number: None,
+        connection: None,
+    })
+    .with_value(0x10, 50, 100)
+    .with_value(0x12, 50, 100)
+    .with_value(0x14, 0x05, 0x0b)
+}
+
+fn settings(values: &[(&str, ValueParam)]) -> BTreeMap<String, ValueParam> {
+    values
+        .iter()
+        .map(|(feature, value)| (feature.to_string(), value.clone()))
+        .collect()
+}
+
+/// Night profile dimming all monitors, the right one even more
+fn night() -> Profile {
+    Profile {
+        settings: settings(&[
+            ("brightness", ValueParam::Value(20)),
+            ("contrast", ValueParam::Text("-10".into())),
+            ("select-color-preset", ValueParam::Text("5000k".into())),
+            ("audio-speaker-volume", ValueParam::Value(10)),
+        ]),
+        overrides: vec![ProfileOverride {
+            monitor: "name=right".into(),
+            settings: settings(&[("brightness", ValueParam::Value(5))]),
+        }],
+    }
+}
+
+fn backend() -> SimulatedBackend {
+    SimulatedBackend::new(vec![display("Left", 1), display("Right", 2)])
+}
+
+#[test]
+fn test_settings_for() {
+    let profile = night();
+    let right = &display("Right", 2).description;
+    let settings = profile.settings_for(1, right).unwrap();
+    assert!(settings.contains(&(0x10, ValueArgument::Absolute(5))));
+    assert!(settings.contains(&(0x12, ValueArgument::Relative(-10))));
+    assert_eq!(settings.iter().filter(|(code, _)| *code == 0x10).count(), 1);
+    let left = &display("Left", 1).description;
+    assert!(profile
+        .settings_for(0, left)
+        .unwrap()
+        .contains(&(0x10, ValueArgument::Absolute(20))));
+}
+
+#[test]
+fn test_apply() {
+    let backend = backend();
+    let applied = apply_profile(&mut backend.clone(), &night(), &ProfileOptions::default()).unwrap();
+    assert_eq!(applied.len(), 2);
+    // The color preset goes first, as it can change the other settings
+    assert_eq!(
+        applied[0].changes,
+        vec![
+            SettingChange {
+                code: 0x14,
+                from: Some(0x05),
+                to: 0x04
+            },
+            SettingChange {
+                code: 0x10,
+                from: Some(50),
+                to: 20
+            },
+            SettingChange {
+                code: 0x12,
+                from: Some(50),
+                to: 40
+            },
+        ]
+    );
+    assert_eq!(applied[0].skipped, vec![0x62]);
+    assert_eq!(applied[1].changes[1].to, 5);
+    assert_eq!(backend.value(0, 0x10), Some(20));
+    assert_eq!(backend.value(1, 0x10), Some(5));
+    assert_eq!(backend.writes().len(), 6);
+
+    // Applying it again only changes the relative contrast
+    let applied = apply_profile(&mut backend.clone(), &night(), &ProfileOptions::default()).unwrap();
+    assert!(applied.iter().all(|monitor| monitor.changes.len() == 1));
+    assert_eq!(backend.value(0, 0x12), Some(30));
+    assert_eq!(backend.writes().len(), 8);
+}
+
+#[test]
+fn test_apply_after_preset_reset() {
+    // Switching the preset resets the brightness to 80, which the relative setting then applies to
+    let mut backend = PresetResetBackend {
+        inner: SimulatedBackend::new(vec![display("Left", 1)]),
+        brightness: 80,
+    };
+    let profile = Profile {
+        settings: settings(&[
+            ("brightness", ValueParam::Text("-10".into())),
+            ("select-color-preset", ValueParam::Text("5000k".into())),
+        ]),
+        overrides: Vec::new(),
+    };
+    let applied = apply_profile(&mut backend, &profile, &ProfileOptions::default()).unwrap();
+    assert_eq!(
+        applied[0].changes[1],
+        SettingChange {
+            code: 0x10,
+            from: Some(80),
+            to: 70
+        }
+    );
+    assert_eq!(backend.inner.writes(), vec![(0, 0x14, 0x04), (0, 0x10, 70)]);
+}
+
+#[test]
+fn test_apply_partially() {
+    let backend = backend();
+    let options = ProfileOptions {
+        display: Some("2".parse().unwrap()),
+        features: Some(vec![0x10]),
+        dry_run: false,
+    };
+    let applied = apply_profile(&mut backend.clone(), &night(), &options).unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].index, 2);
+    assert_eq!(backend.writes(), vec![(1, 0x10, 5)]);
+
+    let options = ProfileOptions {
+        display: Some("name=center".parse().unwrap()),
+        ..ProfileOptions::default()
+    };
+    assert!(matches!(
+        apply_profile(&mut backend.clone(), &night(), &options),
+        Err(Error::MonitorNotFound(_))
+    ));
+}
+
+#[test]
+fn test_preview() {
+    let backend = backend();
+    let options = ProfileOptions {
+        dry_run: true,
+        ..ProfileOptions::default()
+    };
+    let applied = apply_profile(&mut backend.clone(), &night(), &options).unwrap();
+    assert_eq!(applied[1].changes.len(), 3);
+    assert!(backend.writes().is_empty());
+    assert_eq!(
+        applied[1].to_string(),
+        "2: Right (id 10ac:a0c4:2)\n  \
+         Select Color Preset: 5 -> 4\n  \
+         Brightness: 50 -> 5\n  \
+         Contrast: 50 -> 40\n  \
+         Audio Speaker Volume: skipped, could not be read\n"
+    );
+}
+
+#[test]
+fn test_validate() {
+    let mut profile = night();
+    assert!(profile.validate().is_ok());
+    profile.settings = settings(&[("sharpness-ish", ValueParam::Value(1))]);
+    assert!(profile.validate().is_err());
+    profile.settings = settings(&[("input-source", ValueParam::Text("vga-9".into()))]);
+    assert!(profile.validate().is_err());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_from_toml() {
+    use ddc_macos::Profiles;
+
+    let profiles = Profiles::from_toml(
+        r#"
+        [profiles.night]
+        brightness = 20
+        contrast = "-10"
+        select-color-preset = "5000k"
+        audio-speaker-volume = 10
+
+        [[profiles.night.override]]
+        monitor = "name=right"
+        brightness = 5
+
+        [profiles.day]
+        0x10 = 80
+        "#,
+    )
+    .unwrap();
+    assert_eq!(profiles.get("night").unwrap(), &night());
+    assert_eq!(
+        profiles.get("day").unwrap().settings,
+        settings(&[("0x10", ValueParam::Value(80))])
+    );
+    assert!(matches!(profiles.get("evening"), Err(Error::InvalidArgument(_))));
+
+    assert!(matches!(
+        Profiles::from_toml("[profiles.day]\nbrightnes = 80"),
+        Err(Error::InvalidConfiguration(_))
+    ));
+    assert!(matches!(
+        Profiles::from_toml("[profiles.day]\nbrightness = 80\n[[profiles.day.override]]\nbrightness = 10"),
+        Err(Error::InvalidConfiguration(_))
+    ));
+}
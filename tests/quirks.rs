@@ -0,0 +1,134 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::model;
+use ddc_macos::{MaximumOverride, QuirkEntry, QuirkRegistry, Quirks};
+
+#[test]
+fn test_lookup_specificity() {
+    let mut registry = QuirkRegistry::new();
+    registry.add(QuirkEntry {
+        vendor: 0x4c2d,
+        model: Some(0x0e18),
+        firmware: Some(0x0102),
+        quirks: Quirks {
+            set_vcp_delay_ms: Some(200),
+            ..Default::default()
+        },
+    });
+    registry.add(QuirkEntry {
+        vendor: 0x4c2d,
+        model: Some(0x0e18),
+        firmware: None,
+        quirks: Quirks {
+            set_vcp_delay_ms: Some(150),
+            maximum_overrides: vec![MaximumOverride {
+                code: 0x10,
+                maximum: 100,
+            }],
+            ..Default::default()
+        },
+    });
+    registry.add(QuirkEntry {
+        vendor: 0x4c2d,
+        model: None,
+        firmware: None,
+        quirks: Quirks {
+            set_vcp_delay_ms: Some(100),
+            retries: Some(2),
+            ..Default::default()
+        },
+    });
+
+    let monitor = model(0x4c2d, 0x0e18);
+    assert!(registry.needs_firmware(&monitor));
+    assert!(!registry.needs_firmware(&model(0x4c2d, 0x0f00)));
+
+    let quirks = registry.lookup(&monitor, Some(0x0102));
+    assert_eq!(quirks.set_vcp_delay_ms, Some(200));
+    assert_eq!(quirks.retries, Some(2));
+    assert_eq!(quirks.maximum(0x10), Some(100));
+    assert_eq!(quirks.maximum(0x12), None);
+
+    assert_eq!(registry.lookup(&monitor, Some(0x0103)).set_vcp_delay_ms, Some(150));
+    assert_eq!(registry.lookup(&monitor, None).set_vcp_delay_ms, Some(150));
+    assert_eq!(
+        registry.lookup(&model(0x4c2d, 0x0f00), None).set_vcp_delay_ms,
+        Some(100)
+    );
+    assert_eq!(registry.lookup(&model(0x10ac, 0x0e18), None), Quirks::default());
+}
+
+#[test]
+fn test_builtin() {
+    let registry = QuirkRegistry::builtin();
+    assert!(registry.lookup(&model(0x10ac, 0xa0c4), None).retries.is_some());
+    assert!(!registry.needs_firmware(&model(0x10ac, 0xa0c4)));
+
+    let lg = registry.lookup(&model(0x1e6d, 0x5b11), None);
+    assert_eq!(lg.input_source_high_byte, Some(true));
+    assert_eq!(lg.encode_value(0x60, 0x0f), 0x0f00);
+    assert_eq!(
+        registry.lookup(&model(0x1e6d, 0x5b12), None).input_source_high_byte,
+        None
+    );
+
+    let samsung = registry.lookup(&model(0x4c2d, 0x0e18), None);
+    assert_eq!(samsung.maximum(0x10), Some(100));
+    assert_eq!(samsung.set_vcp_delay_ms, Some(100));
+    assert_eq!(registry.lookup(&model(0x4c2d, 0x0f00), None).maximum(0x10), None);
+}
+
+#[test]
+fn test_input_source_encoding() {
+    let quirks = Quirks {
+        input_source_high_byte: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(quirks.encode_value(0x60, 0x0f), 0x0f00);
+    assert_eq!(quirks.decode_value(0x60, 0x0f00), 0x0f);
+    assert_eq!(quirks.encode_value(0x10, 0x0f), 0x0f);
+    assert_eq!(Quirks::default().encode_value(0x60, 0x0f), 0x0f);
+    assert_eq!(Quirks::default().decode_value(0x60, 0x0f00), 0x0f00);
+
+    // A more specific quirk can turn the encoding off again
+    let mut merged = quirks.clone();
+    merged.merge(&Quirks {
+        input_source_high_byte: Some(false),
+        ..Default::default()
+    });
+    assert_eq!(merged.encode_value(0x60, 0x0f), 0x0f);
+    merged.merge(&Quirks::default());
+    assert_eq!(merged.input_source_high_byte, Some(false));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_from_toml() {
+    let registry = QuirkRegistry::from_toml(
+        r#"
+        [[quirk]]
+        vendor = 0x1e6d
+        model = 0x5b11
+        input_source_high_byte = true
+
+        [[quirk]]
+        vendor = 0x4c2d
+        firmware = 0x0102
+        set_vcp_delay_ms = 150
+        maximum_overrides = [{ code = 0x10, maximum = 100 }]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(registry.entries.len(), 2);
+    assert_eq!(
+        registry.lookup(&model(0x1e6d, 0x5b11), None).input_source_high_byte,
+        Some(true)
+    );
+    let quirks = registry.lookup(&model(0x4c2d, 0x0e18), Some(0x0102));
+    assert_eq!(quirks.set_vcp_delay_ms, Some(150));
+    assert_eq!(quirks.maximum(0x10), Some(100));
+
+    assert!(QuirkRegistry::from_toml("[[quirk]]\nmodel = 1").is_err());
+}
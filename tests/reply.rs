@@ -0,0 +1,63 @@
+extern crate ddc_macos;
+
+use ddc::ErrorCode;
+use ddc_macos::{Error, RetryPolicy};
+use std::time::Duration;
+
+fn policy(busy_retries: usize, retries: usize) -> RetryPolicy {
+    RetryPolicy {
+        busy_retries,
+        busy_delay: Duration::ZERO,
+        retries,
+        retry_delay: Duration::ZERO,
+    }
+}
+
+#[test]
+fn test_retry_busy() {
+    let mut attempts = 0;
+    let result = policy(3, 0).run(|| {
+        attempts += 1;
+        if attempts < 3 {
+            Err(Error::NullMessage)
+        } else {
+            Ok(attempts)
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+
+    let mut attempts = 0;
+    let result: Result<(), Error> = policy(2, 5).run(|| {
+        attempts += 1;
+        Err(Error::NullMessage)
+    });
+    assert!(matches!(result, Err(Error::NullMessage)));
+    assert_eq!(attempts, 3);
+}
+
+#[test]
+fn test_retry_errors() {
+    let mut attempts = 0;
+    let result: Result<(), Error> = policy(5, 1).run(|| {
+        attempts += 1;
+        Err(Error::Ddc(ErrorCode::InvalidChecksum))
+    });
+    assert!(matches!(result, Err(Error::Ddc(ErrorCode::InvalidChecksum))));
+    assert_eq!(attempts, 2);
+
+    let mut attempts = 0;
+    let result: Result<(), Error> = policy(5, 5).run(|| {
+        attempts += 1;
+        Err(Error::ServiceNotFound)
+    });
+    assert!(matches!(result, Err(Error::ServiceNotFound)));
+    assert_eq!(attempts, 1);
+
+    let mut attempts = 0;
+    let result: Result<(), Error> = RetryPolicy::never().run(|| {
+        attempts += 1;
+        Err(Error::NullMessage)
+    });
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+}
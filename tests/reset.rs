@@ -0,0 +1,38 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::snapshot;
+use ddc_macos::{ResetOperation, ResetReport, SettingChange, DESTRUCTIVE_CODES};
+
+#[test]
+fn test_operation_codes() {
+    let operations = [
+        ResetOperation::FactoryDefaults,
+        ResetOperation::LuminanceContrast,
+        ResetOperation::Geometry,
+        ResetOperation::Color,
+    ];
+    for operation in operations {
+        assert!(DESTRUCTIVE_CODES.contains(&operation.code().unwrap()));
+    }
+    assert_eq!(ResetOperation::SaveSettings.code(), None);
+    assert_eq!(ResetOperation::Color.to_string(), "restore factory color defaults");
+}
+
+#[test]
+fn test_undo_changes() {
+    let report = ResetReport {
+        operation: ResetOperation::LuminanceContrast,
+        before: snapshot(&[(0x10, 30), (0x12, 60)]),
+        after: snapshot(&[(0x10, 75), (0x12, 60)]),
+    };
+    assert_eq!(
+        report.undo_changes(),
+        [SettingChange {
+            code: 0x10,
+            from: Some(75),
+            to: 30
+        }]
+    );
+}
@@ -0,0 +1,124 @@
+extern crate ddc;
+extern crate ddc_macos;
+
+mod common;
+
+use common::MockDdc;
+use ddc::Ddc;
+use ddc_macos::{read_capabilities_string, Capabilities, Error, Quirks, SafeMode, DESTRUCTIVE_CODES};
+
+fn capabilities() -> Capabilities {
+    "(prot(monitor)type(lcd)cmds(01 02 03 0C F3)vcp(04 08 10 12 14(04 05 08 0B) 60(0F 11 12) 73)mccs_ver(2.1))"
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn test_destructive_codes_locked() {
+    let caps = capabilities();
+    let mut safe_mode = SafeMode::new();
+    for code in DESTRUCTIVE_CODES {
+        assert!(safe_mode.is_locked(*code));
+    }
+    assert!(matches!(
+        safe_mode.check_write(&caps, 0x04, Some(1)),
+        Err(Error::DestructiveFeature { code: 0x04 })
+    ));
+    safe_mode.unlock(0x04);
+    assert!(safe_mode.check_write(&caps, 0x04, Some(1)).is_ok());
+    // Unlocked codes still have to be advertised
+    safe_mode.unlock(0x05);
+    assert!(matches!(
+        safe_mode.check_write(&caps, 0x05, Some(1)),
+        Err(Error::UnsupportedFeature { code: 0x05 })
+    ));
+    safe_mode.lock(0x04);
+    assert!(safe_mode.is_locked(0x04));
+}
+
+#[test]
+fn test_unadvertised_writes_refused() {
+    let caps = capabilities();
+    let safe_mode = SafeMode::new();
+    assert!(safe_mode.check_write(&caps, 0x10, Some(80)).is_ok());
+    assert!(safe_mode.check_write(&caps, 0x60, Some(0x11)).is_ok());
+    assert!(safe_mode.check_write(&caps, 0x73, None).is_ok());
+    assert!(matches!(
+        safe_mode.check_write(&caps, 0x62, Some(10)),
+        Err(Error::UnsupportedFeature { code: 0x62 })
+    ));
+    assert!(matches!(
+        safe_mode.check_write(&caps, 0x60, Some(0x01)),
+        Err(Error::UnsupportedValue {
+            code: 0x60,
+            value: 0x01
+        })
+    ));
+    let error = safe_mode.check_write(&caps, 0x08, Some(1)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "VCP feature 0x08 resets monitor settings and has to be unlocked first"
+    );
+}
+
+#[test]
+fn test_commands_checked_after_quirk_decoding() {
+    let caps = capabilities();
+    let safe_mode = SafeMode::new();
+    let high_byte = Quirks {
+        input_source_high_byte: Some(true),
+        ..Default::default()
+    };
+    // DisplayPort-1 sent in the high byte to a monitor that expects it there
+    assert!(safe_mode
+        .check_command(Some(&caps), &high_byte, &[0x03, 0x60, 0x0f, 0x00])
+        .is_ok());
+    assert!(matches!(
+        safe_mode.check_command(Some(&caps), &Quirks::default(), &[0x03, 0x60, 0x0f, 0x00]),
+        Err(Error::UnsupportedValue {
+            code: 0x60,
+            value: 0x0f00
+        })
+    ));
+    assert!(matches!(
+        safe_mode.check_command(Some(&caps), &high_byte, &[0x03, 0x60, 0x01, 0x00]),
+        Err(Error::UnsupportedValue {
+            code: 0x60,
+            value: 0x01
+        })
+    ));
+    assert!(matches!(
+        safe_mode.check_command(Some(&caps), &high_byte, &[0x03, 0x04, 0x00, 0x01]),
+        Err(Error::DestructiveFeature { code: 0x04 })
+    ));
+    assert!(safe_mode
+        .check_command(Some(&caps), &high_byte, &[0xe7, 0x73, 0x00, 0x00])
+        .is_ok());
+    // Reads are not restricted
+    assert!(safe_mode.check_command(Some(&caps), &high_byte, &[0x01, 0x62]).is_ok());
+    assert!(safe_mode.check_command(Some(&caps), &high_byte, &[0x0c]).is_ok());
+}
+
+#[test]
+fn test_safe_mode_without_capabilities() {
+    // Reads, including the capabilities request, go through while nothing is cached yet
+    let mut ddc = MockDdc::default().with_value(0x10, 50, 100);
+    ddc.capabilities = b"(vcp(10))".to_vec();
+    ddc.safe_mode = Some((SafeMode::new(), None));
+    assert_eq!(ddc.get_vcp_feature(0x10).unwrap().value(), 50);
+    assert_eq!(read_capabilities_string(&mut ddc).unwrap(), b"(vcp(10))");
+    // Writes are refused rather than checked against nothing
+    assert!(matches!(
+        ddc.set_vcp_feature(0x10, 80),
+        Err(Error::CapabilitiesUnavailable(_))
+    ));
+    assert!(matches!(
+        ddc.save_current_settings(),
+        Err(Error::CapabilitiesUnavailable(_))
+    ));
+    assert!(ddc.writes.is_empty());
+
+    ddc.safe_mode = Some((SafeMode::new(), Some("(vcp(10))".parse().unwrap())));
+    ddc.set_vcp_feature(0x10, 80).unwrap();
+    assert_eq!(ddc.writes, vec![(0x10, 80)]);
+}
@@ -0,0 +1,251 @@
+extern crate ddc_macos;
+
+use ddc_macos::{
+    sun_times, Interpolation, LocalTime, Location, Schedule, SchedulePoint, ScheduleTarget, ScheduleTime, TimeOfDay,
+};
+use std::time::Duration;
+
+const AMSTERDAM: Location = Location {
+    latitude: 52.37,
+    longitude: 4.90,
+};
+/// Central European Summer Time
+const CEST: i32 = 2 * 3600;
+
+fn time(s: &str) -> TimeOfDay {
+    s.parse().unwrap()
+}
+
+fn at(hour_minute: &str) -> LocalTime {
+    LocalTime::new(2024, 6, 21, time(hour_minute), CEST)
+}
+
+fn point(at: &str, brightness: Option<u16>, color_temperature: Option<u32>) -> SchedulePoint {
+    SchedulePoint {
+        at: at.parse().unwrap(),
+        target: ScheduleTarget {
+            brightness,
+            color_temperature,
+        },
+    }
+}
+
+/// Bright during the day, dimming in the evening
+fn schedule(interpolation: Interpolation) -> Schedule {
+    Schedule {
+        interpolation,
+        points: vec![
+            point("22:00", Some(20), Some(3400)),
+            point("07:00", Some(40), Some(5000)),
+            point("08:00", Some(80), None),
+            point("19:00", Some(80), Some(6500)),
+        ],
+        ..Schedule::default()
+    }
+}
+
+fn assert_near(actual: Option<TimeOfDay>, expected: &str) {
+    let difference = i64::from(actual.unwrap().seconds()) - i64::from(time(expected).seconds());
+    assert!(
+        difference.abs() <= 5 * 60,
+        "{} is not close to {}",
+        actual.unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_local_time() {
+    let solstice = at("12:30");
+    assert_eq!(solstice.day, 19_895);
+    assert_eq!(solstice.to_unix(), 1_718_965_800);
+    assert_eq!(LocalTime::from_unix(1_718_965_800, CEST), solstice);
+    assert_eq!(LocalTime::new(1970, 1, 1, TimeOfDay::MIDNIGHT, 0).day, 0);
+    assert_eq!(LocalTime::new(2000, 3, 1, TimeOfDay::MIDNIGHT, 0).day, 11_017);
+
+    let late = at("23:30").after(Duration::from_secs(3600));
+    assert_eq!((late.day, late.time), (19_896, time("00:30")));
+    assert_eq!(at("23:30").until(late), Duration::from_secs(3600));
+    assert_eq!(late.until(at("23:30")), Duration::ZERO);
+}
+
+#[test]
+fn test_times() {
+    assert_eq!(time("7:05"), TimeOfDay::new(7, 5).unwrap());
+    assert_eq!(time("07:05").to_string(), "07:05");
+    assert!("24:00".parse::<TimeOfDay>().is_err());
+    assert!("noon".parse::<TimeOfDay>().is_err());
+
+    for (text, parsed) in [
+        ("sunrise", ScheduleTime::Sunrise(0)),
+        ("sunset-30", ScheduleTime::Sunset(-30)),
+        ("sunrise+90", ScheduleTime::Sunrise(90)),
+        ("21:15", ScheduleTime::At(time("21:15"))),
+    ] {
+        assert_eq!(text.parse::<ScheduleTime>().unwrap(), parsed);
+        assert_eq!(parsed.to_string(), text);
+    }
+    assert!("sunset30".parse::<ScheduleTime>().is_err());
+    assert!("dusk".parse::<ScheduleTime>().is_err());
+}
+
+#[test]
+fn test_sun_times() {
+    let summer = sun_times(at("12:00").day, &AMSTERDAM, CEST);
+    assert_near(summer.sunrise, "05:18");
+    assert_near(summer.sunset, "22:06");
+
+    let winter = LocalTime::new(2024, 12, 21, TimeOfDay::MIDNIGHT, 3600);
+    let winter = sun_times(winter.day, &AMSTERDAM, 3600);
+    assert_near(winter.sunrise, "08:48");
+    assert_near(winter.sunset, "16:29");
+
+    let tromso = Location {
+        latitude: 69.65,
+        longitude: 18.96,
+    };
+    let midnight_sun = sun_times(at("12:00").day, &tromso, CEST);
+    assert_eq!((midnight_sun.sunrise, midnight_sun.sunset), (None, None));
+
+    let sun = sun_times(at("12:00").day, &AMSTERDAM, CEST);
+    assert_near(ScheduleTime::Sunset(-60).resolve(&sun), "21:06");
+    // Offsets past midnight wrap around
+    assert_near(ScheduleTime::Sunset(240).resolve(&sun), "02:06");
+    assert_near(ScheduleTime::Sunrise(-360).resolve(&sun), "23:18");
+    assert_eq!(ScheduleTime::At(time("09:00")).resolve(&sun), Some(time("09:00")));
+    assert_eq!(ScheduleTime::Sunrise(0).resolve(&midnight_sun), None);
+}
+
+#[test]
+fn test_step_curve() {
+    let schedule = schedule(Interpolation::Step);
+    let target = |hour_minute| schedule.target_at(at(hour_minute));
+    assert_eq!(
+        target("07:30"),
+        ScheduleTarget {
+            brightness: Some(40),
+            color_temperature: Some(5000),
+        }
+    );
+    // Points only change the settings they set
+    assert_eq!(target("12:00").brightness, Some(80));
+    assert_eq!(target("12:00").color_temperature, Some(5000));
+    // The evening point holds past midnight
+    assert_eq!(target("23:00").brightness, Some(20));
+    assert_eq!(target("03:00").brightness, Some(20));
+
+    assert_eq!(schedule.next_change(at("12:00")), Some(at("19:00")));
+    assert_eq!(
+        schedule.next_change(at("22:00")),
+        Some(LocalTime::new(2024, 6, 22, time("07:00"), CEST))
+    );
+    assert_eq!(Schedule::default().next_change(at("12:00")), None);
+    assert_eq!(Schedule::default().target_at(at("12:00")), ScheduleTarget::default());
+}
+
+#[test]
+fn test_linear_curve() {
+    let schedule = schedule(Interpolation::Linear);
+    let target = |hour_minute| schedule.target_at(at(hour_minute));
+    assert_eq!(target("07:00").brightness, Some(40));
+    assert_eq!(target("07:30").brightness, Some(60));
+    assert_eq!(target("12:00").brightness, Some(80));
+    assert_eq!(target("20:30").brightness, Some(50));
+    // Color temperature moves from 5000 K at 07:00 to 6500 K at 19:00
+    assert_eq!(target("13:00").color_temperature, Some(5750));
+    // Across midnight, from 20 at 22:00 to 40 at 07:00
+    assert_eq!(target("02:30").brightness, Some(30));
+
+    // Color temperature is still moving at noon
+    assert_eq!(schedule.next_change(at("12:00")), Some(at("12:01")));
+    // Settings held between points only change at the next point
+    let brightness_only = Schedule {
+        points: vec![
+            point("08:00", Some(80), None),
+            point("19:00", Some(80), None),
+            point("22:00", Some(20), None),
+        ],
+        ..Schedule::default()
+    };
+    assert_eq!(brightness_only.next_change(at("12:00")), Some(at("19:00")));
+    assert_eq!(brightness_only.next_change(at("21:00")), Some(at("21:01")));
+    // Gradual changes are updated every minute
+    assert_eq!(schedule.next_change(at("07:30")), Some(at("07:31")));
+    let nearly = at("07:59").after(Duration::from_secs(30));
+    assert_eq!(schedule.next_change(nearly), Some(at("08:00")));
+}
+
+#[test]
+fn test_sun_relative_points() {
+    let schedule = Schedule {
+        location: Some(AMSTERDAM),
+        interpolation: Interpolation::Step,
+        points: vec![point("sunrise", Some(70), None), point("sunset-60", Some(30), None)],
+        ..Schedule::default()
+    };
+    assert!(schedule.validate().is_ok());
+    assert_eq!(schedule.target_at(at("05:00")).brightness, Some(30));
+    assert_eq!(schedule.target_at(at("06:00")).brightness, Some(70));
+    assert_eq!(schedule.target_at(at("21:30")).brightness, Some(30));
+    let next = schedule.next_change(at("12:00")).unwrap();
+    assert_eq!(next.day, at("12:00").day);
+    assert_near(Some(next.time), "21:06");
+
+    // A point after midnight takes effect on the following day
+    let late = Schedule {
+        points: vec![point("sunrise", Some(70), None), point("sunset+180", Some(30), None)],
+        ..schedule.clone()
+    };
+    assert_eq!(late.target_at(at("23:30")).brightness, Some(70));
+    assert_eq!(late.target_at(at("01:30")).brightness, Some(30));
+    assert_eq!(late.target_at(at("04:00")).brightness, Some(30));
+    let next = late.next_change(at("23:30")).unwrap();
+    assert_eq!(next.day, at("12:00").day + 1);
+    assert_near(Some(next.time), "01:06");
+
+    let without_location = Schedule {
+        location: None,
+        ..schedule
+    };
+    assert!(without_location.validate().is_err());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_from_toml() {
+    let schedule = Schedule::from_toml(
+        r#"
+        interpolation = "step"
+        monitors = ["name=DELL U2720Q"]
+
+        [[point]]
+        at = "22:00"
+        brightness = 20
+        color_temperature = 3400
+
+        [[point]]
+        at = "07:00"
+        brightness = 40
+        color_temperature = 5000
+
+        [[point]]
+        at = "08:00"
+        brightness = 80
+
+        [[point]]
+        at = "19:00"
+        brightness = 80
+        color_temperature = 6500
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        schedule,
+        Schedule {
+            monitors: vec!["name=DELL U2720Q".into()],
+            ..self::schedule(Interpolation::Step)
+        }
+    );
+    assert!(Schedule::from_toml("[[point]]\nat = \"sunset\"\nbrightness = 10").is_err());
+    assert!(Schedule::from_toml("[[point]]\nat = \"25:00\"\nbrightness = 10").is_err());
+}
@@ -0,0 +1,170 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::{dell, snapshot, PresetResetBackend};
+use ddc_macos::{
+    restore_snapshot, DisplayDescription, Error, MonitorIdentity, MonitorSnapshot, SettingChange, SimulatedBackend,
+    SimulatedDisplay,
+};
+
+#[test]
+fn test_diff_order_and_filtering() {
+    let saved = snapshot(&[
+        (0x10, 50),
+        (0x12, 75),
+        (0x14, 0x05),
+        (0x60, 0x0f),
+        (0xd6, 0x01),
+        (0xac, 30),
+        (0x8d, 0x02),
+    ]);
+    let current = snapshot(&[
+        (0x10, 30),
+        (0x12, 75),
+        (0x14, 0x0b),
+        (0x60, 0x11),
+        (0xd6, 0x04),
+        (0xac, 60),
+    ]);
+    let changes = saved.diff(&current);
+    assert_eq!(
+        changes,
+        vec![
+            SettingChange {
+                code: 0x14,
+                from: Some(0x0b),
+                to: 0x05
+            },
+            SettingChange {
+                code: 0x8d,
+                from: None,
+                to: 0x02
+            },
+            SettingChange {
+                code: 0x10,
+                from: Some(30),
+                to: 50
+            },
+        ]
+    );
+    assert_eq!(changes[2].to_string(), "Brightness: 30 -> 50");
+}
+
+#[test]
+fn test_diff_identical() {
+    let saved = snapshot(&[(0x10, 50), (0x12, 75)]);
+    assert!(saved.diff(&saved).is_empty());
+}
+
+fn preset_backend() -> PresetResetBackend {
+    let display = SimulatedDisplay::new(DisplayDescription {
+        identity: dell(67890, Some("DELL U2415")),
+        description: "DELL U2415".into(),
+        serial_number: None,
+        connection: None,
+    })
+    .with_value(0x10, 50, 100)
+    .with_value(0x12, 75, 100)
+    .with_value(0x14, 0x0b, 0x0b);
+    PresetResetBackend {
+        inner: SimulatedBackend::new(vec![display]),
+        brightness: 80,
+    }
+}
+
+#[test]
+fn test_restore_after_preset_reset() {
+    // Brightness already matches, but switching the preset resets it, so it has to be written too
+    let saved = snapshot(&[(0x10, 50), (0x12, 75), (0x14, 0x05)]);
+    let mut backend = preset_backend();
+    let changes = restore_snapshot(&mut backend, 0, &saved, false).unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            SettingChange {
+                code: 0x14,
+                from: Some(0x0b),
+                to: 0x05
+            },
+            SettingChange {
+                code: 0x10,
+                from: Some(80),
+                to: 50
+            },
+        ]
+    );
+    assert_eq!(backend.inner.writes(), vec![(0, 0x14, 0x05), (0, 0x10, 50)]);
+    assert_eq!(backend.inner.value(0, 0x10), Some(50));
+
+    // Nothing left to do once restored
+    assert!(restore_snapshot(&mut backend, 0, &saved, false).unwrap().is_empty());
+}
+
+#[test]
+fn test_restore_dry_run_and_mismatch() {
+    let saved = snapshot(&[(0x10, 40), (0x14, 0x05)]);
+    let mut backend = preset_backend();
+    let changes = restore_snapshot(&mut backend, 0, &saved, true).unwrap();
+    assert_eq!(
+        changes.iter().map(|change| change.code).collect::<Vec<_>>(),
+        vec![0x14, 0x10]
+    );
+    assert!(backend.inner.writes().is_empty());
+
+    let mut other = saved.clone();
+    other.identity.model += 1;
+    assert!(matches!(
+        restore_snapshot(&mut backend, 0, &other, false),
+        Err(Error::SnapshotMismatch { .. })
+    ));
+    assert!(matches!(
+        restore_snapshot(&mut backend, 1, &saved, false),
+        Err(Error::MonitorNotFound(_))
+    ));
+}
+
+#[test]
+fn test_text_roundtrip() {
+    let saved = snapshot(&[(0x10, 50), (0x14, 0x05)]);
+    let text = saved.to_string();
+    assert_eq!(text, "monitor 10ac:a0c4:12345 DELL U2415\n0x10 50 100\n0x14 5 100\n");
+    assert_eq!(text.parse::<MonitorSnapshot>().unwrap(), saved);
+    assert_eq!(
+        "# saved\nmonitor 10ac:a0c4:12345\n\n10 50 100\n"
+            .parse::<MonitorSnapshot>()
+            .unwrap()
+            .identity
+            .name,
+        None
+    );
+    assert!(matches!(
+        "0x10 50 100\n".parse::<MonitorSnapshot>(),
+        Err(Error::InvalidSnapshot(_))
+    ));
+    assert!(matches!(
+        "monitor 10ac:a0c4:12345\n0x10 fifty 100\n".parse::<MonitorSnapshot>(),
+        Err(Error::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn test_identity_roundtrip() {
+    let identity = snapshot(&[]).identity;
+    let parsed: MonitorIdentity = identity.to_string().parse().unwrap();
+    assert!(parsed.same_model(&identity));
+    assert_eq!(parsed.serial, identity.serial);
+    assert!(matches!(
+        "10ac:a0c4".parse::<MonitorIdentity>(),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_roundtrip() {
+    let saved = snapshot(&[(0x10, 50), (0x14, 0x05)]);
+    let json = serde_json::to_string(&saved).unwrap();
+    assert!(json.contains("\"identity\""));
+    assert_eq!(serde_json::from_str::<MonitorSnapshot>(&json).unwrap(), saved);
+}
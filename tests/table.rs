@@ -0,0 +1,36 @@
+extern crate ddc_macos;
+
+use ddc::commands::CommandResult;
+use ddc_macos::{table_fragments, TableFragment, TABLE_FRAGMENT_SIZE};
+
+#[test]
+fn test_decode_fragment() {
+    let fragment = TableFragment::decode(&[0xe4, 0x00, 0x20, 0x01, 0x02, 0x03]).unwrap();
+    assert_eq!(fragment.offset, 0x20);
+    assert_eq!(fragment.data, vec![0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_decode_end_of_table() {
+    let fragment = TableFragment::decode(&[0xe4, 0x00, 0x40]).unwrap();
+    assert_eq!(fragment.offset, 0x40);
+    assert!(fragment.data.is_empty());
+}
+
+#[test]
+fn test_decode_invalid_fragment() {
+    assert!(TableFragment::decode(&[0xe4, 0x00]).is_err());
+    assert!(TableFragment::decode(&[0xe3, 0x00, 0x00, 0x01]).is_err());
+    assert!(TableFragment::decode(&[0xe4; 3 + TABLE_FRAGMENT_SIZE + 1]).is_err());
+}
+
+#[test]
+fn test_write_fragments() {
+    let data: Vec<u8> = (0..70).collect();
+    let fragments: Vec<_> = table_fragments(0x10, &data).collect();
+    assert_eq!(fragments.len(), 3);
+    assert_eq!(fragments[0], (0x10, &data[0..32]));
+    assert_eq!(fragments[1], (0x30, &data[32..64]));
+    assert_eq!(fragments[2], (0x50, &data[64..70]));
+    assert_eq!(table_fragments(0, &[]).count(), 0);
+}
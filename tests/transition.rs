@@ -0,0 +1,98 @@
+extern crate ddc_macos;
+
+use ddc_macos::{plan_transition, Easing, TransitionControl, TransitionOptions, TransitionStep};
+use std::time::Duration;
+
+fn linear(duration_ms: u64, step_interval_ms: u64) -> TransitionOptions {
+    TransitionOptions {
+        duration: Duration::from_millis(duration_ms),
+        easing: Easing::Linear,
+        step_interval: Duration::from_millis(step_interval_ms),
+    }
+}
+
+#[test]
+fn test_plan_linear() {
+    let steps = plan_transition(0, 100, &linear(400, 100));
+    assert_eq!(
+        steps,
+        [(100, 25), (200, 50), (300, 75), (400, 100)]
+            .iter()
+            .map(|(at, value)| TransitionStep {
+                at: Duration::from_millis(*at),
+                value: *value
+            })
+            .collect::<Vec<_>>()
+    );
+    let down: Vec<u16> = plan_transition(80, 20, &linear(300, 100))
+        .iter()
+        .map(|step| step.value)
+        .collect();
+    assert_eq!(down, [60, 40, 20]);
+}
+
+#[test]
+fn test_plan_edge_cases() {
+    assert!(plan_transition(50, 50, &linear(1000, 100)).is_empty());
+
+    let instant = plan_transition(10, 90, &linear(0, 100));
+    assert_eq!(
+        instant,
+        [TransitionStep {
+            at: Duration::ZERO,
+            value: 90
+        }]
+    );
+
+    // Steps never come faster than the DDC/CI command delay
+    let steps = plan_transition(0, 100, &linear(500, 10));
+    assert_eq!(steps.len(), 10);
+    assert!(steps
+        .windows(2)
+        .all(|pair| pair[1].at - pair[0].at >= Duration::from_millis(50)));
+
+    // Small ranges only write values that change
+    let steps: Vec<u16> = plan_transition(10, 13, &linear(1000, 100))
+        .iter()
+        .map(|step| step.value)
+        .collect();
+    assert_eq!(steps, [11, 12, 13]);
+}
+
+#[test]
+fn test_easing() {
+    for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+        assert_eq!(easing.apply(2.0), 1.0);
+        let samples: Vec<f64> = (0..=20).map(|i| easing.apply(f64::from(i) / 20.0)).collect();
+        assert!(samples.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+    assert!(Easing::EaseIn.apply(0.5) < 0.5);
+    assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+
+    let options = TransitionOptions {
+        easing: Easing::EaseIn,
+        ..linear(400, 100)
+    };
+    let values: Vec<u16> = plan_transition(0, 100, &options)
+        .iter()
+        .map(|step| step.value)
+        .collect();
+    assert_eq!(values, [6, 25, 56, 100]);
+}
+
+#[test]
+fn test_control() {
+    let control = TransitionControl::new();
+    let remote = control.clone();
+    assert!(control.take_target().is_none());
+    remote.retarget(30, Duration::from_millis(100));
+    remote.retarget(40, Duration::from_millis(200));
+    assert_eq!(control.take_target(), Some((40, Duration::from_millis(200))));
+    assert!(control.take_target().is_none());
+    assert!(!control.is_cancelled());
+    remote.cancel();
+    assert!(control.is_cancelled());
+}
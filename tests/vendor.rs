@@ -0,0 +1,81 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::{model, vcp_value};
+use ddc_macos::{Access, Error, VcpFeature, VendorExtension, VendorRegistry, DELL, LG, VENDOR_CODES};
+
+#[test]
+fn test_builtin_features_in_vendor_range() {
+    for extension in VendorRegistry::builtin().extensions() {
+        assert!(extension
+            .features
+            .iter()
+            .all(|feature| VENDOR_CODES.contains(&feature.code)));
+    }
+}
+
+#[test]
+fn test_lookup_by_vendor() {
+    let registry = VendorRegistry::builtin();
+    let dell = model(DELL.vendor, 0xa0c4);
+    let lg = model(LG.vendor, 0x5b11);
+    assert_eq!(registry.feature(&dell, 0xe9).unwrap().name, "Dell PxP Mode");
+    assert!(registry.feature(&lg, 0xe9).is_none());
+    assert_eq!(registry.feature(&lg, 0xf4).unwrap().value_by_name("usb-c"), Some(0xd1));
+    // Standard codes come from the catalog, whatever the vendor
+    assert_eq!(registry.feature(&lg, 0x10).unwrap().name, "Brightness");
+    assert_eq!(
+        registry.describe(&lg, 0xf4, &vcp_value(0xd0, 0xff)),
+        "LG Input Source = DisplayPort-1"
+    );
+    assert_eq!(
+        registry.describe(&model(0x1234, 1), 0xf4, &vcp_value(0xd0, 0xff)),
+        "VCP 0xf4 = 0x00d0"
+    );
+    assert_eq!(registry.feature_by_name(&dell, "dell pxp mode").unwrap().code, 0xe9);
+    assert_eq!(registry.feature_by_name(&dell, "Contrast").unwrap().code, 0x12);
+    assert!(registry.feature_by_name(&lg, "Dell PxP Mode").is_none());
+}
+
+static CUSTOM: VendorExtension = VendorExtension {
+    name: "Custom",
+    vendor: 0x10ac,
+    models: &[0xa0c4],
+    features: &[VcpFeature::continuous(0xe9, "Custom Level", Access::ReadWrite)],
+};
+
+#[test]
+fn test_register_model_specific() {
+    let mut registry = VendorRegistry::builtin();
+    registry.register(CUSTOM);
+    assert_eq!(
+        registry.feature(&model(0x10ac, 0xa0c4), 0xe9).unwrap().name,
+        "Custom Level"
+    );
+    assert_eq!(
+        registry.feature(&model(0x10ac, 0xa0c5), 0xe9).unwrap().name,
+        "Dell PxP Mode"
+    );
+    assert_eq!(
+        registry.feature(&model(0x10ac, 0xa0c4), 0xe7).unwrap().name,
+        "Dell USB Upstream (KVM)"
+    );
+}
+
+#[test]
+fn test_for_monitor_and_parse() {
+    let registry = VendorRegistry::builtin();
+    let dell = model(DELL.vendor, 0xa0c4);
+    let monitor = registry.for_monitor(&dell);
+    assert_eq!(monitor.extensions(), &[DELL]);
+    assert_eq!(monitor.feature(&dell, 0xe9).unwrap().name, "Dell PxP Mode");
+
+    assert_eq!(registry.parse_feature_code(&dell, "dell-pxp-mode").unwrap(), 0xe9);
+    assert_eq!(registry.parse_feature_code(&dell, "brightness").unwrap(), 0x10);
+    assert_eq!(registry.parse_feature_code(&dell, "0xe9").unwrap(), 0xe9);
+    assert!(matches!(
+        registry.parse_feature_code(&model(LG.vendor, 0x5b11), "dell-pxp-mode"),
+        Err(Error::InvalidArgument(_))
+    ));
+}
@@ -0,0 +1,118 @@
+extern crate ddc_macos;
+
+mod common;
+
+use common::MockDdc;
+use ddc_macos::{set_and_verify, Error, VerifiedWrite, VerifyOptions};
+use std::time::Duration;
+
+/// Options without a settle time, to keep the tests fast
+fn options(tolerance: u16, attempts: usize) -> VerifyOptions {
+    VerifyOptions {
+        settle_time: Duration::ZERO,
+        tolerance,
+        attempts,
+    }
+}
+
+#[test]
+fn test_tolerance() {
+    let exact = VerifyOptions::default();
+    assert!(exact.accepts(50, 50));
+    assert!(!exact.accepts(50, 51));
+
+    let steps_of_five = VerifyOptions {
+        tolerance: 2,
+        ..Default::default()
+    };
+    assert!(steps_of_five.accepts(42, 40));
+    assert!(steps_of_five.accepts(43, 45));
+    assert!(!steps_of_five.accepts(42, 45));
+    assert!(steps_of_five.accepts(0, 0));
+    assert!(steps_of_five.accepts(u16::MAX, u16::MAX - 2));
+}
+
+#[test]
+fn test_verified_write() {
+    let write = VerifiedWrite {
+        code: 0x10,
+        requested: 42,
+        actual: 40,
+        attempts: 1,
+    };
+    assert!(!write.is_exact());
+    assert!(VerifiedWrite { actual: 42, ..write }.is_exact());
+}
+
+#[test]
+fn test_set_and_verify() {
+    let mut ddc = MockDdc::default().with_value(0x10, 50, 100);
+    let write = set_and_verify(&mut ddc, 0x10, 70, &options(0, 3)).unwrap();
+    assert_eq!(
+        write,
+        VerifiedWrite {
+            code: 0x10,
+            requested: 70,
+            actual: 70,
+            attempts: 1
+        }
+    );
+    assert_eq!(ddc.writes, vec![(0x10, 70)]);
+}
+
+#[test]
+fn test_set_and_verify_retries_ignored_writes() {
+    let mut ddc = MockDdc {
+        ignored_writes: 2,
+        ..MockDdc::default()
+    }
+    .with_value(0x10, 50, 100);
+    let write = set_and_verify(&mut ddc, 0x10, 70, &options(0, 3)).unwrap();
+    assert_eq!(write.attempts, 3);
+    assert_eq!(ddc.writes, vec![(0x10, 70); 3]);
+    assert_eq!(ddc.value(0x10), Some(70));
+}
+
+#[test]
+fn test_set_and_verify_mismatch() {
+    let mut ddc = MockDdc {
+        ignored_writes: 3,
+        ..MockDdc::default()
+    }
+    .with_value(0x10, 50, 100);
+    assert!(matches!(
+        set_and_verify(&mut ddc, 0x10, 70, &options(0, 2)),
+        Err(Error::VerificationFailed {
+            code: 0x10,
+            requested: 70,
+            actual: 50
+        })
+    ));
+    assert_eq!(ddc.writes.len(), 2);
+
+    // Within the tolerance, the value the monitor kept is accepted
+    let write = set_and_verify(&mut ddc, 0x10, 52, &options(2, 1)).unwrap();
+    assert_eq!(write.actual, 50);
+    assert!(!write.is_exact());
+}
+
+#[test]
+fn test_set_and_verify_errors() {
+    let mut ddc = MockDdc {
+        failures: 1,
+        ..MockDdc::default()
+    }
+    .with_value(0x10, 50, 100);
+    assert!(matches!(
+        set_and_verify(&mut ddc, 0x10, 70, &options(0, 3)),
+        Err(Error::Ddc(_))
+    ));
+    assert!(ddc.writes.is_empty());
+
+    // Unsupported features fail on the read back
+    let mut ddc = MockDdc::default();
+    assert!(matches!(
+        set_and_verify(&mut ddc, 0x12, 70, &options(0, 3)),
+        Err(Error::UnsupportedFeature { code: 0x12 })
+    ));
+}